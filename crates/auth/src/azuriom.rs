@@ -10,7 +10,7 @@
 //! - Logout
 
 use crate::{Authenticator, AuthError, AuthProvider, AuthResult, UserProfile, UserRole};
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use lighty_core::hosts::http_client;
 use serde::Deserialize;
 
 #[cfg(feature = "events")]
@@ -130,7 +130,7 @@ impl Authenticator for AzuriomAuth {
         }
 
         // Send request
-        let response = CLIENT
+        let response = http_client()
             .post(&url)
             .json(&body)
             .send()
@@ -220,7 +220,7 @@ impl Authenticator for AzuriomAuth {
         let url = format!("{}/api/auth/verify", self.base_url);
         lighty_core::trace_debug!(url = %url, "Verifying token");
 
-        let response = CLIENT
+        let response = http_client()
             .post(&url)
             .json(&serde_json::json!({
                 "access_token": token
@@ -263,7 +263,7 @@ impl Authenticator for AzuriomAuth {
         let url = format!("{}/api/auth/logout", self.base_url);
         lighty_core::trace_debug!(url = %url, "Logging out");
 
-        let response = CLIENT
+        let response = http_client()
             .post(&url)
             .json(&serde_json::json!({
                 "access_token": token
@@ -21,7 +21,7 @@
 
 use crate::{Authenticator, AuthError, AuthProvider, AuthResult, UserProfile};
 use base64::Engine;
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use lighty_core::hosts::http_client;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use std::sync::{
@@ -140,7 +140,7 @@ impl MicrosoftAuth {
     async fn request_device_code(&self) -> AuthResult<DeviceCodeResponse> {
         lighty_core::trace_debug!("Requesting device code");
 
-        let response = CLIENT
+        let response = http_client()
             .post(&format!("{}/devicecode", MS_AUTH_URL))
             .form(&[
                 ("client_id", self.client_id.as_str()),
@@ -175,7 +175,7 @@ impl MicrosoftAuth {
 
             sleep(self.poll_interval).await;
 
-            let response = CLIENT
+            let response = http_client()
                 .post(&format!("{}/token", MS_AUTH_URL))
                 .form(&[
                     ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
@@ -218,7 +218,7 @@ impl MicrosoftAuth {
     async fn get_xbox_token(&self, ms_token: &str) -> AuthResult<XboxTokenResponse> {
         lighty_core::trace_debug!("Requesting Xbox Live token");
 
-        let response = CLIENT
+        let response = http_client()
             .post(XBOX_AUTH_URL)
             .json(&serde_json::json!({
                 "Properties": {
@@ -248,7 +248,7 @@ impl MicrosoftAuth {
     async fn get_xsts_token(&self, xbox_token: &str) -> AuthResult<XboxTokenResponse> {
         lighty_core::trace_debug!("Requesting XSTS token");
 
-        let response = CLIENT
+        let response = http_client()
             .post(XSTS_AUTH_URL)
             .json(&serde_json::json!({
                 "Properties": {
@@ -289,7 +289,7 @@ impl MicrosoftAuth {
     async fn get_minecraft_token(&self, xsts_token: &str, uhs: &str) -> AuthResult<MinecraftTokenResponse> {
         lighty_core::trace_debug!("Requesting Minecraft token");
 
-        let response = CLIENT
+        let response = http_client()
             .post(MC_AUTH_URL)
             .json(&serde_json::json!({
                 "identityToken": format!("XBL3.0 x={};{}", uhs, xsts_token)
@@ -313,7 +313,7 @@ impl MicrosoftAuth {
     async fn get_minecraft_profile(&self, mc_token: &str) -> AuthResult<MinecraftProfile> {
         lighty_core::trace_debug!("Fetching Minecraft profile");
 
-        let response = CLIENT
+        let response = http_client()
             .get(MC_PROFILE_URL)
             .header("Authorization", format!("Bearer {}", mc_token))
             .send()
@@ -424,7 +424,7 @@ impl MicrosoftAuth {
     ) -> AuthResult<MicrosoftTokenResponse> {
         lighty_core::trace_debug!("Exchanging authorization code for Microsoft token");
 
-        let response = CLIENT
+        let response = http_client()
             .post(&format!("{}/token", MS_AUTH_URL))
             .form(&[
                 ("grant_type", "authorization_code"),
@@ -579,7 +579,7 @@ impl MicrosoftAuth {
     async fn refresh_microsoft_token(&self, refresh_token: &str) -> AuthResult<MicrosoftTokenResponse> {
         lighty_core::trace_debug!("Refreshing Microsoft token via refresh_token grant");
 
-        let response = CLIENT
+        let response = http_client()
             .post(&format!("{}/token", MS_AUTH_URL))
             .form(&[
                 ("grant_type", "refresh_token"),
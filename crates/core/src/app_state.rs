@@ -12,16 +12,26 @@
 //! No reverse-DNS qualifier, no organization+application split,
 //! no leading-dot side-effects. The user picks the on-disk name,
 //! the OS decides the parent directory.
+//!
+//! Two alternatives bypass the OS locations entirely:
+//! [`AppState::init_portable`] keeps `data/`, `config/` and `cache/`
+//! next to the executable (USB-stick builds), and
+//! [`AppState::init_with_paths`] takes a fully explicit
+//! [`LauncherPaths`] (custom layouts, temp directories in tests).
 
 use std::path::{Path, PathBuf};
 
 use once_cell::sync::OnceCell;
+use path_absolutize::Absolutize;
 
 use crate::errors::{AppStateError, AppStateResult};
 
 /// File under `config_dir` that persists the per-install launcher client_id.
 const CLIENT_ID_FILE: &str = "client_id";
 
+/// Subdirectory of `config_dir` holding the managed Java runtimes.
+const JRE_DIR: &str = "jre";
+
 /// Resolved per-launcher paths.
 #[derive(Debug, Clone)]
 pub struct LauncherPaths {
@@ -31,10 +41,90 @@ pub struct LauncherPaths {
     /// Persistent application data (instances, libraries, assets,
     /// natives, mods, …).
     pub data_dir: PathBuf,
-    /// User configuration (launcher settings, the cached JRE).
+    /// User configuration (launcher settings, client id).
     pub config_dir: PathBuf,
     /// Disposable cache (downloads, intermediate files).
     pub cache_dir: PathBuf,
+    /// Managed Java runtimes. Defaults to `<config_dir>/jre`.
+    pub java_dir: PathBuf,
+}
+
+impl LauncherPaths {
+    /// Explicit paths; `java_dir` defaults to `<config_dir>/jre`.
+    pub fn new(
+        name: impl Into<String>,
+        data_dir: impl Into<PathBuf>,
+        config_dir: impl Into<PathBuf>,
+        cache_dir: impl Into<PathBuf>,
+    ) -> Self {
+        let config_dir = config_dir.into();
+        Self {
+            name: name.into(),
+            data_dir: data_dir.into(),
+            java_dir: config_dir.join(JRE_DIR),
+            config_dir,
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// OS-standard locations with `name` joined on (what [`AppState::init`] uses).
+    pub fn platform(name: impl Into<String>) -> AppStateResult<Self> {
+        let name = name.into();
+        let data_dir = dirs::data_dir()
+            .ok_or(AppStateError::MissingPlatformDir("data"))?
+            .join(&name);
+        let config_dir = dirs::config_dir()
+            .ok_or(AppStateError::MissingPlatformDir("config"))?
+            .join(&name);
+        let cache_dir = dirs::cache_dir()
+            .ok_or(AppStateError::MissingPlatformDir("cache"))?
+            .join(&name);
+        Ok(Self::new(name, data_dir, config_dir, cache_dir))
+    }
+
+    /// Everything under a single `root`: `root/data`, `root/config`,
+    /// `root/cache` (and `root/config/jre`).
+    pub fn under_root(name: impl Into<String>, root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref();
+        Self::new(name, root.join("data"), root.join("config"), root.join("cache"))
+    }
+
+    /// [`Self::under_root`] rooted at the directory holding the running
+    /// executable (what [`AppState::init_portable`] uses).
+    pub fn portable(name: impl Into<String>) -> AppStateResult<Self> {
+        let exe = std::env::current_exe().map_err(AppStateError::ExecutableDir)?;
+        let root = exe.parent().ok_or_else(|| {
+            AppStateError::ExecutableDir(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} has no parent directory", exe.display()),
+            ))
+        })?;
+        Ok(Self::under_root(name, root))
+    }
+
+    /// Overrides the Java runtime directory.
+    pub fn with_java_dir(mut self, java_dir: impl Into<PathBuf>) -> Self {
+        self.java_dir = java_dir.into();
+        self
+    }
+
+    /// Resolves relative directories against the current working
+    /// directory. The game runs with its own working directory, so
+    /// every path handed to it must be absolute.
+    fn absolutized(self) -> AppStateResult<Self> {
+        let abs = |path: PathBuf| -> AppStateResult<PathBuf> {
+            path.absolutize()
+                .map(|p| p.to_path_buf())
+                .map_err(|source| AppStateError::InvalidPath { path: path.clone(), source })
+        };
+        Ok(Self {
+            name: self.name,
+            data_dir: abs(self.data_dir)?,
+            config_dir: abs(self.config_dir)?,
+            cache_dir: abs(self.cache_dir)?,
+            java_dir: abs(self.java_dir)?,
+        })
+    }
 }
 
 static PATHS: OnceCell<LauncherPaths> = OnceCell::new();
@@ -52,31 +142,51 @@ impl AppState {
     /// standard data/config/cache bases. Returns
     /// [`AppStateError::AlreadyInitialized`] on a second call.
     pub fn init(name: impl Into<String>) -> AppStateResult<()> {
-        let name = name.into();
-        let data_dir = dirs::data_dir()
-            .ok_or(AppStateError::MissingPlatformDir("data"))?
-            .join(&name);
-        let config_dir = dirs::config_dir()
-            .ok_or(AppStateError::MissingPlatformDir("config"))?
-            .join(&name);
-        let cache_dir = dirs::cache_dir()
-            .ok_or(AppStateError::MissingPlatformDir("cache"))?
-            .join(&name);
+        Self::init_with_paths(LauncherPaths::platform(name)?)
+    }
+
+    /// Initialises the launcher in portable mode: `data/`, `config/`
+    /// and `cache/` live next to the running executable, so the whole
+    /// launcher can be moved as one folder.
+    pub fn init_portable(name: impl Into<String>) -> AppStateResult<()> {
+        Self::init_with_paths(LauncherPaths::portable(name)?)
+    }
+
+    /// Initialises the global launcher paths from an explicit
+    /// [`LauncherPaths`]. Relative paths are resolved against the
+    /// current working directory. Same once-only rule as [`Self::init`].
+    ///
+    /// ```no_run
+    /// use lighty_core::{AppState, app_state::LauncherPaths};
+    ///
+    /// AppState::init_with_paths(LauncherPaths::under_root("LightyLauncher", "/tmp/lighty"))?;
+    /// assert!(AppState::java_dir().ends_with("config/jre"));
+    /// # Ok::<(), lighty_core::AppStateError>(())
+    /// ```
+    pub fn init_with_paths(paths: LauncherPaths) -> AppStateResult<()> {
         PATHS
-            .set(LauncherPaths { name, data_dir, config_dir, cache_dir })
+            .set(paths.absolutized()?)
             .map_err(|_| AppStateError::AlreadyInitialized)
     }
 
     /// Returns the resolved launcher paths.
     ///
-    /// Panics with a clear message if [`Self::init`] hasn't been
-    /// called — that's a programmer error, not a runtime condition.
+    /// Panics with a clear message if [`Self::init`] (or one of its
+    /// variants) hasn't been called — that's a programmer error, not
+    /// a runtime condition.
     pub fn paths() -> &'static LauncherPaths {
         PATHS.get().expect(
             "AppState::init(\"<launcher-name>\") must be called once at startup",
         )
     }
 
+    /// Like [`Self::paths`], but returns [`AppStateError::NotInitialized`]
+    /// instead of panicking. For optional features (on-disk caches) that
+    /// simply switch off when the launcher paths are unknown.
+    pub fn try_paths() -> AppStateResult<&'static LauncherPaths> {
+        PATHS.get().ok_or(AppStateError::NotInitialized)
+    }
+
     /// Launcher name as supplied to [`Self::init`].
     pub fn name() -> &'static str {
         &Self::paths().name
@@ -87,11 +197,16 @@ impl AppState {
         &Self::paths().data_dir
     }
 
-    /// User configuration directory (the bundled JRE lives here).
+    /// User configuration directory.
     pub fn config_dir() -> &'static Path {
         &Self::paths().config_dir
     }
 
+    /// Directory holding the managed Java runtimes.
+    pub fn java_dir() -> &'static Path {
+        &Self::paths().java_dir
+    }
+
     /// Disposable cache directory.
     pub fn cache_dir() -> &'static Path {
         &Self::paths().cache_dir
@@ -0,0 +1,147 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Cancellation and pause/resume for long-running operations.
+//!
+//! A [`CancellationToken`] is attached to a unit of work with
+//! [`with_cancellation`]; everything that runs inside that scope — the
+//! installer, every download, the JRE installer and the Forge/NeoForge
+//! processors — checks it at safe points through [`checkpoint`]:
+//!
+//! - once [cancelled](CancellationToken::cancel), the next checkpoint fails
+//!   with [`Cancelled`] and the error unwinds the whole pipeline;
+//! - while [paused](CancellationToken::pause), checkpoints wait until the
+//!   token is resumed (or cancelled).
+//!
+//! Checkpoints sit between files and between chunks of a transfer.
+//! Downloads stream into `<dest>.part` and are only renamed once verified,
+//! so stopping never leaves a half-written file at its final path; the
+//! partial transfer is resumed next time.
+//!
+//! ```rust,ignore
+//! use lighty_core::cancel::CancellationToken;
+//!
+//! let token = CancellationToken::new();
+//! let handle = token.clone();
+//!
+//! // "Cancel" button
+//! tokio::spawn(async move {
+//!     tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+//!     handle.cancel();
+//! });
+//!
+//! version.launch(&profile, JavaDistribution::Temurin)
+//!     .with_cancellation(token)
+//!     .run()
+//!     .await?;
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+pub use crate::errors::Cancelled;
+
+tokio::task_local! {
+    static CURRENT: CancellationToken;
+}
+
+/// Shared cancel / pause switch; clones control the same operation.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    cancel: tokio_util::sync::CancellationToken,
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self {
+            cancel: tokio_util::sync::CancellationToken::new(),
+            paused: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Stops the operation at its next checkpoint. Also ends a pause.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        self.cancel.cancelled().await
+    }
+
+    /// Holds the operation at its next checkpoint until [`Self::resume`].
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Waits out a pause, then fails if the token was cancelled.
+    pub async fn checkpoint(&self) -> Result<(), Cancelled> {
+        let mut paused = self.paused.subscribe();
+        tokio::select! {
+            // The sender lives as long as `self`, so this can't fail
+            _ = paused.wait_for(|paused| !paused) => {}
+            _ = self.cancel.cancelled() => {}
+        }
+        if self.is_cancelled() {
+            return Err(Cancelled);
+        }
+        Ok(())
+    }
+
+    /// Runs `fut`, dropping it as soon as the token is cancelled.
+    pub async fn run_until_cancelled<F: Future>(&self, fut: F) -> Result<F::Output, Cancelled> {
+        tokio::select! {
+            output = fut => Ok(output),
+            _ = self.cancel.cancelled() => Err(Cancelled),
+        }
+    }
+}
+
+/// Runs `fut` with `token` governing every [`checkpoint`] inside it.
+pub async fn with_cancellation<F: Future>(token: CancellationToken, fut: F) -> F::Output {
+    CURRENT.scope(token, fut).await
+}
+
+/// Token of the current [`with_cancellation`] scope, if any.
+pub fn current_token() -> Option<CancellationToken> {
+    CURRENT.try_with(|token| token.clone()).ok()
+}
+
+/// [`CancellationToken::checkpoint`] on the current token. Always `Ok`
+/// outside a [`with_cancellation`] scope.
+pub async fn checkpoint() -> Result<(), Cancelled> {
+    match current_token() {
+        Some(token) => token.checkpoint().await,
+        None => Ok(()),
+    }
+}
+
+/// Runs `fut`, dropping it when the current token is cancelled. Plain
+/// `fut.await` outside a [`with_cancellation`] scope.
+pub async fn run_until_cancelled<F: Future>(fut: F) -> Result<F::Output, Cancelled> {
+    match current_token() {
+        Some(token) => token.run_until_cancelled(fut).await,
+        None => Ok(fut.await),
+    }
+}
@@ -17,52 +17,33 @@
  * along with LiquidLauncher. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
 
 use crate::errors::{DownloadResult, DownloadError};
 use crate::{trace_debug};
 use tokio::fs;
-use crate::hosts::{HTTP_CLIENT, RAW_HTTP_CLIENT, build_fallback_urls};
-use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use crate::hash::{calculate_file_hash, ExpectedHash, HashError};
+use crate::hosts::{build_fallback_urls, raw_streaming_http_client, streaming_http_client};
+use crate::mirrors::MirrorPolicy;
+use crate::cancel::checkpoint;
+use crate::offline::ensure_online;
+use crate::scheduler::DownloadScheduler;
+use once_cell::sync::Lazy;
+use reqwest::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use tokio::sync::OwnedMutexGuard;
 
 /// Downloads `url` to `path` without progress reporting.
 ///
-/// Used for small one-shot fetches where streaming and progress callbacks
-/// would be overkill (e.g. mod-loader installer JARs, single manifests).
+/// Used for small one-shot fetches where progress callbacks would be
+/// overkill (e.g. mod-loader installer JARs, single manifests). Streams to
+/// disk through [`download_to_path`] without any integrity expectation.
 pub async fn download_file_untracked(url: &str, path: impl AsRef<Path>) -> DownloadResult<()> {
-    let path = path.as_ref().to_owned();
-    let mut last_error = None;
-
-    for candidate in build_fallback_urls(url) {
-        match download_untracked_once(&HTTP_CLIENT, &candidate, &path).await {
-            Ok(_) => return Ok(()),
-            Err(e) => {
-                let should_retry_raw = is_decode_error(&e);
-                last_error = Some(e);
-
-                if should_retry_raw {
-                    trace_debug!(
-                        url = %candidate,
-                        "Response decode failed; retrying download without automatic decompression"
-                    );
-
-                    match download_untracked_once(&RAW_HTTP_CLIENT, &candidate, &path).await {
-                        Ok(_) => return Ok(()),
-                        Err(raw_err) => {
-                            last_error = Some(raw_err);
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    Err(last_error.unwrap_or_else(|| {
-        DownloadError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "No candidates available for download",
-        ))
-    }))
+    download_to_path(url, path, &DownloadExpectation::default(), |_, _| {}).await
 }
 
 fn is_decode_error(err: &DownloadError) -> bool {
@@ -72,28 +53,6 @@ fn is_decode_error(err: &DownloadError) -> bool {
     }
 }
 
-async fn download_untracked_once(
-    client: &reqwest::Client,
-    url: &str,
-    path: &Path,
-) -> DownloadResult<()> {
-    let response = client
-        .get(url)
-        .header(ACCEPT_ENCODING, "identity")
-        .send()
-        .await?
-        .error_for_status()?;
-
-    let content = response.bytes().await?;
-
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).await?;
-    }
-
-    fs::write(path, content).await?;
-    Ok(())
-}
-
 /// Downloads `url` into a `Vec<u8>`, invoking `on_progress(current, total)`
 /// after each chunk.
 ///
@@ -105,11 +64,16 @@ where
     F: Fn(u64, u64),
 {
     trace_debug!("Downloading file {:?}", url);
+    ensure_online(|| url.to_string())?;
 
     let mut last_error = None;
 
     for candidate in build_fallback_urls(url) {
-        let mut response = match download_streaming_once(&HTTP_CLIENT, candidate.trim()).await {
+        // Paused transfers wait here, without holding a slot
+        checkpoint().await?;
+        let _slot = DownloadScheduler::global().acquire(candidate.trim()).await;
+        let started = Instant::now();
+        let mut response = match download_streaming_once(streaming_http_client(), candidate.trim()).await {
             Ok(response) => response,
             Err(e) => {
                 if is_decode_error(&e) {
@@ -117,19 +81,22 @@ where
                         url = %candidate,
                         "Response decode failed; retrying streaming download without automatic decompression"
                     );
-                    match download_streaming_once(&RAW_HTTP_CLIENT, candidate.trim()).await {
+                    match download_streaming_once(raw_streaming_http_client(), candidate.trim()).await {
                         Ok(response) => response,
                         Err(raw_err) => {
+                            MirrorPolicy::global().record_failure(&candidate);
                             last_error = Some(raw_err);
                             continue;
                         }
                     }
                 } else {
+                    MirrorPolicy::global().record_failure(&candidate);
                     last_error = Some(e);
                     continue;
                 }
             }
         };
+        MirrorPolicy::global().record_success(&candidate, started.elapsed());
 
         trace_debug!("Response received from url");
 
@@ -151,6 +118,8 @@ where
 
         trace_debug!("Reading data from response chunk...");
         while let Some(data) = response.chunk().await? {
+            checkpoint().await?;
+            DownloadScheduler::global().throttle(data.len()).await;
             output.extend_from_slice(&data);
             curr_len += data.len();
             if max_len > 0 {
@@ -166,10 +135,7 @@ where
     }
 
     Err(last_error.unwrap_or_else(|| {
-        DownloadError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "No candidates available for download",
-        ))
+        DownloadError::Io(std::io::Error::other("No candidates available for download"))
     }))
 }
 
@@ -184,3 +150,474 @@ async fn download_streaming_once(
         .await?
         .error_for_status()?)
 }
+
+/// Number of `Range` resumes attempted in a row per candidate URL after
+/// the body stream breaks without delivering anything, before moving on to
+/// the next mirror.
+const MAX_RESUME_ATTEMPTS: u32 = 3;
+
+/// Transfers in progress, by destination.
+static DESTINATION_LOCKS: Lazy<PathLocks> = Lazy::new(PathLocks::default);
+
+/// In-process async locks keyed by path. Entries go away with their last guard.
+#[derive(Default)]
+pub(crate) struct PathLocks {
+    locks: Mutex<HashMap<PathBuf, Weak<tokio::sync::Mutex<()>>>>,
+}
+
+impl PathLocks {
+    /// Waits until no other task holds `key`, then holds it until the
+    /// guard is dropped.
+    pub(crate) async fn lock(&self, key: &Path) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            locks.retain(|_, lock| lock.strong_count() > 0);
+            match locks.get(key).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(tokio::sync::Mutex::new(()));
+                    locks.insert(key.to_path_buf(), Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+}
+
+/// Integrity checks applied by [`download_to_path`] before the file is
+/// moved into place. Every field is optional; an empty expectation only
+/// guarantees the body was received completely.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadExpectation {
+    /// Exact size in bytes.
+    pub size: Option<u64>,
+    /// Expected digest, in whichever algorithm the source publishes.
+    pub hash: Option<ExpectedHash>,
+}
+
+impl DownloadExpectation {
+    pub fn hash(hash: ExpectedHash) -> Self {
+        Self { hash: Some(hash), ..Self::default() }
+    }
+
+    pub fn sha1(hash: impl Into<String>) -> Self {
+        Self::hash(ExpectedHash::sha1(hash))
+    }
+
+    pub fn sha256(hash: impl Into<String>) -> Self {
+        Self::hash(ExpectedHash::sha256(hash))
+    }
+
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Checks the file at `path` against every expectation that is set.
+    async fn verify(&self, path: &Path) -> DownloadResult<()> {
+        if let Some(expected) = self.size {
+            let actual = fs::metadata(path).await?.len();
+            if actual != expected {
+                return Err(DownloadError::SizeMismatch {
+                    path: path.display().to_string(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        if let Some(expected) = &self.hash {
+            let actual = calculate_file_hash(path, expected.algorithm)
+                .await
+                .map_err(|e| match e {
+                    HashError::Io(io) => DownloadError::Io(io),
+                    other => DownloadError::Io(std::io::Error::other(other.to_string())),
+                })?;
+
+            if !expected.matches(&actual) {
+                return Err(DownloadError::HashMismatch {
+                    path: path.display().to_string(),
+                    algorithm: expected.algorithm.name(),
+                    expected: expected.hex.clone(),
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Streams `url` into `dest`, resuming interrupted transfers and verifying
+/// the result before it becomes visible.
+///
+/// The body is written to `<dest>.part` next to the destination. When the
+/// connection drops mid-body, the transfer is resumed with an HTTP `Range`
+/// request from the bytes already on disk (a partial left over by a
+/// previous run is picked up the same way). The server's `ETag` or
+/// `Last-Modified` is kept in `<dest>.part.meta` and sent as `If-Range`,
+/// so a file changed in between is fetched again from the start; a
+/// partial without validator is only resumed when `expected` has a hash.
+/// Once complete, the part file is checked against `expected` and
+/// atomically renamed to `dest`; on mismatch it is deleted and the next
+/// mirror from [`build_fallback_urls`] is tried.
+///
+/// Transfers into the same `dest` are serialized within the process.
+///
+/// `on_progress(current, total)` is invoked after each chunk; `current`
+/// includes resumed bytes and `total` is `0` when unknown.
+///
+/// Each transfer holds a slot of the [download scheduler](crate::scheduler)
+/// and is paced by its bandwidth limit.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use lighty_core::download::{download_to_path, DownloadExpectation};
+///
+/// download_to_path(
+///     "https://piston-data.mojang.com/v1/objects/…/client.jar",
+///     "instances/1.21/1.21.jar",
+///     &DownloadExpectation::sha1("0c2a5d0d…").with_size(27_000_000),
+///     |current, total| println!("{current}/{total}"),
+/// )
+/// .await?;
+/// ```
+pub async fn download_to_path<F>(
+    url: &str,
+    dest: impl AsRef<Path>,
+    expected: &DownloadExpectation,
+    on_progress: F,
+) -> DownloadResult<()>
+where
+    F: Fn(u64, u64),
+{
+    let dest = dest.as_ref();
+    ensure_online(|| dest.display().to_string())?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    let _transfer = DESTINATION_LOCKS.lock(dest).await;
+    let part = part_path(dest);
+    let mut last_error = None;
+
+    for candidate in build_fallback_urls(url) {
+        let candidate = candidate.trim();
+        // Paused transfers wait here, without holding a slot
+        checkpoint().await?;
+        let slot = DownloadScheduler::global().acquire(candidate).await;
+
+        let latency = match fetch_resumable(candidate, &part, expected, &on_progress).await {
+            Ok(latency) => latency,
+            // The part file is kept and resumed next time
+            Err(DownloadError::Cancelled(e)) => return Err(e.into()),
+            Err(e) => {
+                trace_debug!(url = %candidate, error = %e, "Download candidate failed");
+                MirrorPolicy::global().record_failure(candidate);
+                last_error = Some(e);
+                continue;
+            }
+        };
+        // Verification is local work: let the next transfer start
+        drop(slot);
+
+        if let Err(e) = expected.verify(&part).await {
+            trace_debug!(url = %candidate, error = %e, "Downloaded file failed verification");
+            MirrorPolicy::global().record_failure(candidate);
+            discard_part(&part).await;
+            last_error = Some(e);
+            continue;
+        }
+
+        MirrorPolicy::global().record_success(candidate, latency);
+        fs::rename(&part, dest).await?;
+        let _ = fs::remove_file(meta_path(&part)).await;
+        return Ok(());
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        DownloadError::Io(std::io::Error::other("No candidates available for download"))
+    }))
+}
+
+/// Outcome of a single request in [`fetch_resumable`].
+enum Attempt {
+    /// The body was received up to its end.
+    Complete,
+    /// The body stream broke; whatever was written is kept for a resume.
+    Interrupted(DownloadError),
+}
+
+/// Where a partial download resumes from.
+struct ResumeFrom {
+    /// Bytes already in the part file
+    offset: u64,
+    /// `ETag` or `Last-Modified` of the response the bytes came from
+    validator: Option<String>,
+}
+
+/// Fetches `url` into `part`, resuming up to [`MAX_RESUME_ATTEMPTS`] times
+/// in a row; an interrupted request that added bytes resets the count.
+///
+/// Returns the time until the first response headers were received.
+async fn fetch_resumable<F>(
+    url: &str,
+    part: &Path,
+    expected: &DownloadExpectation,
+    on_progress: &F,
+) -> DownloadResult<Duration>
+where
+    F: Fn(u64, u64),
+{
+    let expected_size = expected.size;
+    let mut latency = None;
+    let mut resumes = 0;
+    // Whether automatic decompression was turned off after a decode error
+    let mut raw = false;
+
+    loop {
+        let mut resume = ResumeFrom {
+            offset: fs::metadata(part).await.map(|meta| meta.len()).unwrap_or(0),
+            validator: None,
+        };
+
+        if resume.offset > 0 {
+            resume.validator = read_validator(part, url).await;
+            // Nothing would catch a prefix from another version of the file
+            if resume.validator.is_none() && expected.hash.is_none() {
+                trace_debug!(url = %url, "Partial download without validator discarded");
+                discard_part(part).await;
+                resume.offset = 0;
+            }
+        }
+
+        // A previous run already fetched everything; verification decides
+        if resume.offset > 0 && expected_size == Some(resume.offset) {
+            return Ok(latency.unwrap_or_default());
+        }
+
+        let client = if raw { raw_streaming_http_client() } else { streaming_http_client() };
+        let attempt = match stream_once(client, url, part, &resume, expected_size, on_progress, &mut latency).await {
+            // Decoding fails on the headers or mid-body: restart from the
+            // first byte, the part file may hold decoded data
+            Ok(Attempt::Interrupted(e)) | Err(e) if !raw && is_decode_error(&e) => {
+                trace_debug!(
+                    url = %url,
+                    "Response decode failed; retrying download without automatic decompression"
+                );
+                discard_part(part).await;
+                raw = true;
+                continue;
+            }
+            result => result?,
+        };
+
+        match attempt {
+            Attempt::Complete => return Ok(latency.unwrap_or_default()),
+            Attempt::Interrupted(e) => {
+                // Only requests that broke before adding a byte count
+                let progressed = fs::metadata(part)
+                    .await
+                    .is_ok_and(|meta| meta.len() > resume.offset);
+                if progressed {
+                    resumes = 0;
+                }
+                if resumes >= MAX_RESUME_ATTEMPTS {
+                    return Err(e);
+                }
+                resumes += 1;
+                trace_debug!(
+                    url = %url,
+                    error = %e,
+                    "Download interrupted; resuming ({}/{})",
+                    resumes,
+                    MAX_RESUME_ATTEMPTS
+                );
+            }
+        }
+    }
+}
+
+async fn stream_once<F>(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    resume: &ResumeFrom,
+    expected_size: Option<u64>,
+    on_progress: &F,
+    latency: &mut Option<Duration>,
+) -> DownloadResult<Attempt>
+where
+    F: Fn(u64, u64),
+{
+    let offset = resume.offset;
+    let started = Instant::now();
+    let mut request = client.get(url).header(ACCEPT_ENCODING, "identity");
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+        // A changed file comes back whole (200) instead of as a suffix
+        if let Some(validator) = &resume.validator {
+            request = request.header(IF_RANGE, validator.as_str());
+        }
+    }
+
+    let response = request.send().await?;
+    latency.get_or_insert(started.elapsed());
+
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // `bytes */<len>`: the part file already holds the whole body
+        // when its length matches, otherwise it is stale
+        let complete_len = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("bytes */"))
+            .and_then(|len| len.parse::<u64>().ok());
+
+        if complete_len == Some(offset) {
+            return Ok(Attempt::Complete);
+        }
+
+        discard_part(part).await;
+        return Ok(Attempt::Interrupted(DownloadError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Stale partial download discarded",
+        ))));
+    }
+
+    let mut response = response.error_for_status()?;
+
+    let is_identity = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map_or(true, |encoding| encoding.eq_ignore_ascii_case("identity"));
+
+    let resumed = offset > 0
+        && response.status() == StatusCode::PARTIAL_CONTENT
+        && content_range_start(&response) == Some(offset);
+
+    let mut current = if resumed { offset } else { 0 };
+    let total = expected_size
+        .or_else(|| {
+            response
+                .content_length()
+                .filter(|_| is_identity)
+                .map(|len| len + current)
+        })
+        .unwrap_or(0);
+
+    let file = if resumed {
+        fs::OpenOptions::new().append(true).open(part).await?
+    } else {
+        let file = fs::File::create(part).await?;
+        write_validator(part, url, &response).await;
+        file
+    };
+    let mut writer = BufWriter::with_capacity(256 * 1024, file);
+
+    on_progress(current, total);
+
+    loop {
+        match response.chunk().await {
+            Ok(Some(data)) => {
+                if let Err(e) = checkpoint().await {
+                    // Keep a clean prefix to resume from
+                    writer.flush().await?;
+                    if !is_identity {
+                        drop(writer);
+                        discard_part(part).await;
+                    }
+                    return Err(e.into());
+                }
+                DownloadScheduler::global().throttle(data.len()).await;
+                writer.write_all(&data).await?;
+                current += data.len() as u64;
+                on_progress(if total > 0 { current.min(total) } else { current }, total);
+            }
+            Ok(None) => break,
+            Err(e) => {
+                writer.flush().await?;
+                // Decoded bodies can't be resumed at a byte offset of the
+                // encoded stream: start over instead
+                if !is_identity {
+                    drop(writer);
+                    discard_part(part).await;
+                }
+                return Ok(Attempt::Interrupted(e.into()));
+            }
+        }
+    }
+
+    writer.flush().await?;
+    writer.get_ref().sync_all().await?;
+    Ok(Attempt::Complete)
+}
+
+/// Start offset announced by a `206` response (`Content-Range: bytes <start>-<end>/<len>`).
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// `<dest>.part.meta`: source URL, then validator, one per line.
+fn meta_path(part: &Path) -> PathBuf {
+    let mut name = part.file_name().unwrap_or_default().to_os_string();
+    name.push(".meta");
+    part.with_file_name(name)
+}
+
+/// Validator a partial download of `url` can be resumed with, if any.
+async fn read_validator(part: &Path, url: &str) -> Option<String> {
+    let meta = fs::read_to_string(meta_path(part)).await.ok()?;
+    let mut lines = meta.lines();
+    if lines.next()? != url {
+        return None;
+    }
+    lines.next().filter(|validator| !validator.is_empty()).map(str::to_string)
+}
+
+/// Records the validator of a response whose body starts a new part file.
+///
+/// Weak `ETag`s can't be used with `If-Range`; `Last-Modified` is the
+/// fallback.
+async fn write_validator(part: &Path, url: &str, response: &reqwest::Response) {
+    let headers = response.headers();
+    let validator = headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| headers.get(LAST_MODIFIED).and_then(|value| value.to_str().ok()));
+
+    let meta = meta_path(part);
+    match validator {
+        Some(validator) => {
+            let _ = fs::write(&meta, format!("{}\n{}\n", url, validator)).await;
+        }
+        None => {
+            let _ = fs::remove_file(&meta).await;
+        }
+    }
+}
+
+/// Deletes a part file and its metadata.
+async fn discard_part(part: &Path) {
+    let _ = fs::remove_file(part).await;
+    let _ = fs::remove_file(meta_path(part)).await;
+}
@@ -46,6 +46,23 @@ pub enum DownloadError {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("Size mismatch for {path}: expected {expected} bytes, got {actual}")]
+    SizeMismatch { path: String, expected: u64, actual: u64 },
+
+    #[error(transparent)]
+    OfflineUnavailable(#[from] OfflineUnavailable),
+
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+
+    #[error("{algorithm} mismatch for {path}: expected {expected}, got {actual}")]
+    HashMismatch {
+        path: String,
+        algorithm: &'static str,
+        expected: String,
+        actual: String,
+    },
 }
 
 /// Type alias for System operations results
@@ -65,10 +82,72 @@ pub enum AppStateError {
 
     #[error("Platform doesn't expose a standard {0} directory")]
     MissingPlatformDir(&'static str),
+
+    #[error("Cannot locate the launcher executable directory: {0}")]
+    ExecutableDir(#[source] std::io::Error),
+
+    #[error("Invalid launcher path {path:?}: {source}")]
+    InvalidPath {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
 }
 
+/// Errors related to the shared HTTP client configuration
+#[derive(Debug, Error)]
+pub enum HttpConfigError {
+    #[error("HTTP clients are already initialized — install the HttpConfig before the first request")]
+    AlreadyInitialized,
+
+    #[error("Invalid proxy {url}: {source}")]
+    InvalidProxy {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("Invalid root certificate: {0}")]
+    InvalidCertificate(#[source] reqwest::Error),
+
+    #[error("Failed to read root certificate {path}: {source}")]
+    CertificateIo {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Failed to build HTTP client: {0}")]
+    Build(#[source] reqwest::Error),
+}
+
+/// A resource needed the network while offline mode was on.
+///
+/// `resource` names exactly what is missing locally (a metadata entry, a
+/// file path, a Java runtime, …).
+#[derive(Debug, Clone, Error)]
+#[error("Offline mode: {resource} is not available locally")]
+pub struct OfflineUnavailable {
+    pub resource: String,
+}
+
+impl OfflineUnavailable {
+    pub fn new(resource: impl Into<String>) -> Self {
+        Self { resource: resource.into() }
+    }
+}
+
+/// The operation was stopped through its
+/// [`CancellationToken`](crate::cancel::CancellationToken).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Operation cancelled")]
+pub struct Cancelled;
+
 /// Type alias for download operations results
 pub type DownloadResult<T> = Result<T, DownloadError>;
 
 /// Type alias for app state operations results
 pub type AppStateResult<T> = Result<T, AppStateError>;
+
+/// Type alias for HTTP configuration results
+pub type HttpConfigResult<T> = Result<T, HttpConfigError>;
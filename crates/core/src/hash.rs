@@ -3,10 +3,15 @@
 
 //! File hashing utilities
 //!
-//! Provides SHA1 hash verification for files with both sync and async implementations
+//! Provides SHA1 hash verification for files with both sync and async
+//! implementations, plus algorithm-generic helpers ([`HashAlgorithm`],
+//! [`ExpectedHash`]) for sources that publish SHA-256 or SHA-512.
 
+use std::fmt;
 use std::path::Path;
+use serde::{Deserialize, Serialize};
 use sha1::{Sha1, Digest};
+use sha2::{Sha256, Sha512};
 use std::io::Read;
 use tokio::fs;
 use thiserror::Error;
@@ -142,3 +147,205 @@ pub fn calculate_sha1_bytes_raw(data: &[u8]) -> [u8; 20] {
     hasher.update(data);
     hasher.finalize().into()
 }
+
+/// Hash algorithms understood by the launcher, ordered from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// Display name (`"SHA-1"`, `"SHA-256"`, `"SHA-512"`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha1 => "SHA-1",
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha512 => "SHA-512",
+        }
+    }
+
+    /// Length of the hex-encoded digest.
+    pub fn hex_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 40,
+            HashAlgorithm::Sha256 => 64,
+            HashAlgorithm::Sha512 => 128,
+        }
+    }
+
+    fn hasher(&self) -> Hasher {
+        match self {
+            HashAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            HashAlgorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Incremental hasher over any [`HashAlgorithm`].
+enum Hasher {
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Hasher::Sha1(h) => hex::encode(h.finalize()),
+            Hasher::Sha256(h) => hex::encode(h.finalize()),
+            Hasher::Sha512(h) => hex::encode(h.finalize()),
+        }
+    }
+}
+
+/// A digest published by a download source, paired with its algorithm.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExpectedHash {
+    pub algorithm: HashAlgorithm,
+    /// Hex-encoded digest (compared case-insensitively).
+    pub hex: String,
+}
+
+impl ExpectedHash {
+    pub fn new(algorithm: HashAlgorithm, hex: impl Into<String>) -> Self {
+        Self { algorithm, hex: hex.into() }
+    }
+
+    pub fn sha1(hex: impl Into<String>) -> Self {
+        Self::new(HashAlgorithm::Sha1, hex)
+    }
+
+    pub fn sha256(hex: impl Into<String>) -> Self {
+        Self::new(HashAlgorithm::Sha256, hex)
+    }
+
+    pub fn sha512(hex: impl Into<String>) -> Self {
+        Self::new(HashAlgorithm::Sha512, hex)
+    }
+
+    /// Picks the strongest hash among `hashes`.
+    pub fn strongest<'a, I>(hashes: I) -> Option<&'a ExpectedHash>
+    where
+        I: IntoIterator<Item = &'a ExpectedHash>,
+    {
+        hashes.into_iter().max_by_key(|hash| hash.algorithm)
+    }
+
+    /// Whether `actual` (hex) equals this digest.
+    pub fn matches(&self, actual: &str) -> bool {
+        self.hex.eq_ignore_ascii_case(actual)
+    }
+
+    /// Streams the file at `path` and compares its digest (async).
+    pub async fn verify_file(&self, path: &Path) -> HashResult<bool> {
+        Ok(self.matches(&calculate_file_hash(path, self.algorithm).await?))
+    }
+
+    /// Streams the file at `path` and compares its digest (sync).
+    pub fn verify_file_sync(&self, path: &Path) -> HashResult<bool> {
+        Ok(self.matches(&calculate_file_hash_sync(path, self.algorithm)?))
+    }
+}
+
+impl fmt::Display for ExpectedHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.hex)
+    }
+}
+
+/// Calculates the digest of a file with streaming reads (async version)
+///
+/// # Returns
+/// The hash as a lowercase hex string
+pub async fn calculate_file_hash(path: &Path, algorithm: HashAlgorithm) -> HashResult<String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let n = file.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hasher.finalize_hex())
+}
+
+/// Calculates the digest of a file with streaming reads (sync version)
+///
+/// # Returns
+/// The hash as a lowercase hex string
+pub fn calculate_file_hash_sync(path: &Path, algorithm: HashAlgorithm) -> HashResult<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = algorithm.hasher();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hasher.finalize_hex())
+}
+
+/// Files at least this large are hashed through a memory map.
+const MMAP_THRESHOLD: u64 = 256 * 1024;
+
+/// Calculates the digest of a file through a memory map (sync version)
+///
+/// Large files are mapped and hashed in one pass without copying through a
+/// userspace buffer; files under 256 KiB are read directly, where mapping
+/// costs more than it saves. Blocking — run it on a blocking thread pool.
+///
+/// # Returns
+/// The hash as a lowercase hex string
+pub fn calculate_file_hash_mmap(path: &Path, algorithm: HashAlgorithm) -> HashResult<String> {
+    let file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+
+    if len < MMAP_THRESHOLD {
+        return Ok(calculate_hash_bytes(&std::fs::read(path)?, algorithm));
+    }
+
+    // SAFETY: the map is read-only and dropped before returning. A file
+    // truncated by another process while mapped would fault; the launcher
+    // owns these files and never rewrites them in place (downloads land
+    // through a `.part` rename).
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    Ok(calculate_hash_bytes(&map, algorithm))
+}
+
+/// Calculates the digest of arbitrary bytes
+///
+/// # Returns
+/// The hash as a lowercase hex string
+pub fn calculate_hash_bytes(data: &[u8], algorithm: HashAlgorithm) -> String {
+    let mut hasher = algorithm.hasher();
+    hasher.update(data);
+    hasher.finalize_hex()
+}
@@ -1,173 +1,373 @@
 use std::env;
+use std::path::PathBuf;
 use std::time::Duration;
-use once_cell::sync::Lazy;
-use reqwest::{Client, Url};
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::{Certificate, Client, NoProxy, Proxy};
 use tokio::fs;
 use thiserror::Error;
 
-/// Global User-Agent for the launcher (commented placeholder).
-///static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+use crate::errors::{HttpConfigError, HttpConfigResult};
+use crate::mirrors::MirrorPolicy;
 
-/// Shared HTTP client tuned for parallel asset/library downloads.
+/// Default User-Agent sent by every launcher request.
+pub const DEFAULT_USER_AGENT: &str = concat!("LightyLauncher/", env!("CARGO_PKG_VERSION"));
+
+/// Network settings shared by every HTTP request the launcher makes.
 ///
-/// Tuned for the high concurrency of installer workloads: large connection
-/// pool, HTTP/2 window scaling, TCP keepalive, and aggressive compression.
-pub static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
-    Client::builder()
-        // Connection pooling - balance between performance and OS limits
-        .pool_max_idle_per_host(100)
-        .pool_idle_timeout(Some(Duration::from_secs(90)))
-
-        // HTTP/2 optimisations
-        .http2_initial_stream_window_size(Some(2 * 1024 * 1024))
-        .http2_initial_connection_window_size(Some(4 * 1024 * 1024))
-        .http2_adaptive_window(true)
-        .http2_max_frame_size(Some(16 * 1024))
-
-        // TCP optimisations
-        .tcp_keepalive(Some(Duration::from_secs(60)))
-        .tcp_nodelay(true)
-
-        // Timeouts - prevent stuck connections
-        .timeout(Duration::from_secs(60))
-        .connect_timeout(Duration::from_secs(5))
-
-        // Compression
-        .zstd(true)
-        .gzip(true)
-        .brotli(true)
-
-        .build()
-        .expect("Failed to build HTTP client with default configuration - this should never fail")
-});
-
-    /// Fallback client that disables automatic response decompression.
-    ///
-    /// Some mod-loader or installer endpoints incorrectly advertise a content
-    /// encoding that does not match the payload. When that happens, the normal
-    /// client can fail with a response-body decode error. This client is used as
-    /// a last resort so we can still save the raw bytes when the server returns
-    /// an already-plain response or a mislabeled body.
-    pub static RAW_HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
-        Client::builder()
-        .pool_max_idle_per_host(100)
-        .pool_idle_timeout(Some(Duration::from_secs(90)))
-        .http2_initial_stream_window_size(Some(2 * 1024 * 1024))
-        .http2_initial_connection_window_size(Some(4 * 1024 * 1024))
-        .http2_adaptive_window(true)
-        .http2_max_frame_size(Some(16 * 1024))
-        .tcp_keepalive(Some(Duration::from_secs(60)))
-        .tcp_nodelay(true)
-        .timeout(Duration::from_secs(60))
-        .connect_timeout(Duration::from_secs(5))
-        .gzip(false)
-        .brotli(false)
-        .zstd(false)
-        .build()
-        .expect("Failed to build raw HTTP client - this should never fail")
-    });
-
-fn env_base(var: &str) -> Option<String> {
-    env::var(var)
-        .ok()
-        .map(|value| value.trim().trim_end_matches('/').to_string())
-        .filter(|value| !value.is_empty())
-}
-
-fn join_base_and_path(base: &str, path: &str) -> String {
-    if path.starts_with('/') {
-        format!("{}{}", base, path)
-    } else {
-        format!("{}/{}", base, path)
-    }
+/// Install it once at startup with [`init_http_config`], before any
+/// download, loader fetch, mod-platform call or authentication request.
+/// Without it, [`HttpConfig::default`] is used on first request.
+///
+/// When no [`ProxyConfig`] is set, the standard `HTTP_PROXY` /
+/// `HTTPS_PROXY` / `NO_PROXY` environment variables still apply.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use lighty_core::hosts::{init_http_config, HttpConfig};
+///
+/// init_http_config(
+///     HttpConfig::default()
+///         .with_user_agent("MyLauncher/1.0")
+///         .with_proxy("http://proxy.corp.example:3128")
+///         .with_proxy_auth("alice", "secret")
+///         .with_root_certificate_file("/etc/ssl/corp-root.pem"),
+/// )?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// `User-Agent` header value.
+    pub user_agent: String,
+    /// Explicit proxy for every request.
+    pub proxy: Option<ProxyConfig>,
+    /// Extra trusted roots, merged with the platform/built-in ones.
+    pub root_certificates: Vec<RootCertificate>,
+    /// Total timeout of a single request, body included. Not applied to
+    /// streamed downloads, which can legitimately take longer.
+    pub timeout: Duration,
+    /// Longest wait for the next piece of a response body. Streamed
+    /// downloads rely on it instead of [`timeout`](Self::timeout); time
+    /// spent in the bandwidth limiter between reads does not count.
+    pub read_timeout: Duration,
+    /// Timeout of the TCP/TLS connect phase.
+    pub connect_timeout: Duration,
+    /// How long idle pooled connections are kept alive.
+    pub pool_idle_timeout: Duration,
+    /// Maximum idle connections kept per host.
+    pub pool_max_idle_per_host: usize,
 }
 
-fn push_fastmcmirror(urls: &mut Vec<String>, base: &str, path: &str) {
-    if !path.is_empty() {
-        urls.push(join_base_and_path(base, path));
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            root_certificates: Vec::new(),
+            timeout: Duration::from_secs(60),
+            read_timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(5),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 100,
+        }
     }
 }
 
-/// Builds a PrismLauncher metadata URL for a package/version pair.
-///
-/// PrismLauncher stores loader metadata as per-package JSON files under
-/// `https://meta.prismlauncher.org/v1/{uid}/{version}.json`.
-pub fn prism_meta_url(package_uid: &str, version: &str) -> String {
-    format!("https://meta.prismlauncher.org/v1/{}/{}.json", package_uid, version)
-}
-
-pub fn build_fallback_urls(original: &str) -> Vec<String> {
-    let mut urls = vec![original.to_string()];
-
-    let Ok(parsed) = Url::parse(original) else {
-        return urls;
-    };
+impl HttpConfig {
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
 
-    let host = parsed.host_str().unwrap_or_default();
-    let path = parsed.path();
+    /// Routes every request (HTTP and HTTPS) through `url`.
+    pub fn with_proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(ProxyConfig::new(url));
+        self
+    }
 
-    if host.eq_ignore_ascii_case("resources.download.minecraft.net") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com/assets", path);
-        if let Some(base) = env_base("LIGHTY_MIRROR_MOJANG_ASSETS") {
-            urls.push(join_base_and_path(&base, path));
+    /// Sets Basic credentials on the configured proxy. No-op without one.
+    pub fn with_proxy_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        if let Some(proxy) = self.proxy.as_mut() {
+            proxy.username = Some(username.into());
+            proxy.password = Some(password.into());
         }
+        self
     }
 
-    if host.eq_ignore_ascii_case("piston-meta.mojang.com") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com", path);
-        if let Some(base) = env_base("LIGHTY_MIRROR_PISTON_META") {
-            urls.push(join_base_and_path(&base, path));
+    /// Comma-separated hosts that bypass the configured proxy
+    /// (same syntax as `NO_PROXY`). No-op without a proxy.
+    pub fn with_no_proxy(mut self, no_proxy: impl Into<String>) -> Self {
+        if let Some(proxy) = self.proxy.as_mut() {
+            proxy.no_proxy = Some(no_proxy.into());
         }
+        self
     }
 
-    if host.eq_ignore_ascii_case("launchermeta.mojang.com") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com", path);
+    /// Trusts the certificate(s) of a PEM bundle.
+    pub fn with_root_certificate_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(RootCertificate::Pem(pem.into()));
+        self
     }
 
-    if host.eq_ignore_ascii_case("libraries.minecraft.net") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com/maven", path);
+    /// Trusts a DER-encoded certificate.
+    pub fn with_root_certificate_der(mut self, der: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(RootCertificate::Der(der.into()));
+        self
     }
 
-    if host.eq_ignore_ascii_case("meta.fabricmc.net") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://meta2.fabricmc.net", path);
-        // NOTE: bmclapi2.bangbang93.com/fabric-meta returns 404 "未找到fabric meta"
-        // Not supported by this mirror, only use if explicitly configured via env var
-        if let Some(base) = env_base("LIGHTY_MIRROR_FABRIC_META") {
-            urls.push(join_base_and_path(&base, path));
-        }
+    /// Trusts the certificate(s) stored at `path` (DER if the extension is
+    /// `.der` or `.cer`, PEM bundle otherwise). Read when the clients are built.
+    pub fn with_root_certificate_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.root_certificates.push(RootCertificate::File(path.into()));
+        self
     }
 
-    if host.eq_ignore_ascii_case("maven.fabricmc.net") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com/maven", path);
-        if let Some(base) = env_base("LIGHTY_MIRROR_FABRIC_MAVEN") {
-            urls.push(join_base_and_path(&base, path));
-        }
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
     }
 
-    if host.eq_ignore_ascii_case("maven.minecraftforge.net") && !path.is_empty() {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com/maven", path);
-        if let Some(base) = env_base("LIGHTY_MIRROR_FORGE_MAVEN") {
-            urls.push(join_base_and_path(&base, path));
+    fn build_proxy(&self) -> HttpConfigResult<Option<Proxy>> {
+        let Some(config) = &self.proxy else {
+            return Ok(None);
+        };
+
+        let mut proxy = Proxy::all(&config.url).map_err(|source| HttpConfigError::InvalidProxy {
+            url: config.url.clone(),
+            source,
+        })?;
+
+        if let Some(username) = &config.username {
+            proxy = proxy.basic_auth(username, config.password.as_deref().unwrap_or_default());
         }
+        if let Some(no_proxy) = &config.no_proxy {
+            proxy = proxy.no_proxy(NoProxy::from_string(no_proxy));
+        }
+
+        Ok(Some(proxy))
     }
 
-    if host.eq_ignore_ascii_case("files.minecraftforge.net") && path.starts_with("/maven") {
-        push_fastmcmirror(&mut urls, "https://bmclapi2.bangbang93.com", path);
+    fn build_certificates(&self) -> HttpConfigResult<Vec<Certificate>> {
+        let mut certificates = Vec::new();
+
+        for root in &self.root_certificates {
+            match root {
+                RootCertificate::Pem(pem) => certificates.extend(
+                    Certificate::from_pem_bundle(pem).map_err(HttpConfigError::InvalidCertificate)?,
+                ),
+                RootCertificate::Der(der) => certificates.push(
+                    Certificate::from_der(der).map_err(HttpConfigError::InvalidCertificate)?,
+                ),
+                RootCertificate::File(path) => {
+                    let bytes = std::fs::read(path).map_err(|source| HttpConfigError::CertificateIo {
+                        path: path.display().to_string(),
+                        source,
+                    })?;
+                    let is_der = path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("der") || ext.eq_ignore_ascii_case("cer"));
+
+                    if is_der {
+                        certificates.push(Certificate::from_der(&bytes).map_err(HttpConfigError::InvalidCertificate)?);
+                    } else {
+                        certificates.extend(
+                            Certificate::from_pem_bundle(&bytes).map_err(HttpConfigError::InvalidCertificate)?,
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(certificates)
     }
 
-    if host.eq_ignore_ascii_case("maven.neoforged.net") && !path.is_empty() {
-        if let Some(base) = env_base("LIGHTY_MIRROR_NEOFORGE_MAVEN") {
-            urls.push(join_base_and_path(&base, path));
+    /// Builds a client from this configuration.
+    ///
+    /// Tuned for the high concurrency of installer workloads: large connection
+    /// pool, HTTP/2 window scaling, TCP keepalive. `decompress` toggles
+    /// automatic gzip/brotli/zstd decoding of response bodies; `streaming`
+    /// drops the total timeout and keeps only the read timeout.
+    fn build_client(&self, decompress: bool, streaming: bool) -> HttpConfigResult<Client> {
+        let mut builder = Client::builder()
+            .user_agent(&self.user_agent)
+
+            // Connection pooling - balance between performance and OS limits
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(Some(self.pool_idle_timeout))
+
+            // HTTP/2 optimisations
+            .http2_initial_stream_window_size(Some(2 * 1024 * 1024))
+            .http2_initial_connection_window_size(Some(4 * 1024 * 1024))
+            .http2_adaptive_window(true)
+            .http2_max_frame_size(Some(16 * 1024))
+
+            // TCP optimisations
+            .tcp_keepalive(Some(Duration::from_secs(60)))
+            .tcp_nodelay(true)
+
+            // Timeouts - prevent stuck connections
+            .read_timeout(self.read_timeout)
+            .connect_timeout(self.connect_timeout)
+
+            // Compression
+            .zstd(decompress)
+            .gzip(decompress)
+            .brotli(decompress);
+
+        if !streaming {
+            builder = builder.timeout(self.timeout);
+        }
+        if let Some(proxy) = self.build_proxy()? {
+            builder = builder.proxy(proxy);
+        }
+
+        let certificates = self.build_certificates()?;
+        if !certificates.is_empty() {
+            builder = builder.tls_certs_merge(certificates);
         }
+
+        builder.build().map_err(HttpConfigError::Build)
     }
+}
 
-    if host.eq_ignore_ascii_case("api.adoptium.net") && !path.is_empty() {
-        if let Some(base) = env_base("LIGHTY_MIRROR_ADOPTIUM") {
-            urls.push(join_base_and_path(&base, path));
+/// Explicit proxy settings for [`HttpConfig`].
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    /// Proxy URL, e.g. `http://proxy:3128` or `socks5://proxy:1080`.
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Comma-separated bypass list (`NO_PROXY` syntax).
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            username: None,
+            password: None,
+            no_proxy: None,
         }
     }
+}
+
+/// Additional trusted root certificate for [`HttpConfig`].
+#[derive(Debug, Clone)]
+pub enum RootCertificate {
+    /// PEM bundle (one or more certificates).
+    Pem(Vec<u8>),
+    /// Single DER-encoded certificate.
+    Der(Vec<u8>),
+    /// File read when the clients are built.
+    File(PathBuf),
+}
+
+struct HttpClients {
+    config: HttpConfig,
+    default: Client,
+    raw: Client,
+    streaming: Client,
+    raw_streaming: Client,
+}
+
+impl HttpClients {
+    fn build(config: HttpConfig) -> HttpConfigResult<Self> {
+        Ok(Self {
+            default: config.build_client(true, false)?,
+            raw: config.build_client(false, false)?,
+            streaming: config.build_client(true, true)?,
+            raw_streaming: config.build_client(false, true)?,
+            config,
+        })
+    }
+}
+
+static CLIENTS: OnceCell<HttpClients> = OnceCell::new();
+
+/// Installs the process-wide [`HttpConfig`].
+///
+/// Must be called once, before the first request. Returns
+/// [`HttpConfigError::AlreadyInitialized`] if the clients were already built
+/// (by a previous call or by a request made with the default configuration).
+pub fn init_http_config(config: HttpConfig) -> HttpConfigResult<()> {
+    let clients = HttpClients::build(config)?;
+    CLIENTS
+        .set(clients)
+        .map_err(|_| HttpConfigError::AlreadyInitialized)
+}
+
+fn clients() -> &'static HttpClients {
+    CLIENTS.get_or_init(|| {
+        HttpClients::build(HttpConfig::default())
+            .expect("Failed to build HTTP client with default configuration - this should never fail")
+    })
+}
+
+/// Returns the installed [`HttpConfig`] (the default one if none was installed).
+pub fn http_config() -> &'static HttpConfig {
+    &clients().config
+}
+
+/// Shared HTTP client used by every subsystem, built from the installed
+/// [`HttpConfig`].
+pub fn http_client() -> &'static Client {
+    &clients().default
+}
+
+/// Fallback client that disables automatic response decompression.
+///
+/// Some mod-loader or installer endpoints incorrectly advertise a content
+/// encoding that does not match the payload. When that happens, the normal
+/// client can fail with a response-body decode error. This client is used as
+/// a last resort so we can still save the raw bytes when the server returns
+/// an already-plain response or a mislabeled body.
+pub fn raw_http_client() -> &'static Client {
+    &clients().raw
+}
+
+/// [`http_client`] without the total timeout, for downloads streamed in
+/// chunks (see [`HttpConfig::read_timeout`]).
+pub(crate) fn streaming_http_client() -> &'static Client {
+    &clients().streaming
+}
+
+/// [`raw_http_client`] without the total timeout.
+pub(crate) fn raw_streaming_http_client() -> &'static Client {
+    &clients().raw_streaming
+}
+
+/// Handle on [`http_client`], kept for code that expects a static.
+pub static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| http_client().clone());
+
+/// Handle on [`raw_http_client`], kept for code that expects a static.
+pub static RAW_HTTP_CLIENT: Lazy<Client> = Lazy::new(|| raw_http_client().clone());
 
-    urls
+/// Builds a PrismLauncher metadata URL for a package/version pair.
+///
+/// PrismLauncher stores loader metadata as per-package JSON files under
+/// `https://meta.prismlauncher.org/v1/{uid}/{version}.json`.
+pub fn prism_meta_url(package_uid: &str, version: &str) -> String {
+    format!("https://meta.prismlauncher.org/v1/{}/{}.json", package_uid, version)
+}
+
+/// Returns the ordered download candidates for `original`.
+///
+/// The original URL comes first, followed by the mirrors configured for its
+/// host in the global [`MirrorPolicy`]. Sources with a poor health record
+/// are moved to the end of the list.
+pub fn build_fallback_urls(original: &str) -> Vec<String> {
+    MirrorPolicy::global().candidates(original)
 }
 
 
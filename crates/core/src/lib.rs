@@ -3,14 +3,22 @@
 //! This crate has no `lighty-*` dependencies; everything else depends on it.
 //! It groups the cross-cutting helpers needed throughout the launcher:
 //! [`AppState`] for project directories, an HTTP client and host-file guard
-//! in [`hosts`], async [`download`] / [`extract`] / [`hash`] helpers, an OS
-//! and architecture detection layer in [`system`], and conditional logging
-//! [`macros`].
+//! in [`hosts`], a configurable download [`mirrors`] policy, async
+//! [`download`] / [`extract`] / [`hash`] helpers, the process-wide
+//! download [`scheduler`], shared [`store`], [`offline`] switch,
+//! [`cancel`] tokens, crash-safe writes in [`persist`], an OS and architecture detection layer in
+//! [`system`], and conditional logging [`macros`].
 
 pub mod system;
 pub mod macros;
 pub mod hosts;
+pub mod mirrors;
 pub mod download;
+pub mod scheduler;
+pub mod store;
+pub mod offline;
+pub mod cancel;
+pub mod persist;
 pub mod extract;
 pub mod hash;
 pub mod errors;
@@ -22,6 +30,9 @@ pub use errors::{
     ExtractError, ExtractResult,
     DownloadError, DownloadResult,
     AppStateError, AppStateResult,
+    HttpConfigError, HttpConfigResult,
+    OfflineUnavailable,
+    Cancelled,
 };
 
 // Re-export hash types for easy access
@@ -30,7 +41,25 @@ pub use hash::{
     verify_file_sha1, verify_file_sha1_streaming,
     calculate_file_sha1_sync, verify_file_sha1_sync,
     calculate_sha1_bytes, calculate_sha1_bytes_raw,
+    HashAlgorithm, ExpectedHash,
+    calculate_file_hash, calculate_file_hash_sync, calculate_file_hash_mmap,
+    calculate_hash_bytes,
 };
 
+// Re-export HTTP configuration
+pub use hosts::{HttpConfig, init_http_config};
+
+// Re-export mirror policy
+pub use mirrors::{Mirror, MirrorHealth, MirrorPolicy};
+
+// Re-export download scheduler
+pub use scheduler::{DownloadPriority, DownloadScheduler, SchedulerLimits};
+
+// Re-export cancellation
+pub use cancel::CancellationToken;
+
+// Re-export shared store
+pub use store::{SharedStore, LinkMethod};
+
 // Re-export app state
-pub use app_state::AppState;
\ No newline at end of file
+pub use app_state::{AppState, LauncherPaths};
\ No newline at end of file
@@ -0,0 +1,612 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Per-host download mirror registry with health-based demotion.
+//!
+//! [`MirrorPolicy`] maps an upstream host (`libraries.minecraft.net`,
+//! `meta.fabricmc.net`, …) to an ordered list of [`Mirror`]s. Every
+//! download path in the launcher goes through
+//! [`build_fallback_urls`](crate::hosts::build_fallback_urls), which asks
+//! the [global](MirrorPolicy::global) policy for the candidate list:
+//! the original URL first, then each enabled mirror in registration
+//! order.
+//!
+//! Downloaders report the outcome of each attempt back with
+//! [`MirrorPolicy::record_success`] / [`MirrorPolicy::record_failure`].
+//! Sources (the upstream itself included) whose failure rate or latency
+//! drifts too far from the healthy pack are moved to the end of the
+//! candidate list instead of being retried first on every file. A
+//! demoted source is given another chance once [`RECOVERY_WINDOW`] has
+//! passed since its last failure (or, for a slow one, since its last
+//! attempt).
+//!
+//! The default policy reproduces the historical hard-coded fallbacks
+//! (BMCLAPI, `meta2.fabricmc.net`) plus the `LIGHTY_MIRROR_*`
+//! environment variables.
+//!
+//! ```rust,ignore
+//! use lighty_core::mirrors::{Mirror, MirrorPolicy, BMCLAPI};
+//!
+//! let policy = MirrorPolicy::global();
+//! // EU users: never touch the third-party mirror
+//! policy.set_enabled_everywhere(BMCLAPI, false);
+//! // Corporate Nexus proxy, tried before anything else
+//! policy.insert_mirror(
+//!     "libraries.minecraft.net",
+//!     0,
+//!     Mirror::new("nexus", "https://nexus.corp.example/repository/mojang"),
+//! );
+//! ```
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use reqwest::Url;
+
+/// Name under which the BMCLAPI mirrors are registered by default.
+pub const BMCLAPI: &str = "bmclapi";
+
+/// Name under which `meta2.fabricmc.net` is registered by default.
+pub const FABRIC_META2: &str = "fabric-meta2";
+
+/// Name under which `LIGHTY_MIRROR_*` environment overrides are registered.
+pub const ENV_MIRROR: &str = "env";
+
+/// Samples required before a source can be demoted.
+const MIN_SAMPLES: u32 = 3;
+
+/// Failure rate (EWMA) above which a source is demoted.
+const MAX_FAILURE_RATE: f64 = 0.5;
+
+/// A source slower than `SLOW_FACTOR` × the fastest healthy candidate is demoted.
+const SLOW_FACTOR: f64 = 4.0;
+
+/// Smoothing factor for the latency / failure moving averages.
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Time after the last failure (or last attempt, for a slow source) before
+/// a demoted source is retried in order.
+pub const RECOVERY_WINDOW: Duration = Duration::from_secs(300);
+
+static GLOBAL_POLICY: Lazy<MirrorPolicy> = Lazy::new(MirrorPolicy::with_defaults);
+
+/// A single mirror for an upstream host.
+///
+/// The upstream URL path is appended to [`Self::base_url`], so
+/// `https://libraries.minecraft.net/org/lwjgl/…` becomes
+/// `{base_url}/org/lwjgl/…`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mirror {
+    /// Stable identifier used by [`MirrorPolicy::reorder`],
+    /// [`MirrorPolicy::set_enabled`] and friends.
+    pub name: String,
+    /// Base URL without trailing slash.
+    pub base_url: String,
+    /// Disabled mirrors stay registered but are never returned as candidates.
+    pub enabled: bool,
+    /// When set, the mirror only applies to upstream paths starting with
+    /// this prefix (e.g. `/maven` on `files.minecraftforge.net`).
+    pub path_prefix: Option<String>,
+}
+
+impl Mirror {
+    /// Creates an enabled mirror that applies to every path on its host.
+    pub fn new(name: impl Into<String>, base_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            base_url: base_url.into().trim().trim_end_matches('/').to_string(),
+            enabled: true,
+            path_prefix: None,
+        }
+    }
+
+    /// Restricts the mirror to upstream paths starting with `prefix`.
+    pub fn with_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    fn applies_to(&self, path: &str) -> bool {
+        self.enabled
+            && !path.is_empty()
+            && self
+                .path_prefix
+                .as_deref()
+                .map_or(true, |prefix| path.starts_with(prefix))
+    }
+}
+
+/// Snapshot of the health statistics tracked for one download source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MirrorHealth {
+    /// Attempts recorded so far.
+    pub samples: u32,
+    /// Exponentially-weighted failure rate in `0.0..=1.0`.
+    pub failure_rate: f64,
+    /// Exponentially-weighted latency of successful attempts.
+    pub latency: Option<Duration>,
+    /// Aggregate score in `0.0..=1.0` (higher is better), derived from
+    /// failure rate and latency. Unmeasured sources score `1.0`.
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct HealthStats {
+    samples: u32,
+    failure_rate: f64,
+    latency_ms: Option<f64>,
+    last_failure: Option<Instant>,
+    last_attempt: Option<Instant>,
+}
+
+impl HealthStats {
+    fn record(&mut self, latency: Option<Duration>) {
+        let failed = latency.is_none();
+        let failure = if failed { 1.0 } else { 0.0 };
+
+        self.failure_rate = if self.samples == 0 {
+            failure
+        } else {
+            self.failure_rate + EWMA_ALPHA * (failure - self.failure_rate)
+        };
+        self.samples = self.samples.saturating_add(1);
+        self.last_attempt = Some(Instant::now());
+
+        match latency {
+            Some(latency) => {
+                let ms = latency.as_secs_f64() * 1000.0;
+                self.latency_ms = Some(match self.latency_ms {
+                    Some(avg) => avg + EWMA_ALPHA * (ms - avg),
+                    None => ms,
+                });
+            }
+            None => self.last_failure = Some(Instant::now()),
+        }
+    }
+
+    fn score(&self) -> f64 {
+        let latency_factor = self
+            .latency_ms
+            .map_or(1.0, |ms| 1.0 / (1.0 + ms / 1000.0));
+        (1.0 - self.failure_rate) * latency_factor
+    }
+
+    fn snapshot(&self) -> MirrorHealth {
+        MirrorHealth {
+            samples: self.samples,
+            failure_rate: self.failure_rate,
+            latency: self.latency_ms.map(|ms| Duration::from_secs_f64(ms / 1000.0)),
+            score: self.score(),
+        }
+    }
+
+    /// Whether [`RECOVERY_WINDOW`] has passed since `since`: a demoted
+    /// source is then probed in its configured place again.
+    fn is_recovering(since: Option<Instant>) -> bool {
+        since.map_or(true, |at| at.elapsed() >= RECOVERY_WINDOW)
+    }
+}
+
+/// Registry of mirrors per upstream host plus their health statistics.
+///
+/// All methods take `&self`; the registry is internally synchronised so
+/// the [global](Self::global) instance can be reconfigured at any time
+/// while downloads are in flight.
+#[derive(Debug, Default)]
+pub struct MirrorPolicy {
+    /// Upstream host (lowercase) → ordered mirrors.
+    hosts: RwLock<HashMap<String, Vec<Mirror>>>,
+    /// Source base URL → health statistics.
+    health: Mutex<HashMap<String, HealthStats>>,
+}
+
+impl MirrorPolicy {
+    /// Creates an empty policy: every URL is only fetched from its origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy pre-populated with the built-in mirrors and the
+    /// `LIGHTY_MIRROR_*` environment overrides.
+    pub fn with_defaults() -> Self {
+        let policy = Self::new();
+
+        let bmclapi = "https://bmclapi2.bangbang93.com";
+
+        policy.add_mirror("resources.download.minecraft.net", Mirror::new(BMCLAPI, format!("{}/assets", bmclapi)));
+        policy.add_mirror("piston-meta.mojang.com", Mirror::new(BMCLAPI, bmclapi));
+        policy.add_mirror("launchermeta.mojang.com", Mirror::new(BMCLAPI, bmclapi));
+        policy.add_mirror("libraries.minecraft.net", Mirror::new(BMCLAPI, format!("{}/maven", bmclapi)));
+        policy.add_mirror("meta.fabricmc.net", Mirror::new(FABRIC_META2, "https://meta2.fabricmc.net"));
+        // NOTE: bmclapi2.bangbang93.com/fabric-meta returns 404 "未找到fabric meta"
+        // Not supported by this mirror, only use if explicitly configured via env var
+        policy.add_mirror("maven.fabricmc.net", Mirror::new(BMCLAPI, format!("{}/maven", bmclapi)));
+        policy.add_mirror("maven.minecraftforge.net", Mirror::new(BMCLAPI, format!("{}/maven", bmclapi)));
+        policy.add_mirror(
+            "files.minecraftforge.net",
+            Mirror::new(BMCLAPI, bmclapi).with_path_prefix("/maven"),
+        );
+
+        const ENV_OVERRIDES: &[(&str, &str)] = &[
+            ("resources.download.minecraft.net", "LIGHTY_MIRROR_MOJANG_ASSETS"),
+            ("piston-meta.mojang.com", "LIGHTY_MIRROR_PISTON_META"),
+            ("meta.fabricmc.net", "LIGHTY_MIRROR_FABRIC_META"),
+            ("maven.fabricmc.net", "LIGHTY_MIRROR_FABRIC_MAVEN"),
+            ("maven.minecraftforge.net", "LIGHTY_MIRROR_FORGE_MAVEN"),
+            ("maven.neoforged.net", "LIGHTY_MIRROR_NEOFORGE_MAVEN"),
+            ("api.adoptium.net", "LIGHTY_MIRROR_ADOPTIUM"),
+        ];
+        for (host, var) in ENV_OVERRIDES {
+            if let Some(base) = env_base(var) {
+                policy.add_mirror(host, Mirror::new(ENV_MIRROR, base));
+            }
+        }
+
+        policy
+    }
+
+    /// Process-wide policy used by [`build_fallback_urls`](crate::hosts::build_fallback_urls).
+    ///
+    /// Initialised lazily with [`Self::with_defaults`] on first use.
+    pub fn global() -> &'static MirrorPolicy {
+        &GLOBAL_POLICY
+    }
+
+    /// Appends `mirror` to the list for `host`, replacing any mirror with
+    /// the same name.
+    pub fn add_mirror(&self, host: &str, mirror: Mirror) {
+        let mut hosts = self.hosts.write().unwrap();
+        let list = hosts.entry(normalize_host(host)).or_default();
+        list.retain(|m| m.name != mirror.name);
+        list.push(mirror);
+    }
+
+    /// Inserts `mirror` at `index` (clamped) in the list for `host`,
+    /// replacing any mirror with the same name.
+    pub fn insert_mirror(&self, host: &str, index: usize, mirror: Mirror) {
+        let mut hosts = self.hosts.write().unwrap();
+        let list = hosts.entry(normalize_host(host)).or_default();
+        list.retain(|m| m.name != mirror.name);
+        let index = index.min(list.len());
+        list.insert(index, mirror);
+    }
+
+    /// Removes the mirror called `name` from `host`.
+    ///
+    /// Returns `true` if a mirror was removed.
+    pub fn remove_mirror(&self, host: &str, name: &str) -> bool {
+        let mut hosts = self.hosts.write().unwrap();
+        let Some(list) = hosts.get_mut(&normalize_host(host)) else {
+            return false;
+        };
+        let before = list.len();
+        list.retain(|m| m.name != name);
+        before != list.len()
+    }
+
+    /// Removes every mirror registered for `host`.
+    pub fn clear_host(&self, host: &str) {
+        self.hosts.write().unwrap().remove(&normalize_host(host));
+    }
+
+    /// Enables or disables the mirror called `name` on `host`.
+    ///
+    /// Returns `true` if the mirror exists.
+    pub fn set_enabled(&self, host: &str, name: &str, enabled: bool) -> bool {
+        let mut hosts = self.hosts.write().unwrap();
+        let Some(mirror) = hosts
+            .get_mut(&normalize_host(host))
+            .and_then(|list| list.iter_mut().find(|m| m.name == name))
+        else {
+            return false;
+        };
+        mirror.enabled = enabled;
+        true
+    }
+
+    /// Enables or disables every mirror called `name`, on every host.
+    ///
+    /// Returns the number of mirrors affected.
+    pub fn set_enabled_everywhere(&self, name: &str, enabled: bool) -> usize {
+        let mut hosts = self.hosts.write().unwrap();
+        let mut count = 0;
+        for mirror in hosts.values_mut().flatten().filter(|m| m.name == name) {
+            mirror.enabled = enabled;
+            count += 1;
+        }
+        count
+    }
+
+    /// Reorders the mirrors of `host`: the names listed in `order` come
+    /// first (in that order), the remaining mirrors keep their relative
+    /// order after them. Unknown names are ignored.
+    pub fn reorder(&self, host: &str, order: &[&str]) {
+        let mut hosts = self.hosts.write().unwrap();
+        let Some(list) = hosts.get_mut(&normalize_host(host)) else {
+            return;
+        };
+        list.sort_by_key(|m| {
+            order
+                .iter()
+                .position(|name| *name == m.name)
+                .unwrap_or(order.len())
+        });
+    }
+
+    /// Returns the mirrors registered for `host`, in configured order.
+    pub fn mirrors(&self, host: &str) -> Vec<Mirror> {
+        self.hosts
+            .read()
+            .unwrap()
+            .get(&normalize_host(host))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the ordered download candidates for `original`.
+    ///
+    /// The original URL comes first, followed by the enabled mirrors for
+    /// its host. Sources currently demoted (too many failures, or much
+    /// slower than the fastest healthy candidate) are moved to the end,
+    /// best score first, so they remain a last resort.
+    pub fn candidates(&self, original: &str) -> Vec<String> {
+        let Ok(parsed) = Url::parse(original) else {
+            return vec![original.to_string()];
+        };
+
+        let host = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+        let path = parsed.path();
+
+        // (source key, candidate URL)
+        let mut sources = vec![(origin_key(&parsed), original.to_string())];
+        if let Some(list) = self.hosts.read().unwrap().get(&host) {
+            for mirror in list.iter().filter(|m| m.applies_to(path)) {
+                sources.push((mirror.base_url.clone(), join_base_and_path(&mirror.base_url, path)));
+            }
+        }
+
+        if sources.len() == 1 {
+            return sources.into_iter().map(|(_, url)| url).collect();
+        }
+
+        let health = self.health.lock().unwrap();
+        let stats: Vec<Option<&HealthStats>> = sources.iter().map(|(key, _)| health.get(key)).collect();
+
+        let fastest_ms = stats
+            .iter()
+            .flatten()
+            .filter(|s| s.failure_rate < MAX_FAILURE_RATE)
+            .filter_map(|s| s.latency_ms)
+            .fold(None, |min: Option<f64>, ms| Some(min.map_or(ms, |m| m.min(ms))));
+
+        let is_demoted = |s: &HealthStats| {
+            if s.samples < MIN_SAMPLES {
+                return false;
+            }
+            let failing = s.failure_rate >= MAX_FAILURE_RATE
+                && !HealthStats::is_recovering(s.last_failure);
+            // Slow sources are rarely tried once demoted, so the window
+            // runs from their last attempt
+            let too_slow = match (s.latency_ms, fastest_ms) {
+                (Some(ms), Some(best)) => {
+                    ms > best * SLOW_FACTOR && !HealthStats::is_recovering(s.last_attempt)
+                }
+                _ => false,
+            };
+            failing || too_slow
+        };
+
+        let (mut healthy, mut demoted): (Vec<_>, Vec<_>) = sources
+            .into_iter()
+            .zip(stats.iter().map(|s| s.map(|s| (is_demoted(s), s.score()))))
+            .partition(|(_, s)| !matches!(s, Some((true, _))));
+
+        drop(health);
+
+        demoted.sort_by(|(_, a), (_, b)| {
+            let a = a.map_or(1.0, |(_, score)| score);
+            let b = b.map_or(1.0, |(_, score)| score);
+            b.total_cmp(&a)
+        });
+        healthy.extend(demoted);
+
+        healthy.into_iter().map(|((_, url), _)| url).collect()
+    }
+
+    /// Records a successful attempt against the source serving `url`.
+    ///
+    /// `latency` is how long the attempt took to get a usable response.
+    pub fn record_success(&self, url: &str, latency: Duration) {
+        self.record(url, Some(latency));
+    }
+
+    /// Records a failed attempt (connect error, HTTP error status, broken
+    /// body) against the source serving `url`.
+    pub fn record_failure(&self, url: &str) {
+        self.record(url, None);
+    }
+
+    /// Returns the health snapshot of the source serving `url`, if any
+    /// attempt against it has been recorded.
+    pub fn health(&self, url: &str) -> Option<MirrorHealth> {
+        let key = self.source_key(url)?;
+        self.health.lock().unwrap().get(&key).map(HealthStats::snapshot)
+    }
+
+    /// Forgets every recorded health statistic.
+    pub fn reset_health(&self) {
+        self.health.lock().unwrap().clear();
+    }
+
+    fn record(&self, url: &str, latency: Option<Duration>) {
+        let Some(key) = self.source_key(url) else {
+            return;
+        };
+        self.health
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_default()
+            .record(latency);
+    }
+
+    /// Maps a candidate URL back to the source it was generated from:
+    /// the longest registered mirror base that prefixes it, otherwise the
+    /// URL's own `scheme://host[:port]`.
+    fn source_key(&self, url: &str) -> Option<String> {
+        let hosts = self.hosts.read().unwrap();
+        let mirror_base = hosts
+            .values()
+            .flatten()
+            .map(|m| m.base_url.as_str())
+            .filter(|base| {
+                url.strip_prefix(base)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|base| base.len());
+
+        if let Some(base) = mirror_base {
+            return Some(base.to_string());
+        }
+
+        Url::parse(url).ok().map(|parsed| origin_key(&parsed))
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim().to_ascii_lowercase()
+}
+
+fn origin_key(url: &Url) -> String {
+    url.origin().ascii_serialization()
+}
+
+fn env_base(var: &str) -> Option<String> {
+    env::var(var)
+        .ok()
+        .map(|value| value.trim().trim_end_matches('/').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn join_base_and_path(base: &str, path: &str) -> String {
+    if path.starts_with('/') {
+        format!("{}{}", base, path)
+    } else {
+        format!("{}/{}", base, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: &str = "https://libraries.example.net/org/lwjgl/lwjgl.jar";
+    const ORIGIN_BASE: &str = "https://libraries.example.net";
+    const FIRST: &str = "https://first.example.org/maven";
+    const SECOND: &str = "https://second.example.org/maven";
+
+    fn policy() -> MirrorPolicy {
+        let policy = MirrorPolicy::new();
+        policy.add_mirror("libraries.example.net", Mirror::new("first", FIRST));
+        policy.add_mirror("libraries.example.net", Mirror::new("second", SECOND));
+        policy
+    }
+
+    fn url(base: &str) -> String {
+        format!("{}/org/lwjgl/lwjgl.jar", base)
+    }
+
+    fn record(policy: &MirrorPolicy, base: &str, latency: Option<Duration>, times: u32) {
+        for _ in 0..times {
+            policy.record(&url(base), latency);
+        }
+    }
+
+    /// Moves the recorded timestamps of `base` back past the recovery window.
+    fn age(policy: &MirrorPolicy, base: &str) {
+        let past = Instant::now().checked_sub(RECOVERY_WINDOW + Duration::from_secs(1));
+        let mut health = policy.health.lock().unwrap();
+        let stats = health.get_mut(base).unwrap();
+        stats.last_failure = stats.last_failure.and(past);
+        stats.last_attempt = past;
+    }
+
+    #[test]
+    fn origin_then_mirrors_in_configured_order() {
+        let policy = policy();
+        assert_eq!(policy.candidates(ORIGIN), vec![url(ORIGIN_BASE), url(FIRST), url(SECOND)]);
+
+        policy.reorder("libraries.example.net", &["second"]);
+        assert_eq!(policy.candidates(ORIGIN), vec![url(ORIGIN_BASE), url(SECOND), url(FIRST)]);
+
+        policy.set_enabled("libraries.example.net", "second", false);
+        assert_eq!(policy.candidates(ORIGIN), vec![url(ORIGIN_BASE), url(FIRST)]);
+    }
+
+    #[test]
+    fn unrelated_host_has_no_mirrors() {
+        let policy = policy();
+        let other = "https://maven.example.com/a.jar";
+        assert_eq!(policy.candidates(other), vec![other.to_string()]);
+    }
+
+    #[test]
+    fn failing_source_is_demoted_after_min_samples() {
+        let policy = policy();
+        record(&policy, ORIGIN_BASE, None, MIN_SAMPLES - 1);
+        assert_eq!(policy.candidates(ORIGIN)[0], url(ORIGIN_BASE));
+
+        record(&policy, ORIGIN_BASE, None, 1);
+        assert_eq!(policy.candidates(ORIGIN), vec![url(FIRST), url(SECOND), url(ORIGIN_BASE)]);
+    }
+
+    #[test]
+    fn failing_source_recovers_after_window() {
+        let policy = policy();
+        record(&policy, ORIGIN_BASE, None, MIN_SAMPLES);
+        age(&policy, ORIGIN_BASE);
+        assert_eq!(policy.candidates(ORIGIN), vec![url(ORIGIN_BASE), url(FIRST), url(SECOND)]);
+    }
+
+    #[test]
+    fn slow_source_is_demoted() {
+        let policy = policy();
+        record(&policy, ORIGIN_BASE, Some(Duration::from_millis(2000)), MIN_SAMPLES);
+        record(&policy, FIRST, Some(Duration::from_millis(100)), MIN_SAMPLES);
+        assert_eq!(policy.candidates(ORIGIN), vec![url(FIRST), url(SECOND), url(ORIGIN_BASE)]);
+    }
+
+    #[test]
+    fn slow_source_recovers_after_window() {
+        let policy = policy();
+        record(&policy, ORIGIN_BASE, Some(Duration::from_millis(2000)), MIN_SAMPLES);
+        record(&policy, FIRST, Some(Duration::from_millis(100)), MIN_SAMPLES);
+        age(&policy, ORIGIN_BASE);
+        assert_eq!(policy.candidates(ORIGIN), vec![url(ORIGIN_BASE), url(FIRST), url(SECOND)]);
+    }
+
+    #[test]
+    fn demoted_sources_are_ordered_by_score() {
+        let policy = policy();
+        record(&policy, ORIGIN_BASE, None, MIN_SAMPLES);
+        record(&policy, FIRST, None, MIN_SAMPLES + 2);
+        record(&policy, FIRST, Some(Duration::from_millis(100)), 1);
+        // first: failure rate 0.8, origin: 1.0
+        assert_eq!(policy.candidates(ORIGIN), vec![url(SECOND), url(FIRST), url(ORIGIN_BASE)]);
+    }
+
+    #[test]
+    fn health_is_tracked_per_source() {
+        let policy = policy();
+        record(&policy, FIRST, Some(Duration::from_millis(100)), 1);
+        record(&policy, FIRST, None, 1);
+
+        let health = policy.health(&url(FIRST)).unwrap();
+        assert_eq!(health.samples, 2);
+        assert!((health.failure_rate - EWMA_ALPHA).abs() < f64::EPSILON);
+        assert!(policy.health(&url(SECOND)).is_none());
+    }
+}
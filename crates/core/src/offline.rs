@@ -0,0 +1,61 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Process-wide offline switch.
+//!
+//! When offline mode is on, every subsystem works from on-disk state only:
+//! metadata comes from the caches, the installer refuses to download, the
+//! JRE provider APIs are never queried and the mod resolver replays its last
+//! resolution. Anything that would need the network fails fast with
+//! [`OfflineUnavailable`] naming the missing resource.
+//!
+//! The switch starts from the `LIGHTY_OFFLINE` environment variable (`1` /
+//! `true`) and can be flipped at any time with [`set_offline`].
+//!
+//! ```no_run
+//! use lighty_core::offline;
+//!
+//! offline::set_offline(true);
+//! assert!(offline::is_offline());
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::Lazy;
+
+pub use crate::errors::OfflineUnavailable;
+
+/// Environment variable that enables offline mode at startup.
+pub const ENV_OFFLINE: &str = "LIGHTY_OFFLINE";
+
+static OFFLINE: Lazy<AtomicBool> = Lazy::new(|| {
+    let enabled = std::env::var(ENV_OFFLINE)
+        .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    AtomicBool::new(enabled)
+});
+
+/// Turns offline mode on or off for the whole process.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether offline mode is on.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fails with [`OfflineUnavailable`] for `resource` when offline mode is on.
+///
+/// `resource` is only evaluated in offline mode, so callers can format
+/// freely.
+pub fn ensure_online<F>(resource: F) -> Result<(), OfflineUnavailable>
+where
+    F: FnOnce() -> String,
+{
+    if is_offline() {
+        Err(OfflineUnavailable::new(resource()))
+    } else {
+        Ok(())
+    }
+}
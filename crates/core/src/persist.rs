@@ -0,0 +1,65 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Crash-safe file writes.
+//!
+//! [`write_atomic`] writes the bytes to a temporary file next to the
+//! target, then renames it over the target: readers see the old file or
+//! the new one, never a torn one. The temporary name is unique per call,
+//! so two writers (threads or processes) never share one; the last rename
+//! wins.
+//!
+//! Renaming replaces the directory entry, not the file: a hardlink at the
+//! target keeps its old content.
+//!
+//! ```rust,ignore
+//! use lighty_core::persist::write_atomic;
+//!
+//! write_atomic(&game_dir.join("history.json"), &serde_json::to_vec(&history)?).await?;
+//! ```
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Calls in this process, part of each temporary name.
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `bytes` to `path` through a temporary file and a rename.
+/// The parent directory must exist.
+pub async fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = temp_path(path);
+    let result = async {
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, path).await
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
+    result
+}
+
+/// Blocking [`write_atomic`].
+pub fn write_atomic_sync(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = temp_path(path);
+    let result = std::fs::write(&tmp, bytes).and_then(|()| std::fs::rename(&tmp, path));
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// `<name>.<pid>-<n>.tmp` in the directory of `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
@@ -0,0 +1,311 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Process-wide download scheduler.
+//!
+//! Every transfer started by [`download_to_path`](crate::download::download_to_path)
+//! (and therefore every installer, JRE and loader download) takes a slot
+//! from the [global](DownloadScheduler::global) scheduler for the mirror
+//! candidate it is about to contact, and feeds each received chunk through
+//! its bandwidth limiter. Concurrent installs therefore share one set of
+//! limits instead of each bringing their own:
+//!
+//! - a global cap on simultaneous transfers,
+//! - a per-host connection cap,
+//! - a bytes-per-second limit (token bucket, one second of burst).
+//!
+//! Waiting transfers are started by [`DownloadPriority`], then in arrival
+//! order. The priority of a download is taken from the surrounding
+//! [`with_priority`] scope, so callers don't have to thread it through
+//! every download helper.
+//!
+//! Limits can be changed at any time with [`DownloadScheduler::set_limits`];
+//! transfers already running keep their slot, queued ones see the new
+//! limits immediately.
+//!
+//! ```rust,ignore
+//! use lighty_core::scheduler::{DownloadScheduler, SchedulerLimits};
+//!
+//! // Metered link: 2 MiB/s, at most 4 connections per host
+//! DownloadScheduler::global().set_limits(SchedulerLimits {
+//!     max_bytes_per_second: Some(2 * 1024 * 1024),
+//!     max_connections_per_host: 4,
+//!     ..SchedulerLimits::default()
+//! });
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use tokio::sync::oneshot;
+
+static GLOBAL_SCHEDULER: Lazy<DownloadScheduler> = Lazy::new(DownloadScheduler::new);
+
+tokio::task_local! {
+    static PRIORITY: DownloadPriority;
+}
+
+/// Order in which queued downloads are started.
+///
+/// Declared from most to least urgent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DownloadPriority {
+    /// Needed before anything else can happen (client JAR, libraries, natives).
+    High,
+    /// Default for downloads outside a [`with_priority`] scope.
+    #[default]
+    Normal,
+    /// Bulk content that can trail behind (assets).
+    Low,
+}
+
+/// Runs `fut` with every download it starts scheduled at `priority`.
+pub async fn with_priority<F: Future>(priority: DownloadPriority, fut: F) -> F::Output {
+    PRIORITY.scope(priority, fut).await
+}
+
+/// Priority of the current [`with_priority`] scope, [`DownloadPriority::Normal`] outside one.
+pub fn current_priority() -> DownloadPriority {
+    PRIORITY.try_with(|priority| *priority).unwrap_or_default()
+}
+
+/// Limits enforced by the [`DownloadScheduler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchedulerLimits {
+    /// Maximum transfers running at once, all hosts combined (default: 50).
+    pub max_concurrent_downloads: usize,
+    /// Maximum transfers running at once against a single host; `0` means
+    /// no per-host cap (default: 0).
+    pub max_connections_per_host: usize,
+    /// Total download bandwidth; `None` means unlimited (default: `None`).
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl Default for SchedulerLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_downloads: 50,
+            max_connections_per_host: 0,
+            max_bytes_per_second: None,
+        }
+    }
+}
+
+struct Waiter {
+    host: String,
+    grant: oneshot::Sender<DownloadSlot>,
+}
+
+struct SlotState {
+    limits: SchedulerLimits,
+    active: usize,
+    per_host: HashMap<String, usize>,
+    /// Keyed by `(priority, arrival)`, so iteration yields the next waiter to start.
+    queue: BTreeMap<(DownloadPriority, u64), Waiter>,
+    next_seq: u64,
+}
+
+impl SlotState {
+    fn has_room(&self, host: &str) -> bool {
+        let limits = &self.limits;
+        self.active < limits.max_concurrent_downloads.max(1)
+            && (limits.max_connections_per_host == 0
+                || self.per_host.get(host).copied().unwrap_or(0) < limits.max_connections_per_host)
+    }
+
+    fn take(&mut self, host: &str) {
+        self.active += 1;
+        *self.per_host.entry(host.to_string()).or_default() += 1;
+    }
+
+    fn give_back(&mut self, host: &str) {
+        self.active = self.active.saturating_sub(1);
+        if let Some(count) = self.per_host.get_mut(host) {
+            *count -= 1;
+            if *count == 0 {
+                self.per_host.remove(host);
+            }
+        }
+    }
+
+    /// Starts every queued waiter that fits, most urgent first. A waiter
+    /// whose host is at its cap doesn't hold back waiters for other hosts.
+    fn dispatch(&mut self) {
+        let keys: Vec<_> = self.queue.keys().copied().collect();
+        for key in keys {
+            if self.active >= self.limits.max_concurrent_downloads.max(1) {
+                break;
+            }
+            if !self.has_room(&self.queue[&key].host) {
+                continue;
+            }
+
+            let waiter = self.queue.remove(&key).expect("key collected above");
+            if waiter.grant.is_closed() {
+                continue;
+            }
+            self.take(&waiter.host);
+            let slot = DownloadSlot { host: Some(waiter.host) };
+            if let Err(mut slot) = waiter.grant.send(slot) {
+                // Receiver dropped in between: reclaim without re-locking
+                if let Some(host) = slot.host.take() {
+                    self.give_back(&host);
+                }
+            }
+        }
+    }
+}
+
+struct Bucket {
+    /// Bytes that may be sent right away; negative while reservations are pending.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Shared transfer slots and bandwidth limiter, reached through [`Self::global`].
+pub struct DownloadScheduler {
+    slots: Mutex<SlotState>,
+    bucket: Mutex<Bucket>,
+}
+
+impl DownloadScheduler {
+    fn new() -> Self {
+        Self {
+            slots: Mutex::new(SlotState {
+                limits: SchedulerLimits::default(),
+                active: 0,
+                per_host: HashMap::new(),
+                queue: BTreeMap::new(),
+                next_seq: 0,
+            }),
+            bucket: Mutex::new(Bucket { tokens: 0.0, refilled_at: Instant::now() }),
+        }
+    }
+
+    /// Process-wide scheduler used by every download.
+    pub fn global() -> &'static DownloadScheduler {
+        &GLOBAL_SCHEDULER
+    }
+
+    /// Current limits.
+    pub fn limits(&self) -> SchedulerLimits {
+        self.lock_slots().limits
+    }
+
+    /// Replaces the limits. Queued transfers are re-evaluated at once, so
+    /// raising a cap starts waiting downloads immediately.
+    pub fn set_limits(&self, limits: SchedulerLimits) {
+        let mut slots = self.lock_slots();
+        if slots.limits.max_bytes_per_second != limits.max_bytes_per_second {
+            let mut bucket = self.lock_bucket();
+            bucket.tokens = 0.0;
+            bucket.refilled_at = Instant::now();
+        }
+        slots.limits = limits;
+        slots.dispatch();
+    }
+
+    /// Number of transfers currently holding a slot.
+    pub fn active_downloads(&self) -> usize {
+        self.lock_slots().active
+    }
+
+    /// Number of transfers waiting for a slot.
+    pub fn queued_downloads(&self) -> usize {
+        self.lock_slots().queue.len()
+    }
+
+    /// Waits for a slot to download `url` at the [current](current_priority) priority.
+    pub async fn acquire(&self, url: &str) -> DownloadSlot {
+        self.acquire_with_priority(url, current_priority()).await
+    }
+
+    /// Waits for a slot to download `url` at `priority`. The slot is
+    /// released when the returned guard is dropped.
+    pub async fn acquire_with_priority(&self, url: &str, priority: DownloadPriority) -> DownloadSlot {
+        let host = host_of(url);
+
+        let receiver = {
+            let mut slots = self.lock_slots();
+            if slots.queue.is_empty() && slots.has_room(&host) {
+                slots.take(&host);
+                return DownloadSlot { host: Some(host) };
+            }
+
+            let (grant, receiver) = oneshot::channel();
+            let seq = slots.next_seq;
+            slots.next_seq += 1;
+            slots.queue.insert((priority, seq), Waiter { host, grant });
+            // A waiter for a capped host may be queued ahead of room for this one
+            slots.dispatch();
+            receiver
+        };
+
+        receiver.await.expect("scheduler never drops queued waiters")
+    }
+
+    /// Waits until `bytes` fit in the bandwidth budget. Returns at once
+    /// when no limit is set.
+    pub async fn throttle(&self, bytes: usize) {
+        let delay = {
+            let Some(rate) = self.limits().max_bytes_per_second.filter(|rate| *rate > 0) else {
+                return;
+            };
+            let rate = rate as f64;
+
+            let mut bucket = self.lock_bucket();
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+            bucket.refilled_at = now;
+            // Reserve now, pay later: concurrent callers queue up behind the debt
+            bucket.tokens -= bytes as f64;
+
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        };
+
+        tokio::time::sleep(delay).await;
+    }
+
+    fn release(&self, host: &str) {
+        let mut slots = self.lock_slots();
+        slots.give_back(host);
+        slots.dispatch();
+    }
+
+    fn lock_slots(&self) -> MutexGuard<'_, SlotState> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_bucket(&self) -> MutexGuard<'_, Bucket> {
+        self.bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A running transfer's slot; released on drop.
+#[must_use = "the slot is released as soon as it is dropped"]
+pub struct DownloadSlot {
+    host: Option<String>,
+}
+
+impl Drop for DownloadSlot {
+    fn drop(&mut self) {
+        if let Some(host) = self.host.take() {
+            GLOBAL_SCHEDULER.release(&host);
+        }
+    }
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default()
+}
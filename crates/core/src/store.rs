@@ -0,0 +1,227 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Content-addressed file store shared by every instance.
+//!
+//! Without a store, each instance keeps its own `libraries/` and
+//! `assets/` under its game directory, so ten instances of the same
+//! version hold ten copies of the same files. Once a [`SharedStore`] is
+//! [enabled](enable_shared_store):
+//!
+//! - every download with a known hash lands once in `objects/`, named by
+//!   that hash (`objects/<algorithm>/<ab>/<abcdef…>`);
+//! - the file is then linked at its usual place in the shared `libraries/`
+//!   and `assets/` trees of the store, or copied into the instance itself
+//!   for the client JAR and mods, which mod managers and patchers may
+//!   rewrite in place;
+//! - `${library_directory}` and `${assets_root}` point at the store's
+//!   trees instead of the instance's.
+//!
+//! Links and copies are made with the cheapest method the filesystem
+//! supports, see [`LinkMethod`].
+//!
+//! ```rust,ignore
+//! use lighty_core::store::{enable_shared_store, SharedStore};
+//!
+//! AppState::init("MyLauncher")?;
+//! // <data_dir>/store
+//! enable_shared_store(SharedStore::in_data_dir());
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use path_absolutize::Absolutize;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::download::PathLocks;
+use crate::hash::ExpectedHash;
+use crate::AppState;
+
+static SHARED_STORE: Lazy<RwLock<Option<SharedStore>>> = Lazy::new(|| RwLock::new(None));
+
+/// Objects and generated files being written, by path.
+static PATH_LOCKS: Lazy<PathLocks> = Lazy::new(PathLocks::default);
+
+/// Enables the shared store for every install and launch that follows.
+pub fn enable_shared_store(store: SharedStore) {
+    *SHARED_STORE.write().unwrap_or_else(|e| e.into_inner()) = Some(store);
+}
+
+/// Goes back to per-instance `libraries/` and `assets/`. Files already in
+/// the store are left untouched.
+pub fn disable_shared_store() {
+    *SHARED_STORE.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// The active store, if any.
+pub fn shared_store() -> Option<SharedStore> {
+    SHARED_STORE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// How a store object was made visible at its destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkMethod {
+    /// Same inode; tried first inside the store. Requires the same volume.
+    Hardlink,
+    /// Copy-on-write clone (Btrfs, XFS, APFS, ReFS); tried first for
+    /// instance files.
+    Reflink,
+    /// Symbolic link to the object; may need privileges on Windows.
+    /// Only used inside the store.
+    Symlink,
+    /// Plain copy, when nothing else is possible.
+    Copy,
+}
+
+/// Location of a shared store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedStore {
+    root: PathBuf,
+}
+
+impl SharedStore {
+    /// Store rooted at `root`. A relative root is resolved against the
+    /// current directory, so symlinked objects stay valid.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let root = root.absolutize().map(|path| path.into_owned()).unwrap_or(root);
+        Self { root }
+    }
+
+    /// Store at `<data_dir>/store`.
+    ///
+    /// Panics if [`AppState`] has not been initialized.
+    pub fn in_data_dir() -> Self {
+        Self::new(AppState::data_dir().join("store"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Shared Maven tree, used as `${library_directory}`.
+    pub fn libraries_dir(&self) -> PathBuf {
+        self.root.join("libraries")
+    }
+
+    /// Shared assets tree (`indexes/`, `objects/`), used as `${assets_root}`.
+    pub fn assets_dir(&self) -> PathBuf {
+        self.root.join("assets")
+    }
+
+    /// Content-addressed objects.
+    pub fn objects_dir(&self) -> PathBuf {
+        self.root.join("objects")
+    }
+
+    /// Where the object with `hash` is (or will be) stored.
+    pub fn object_path(&self, hash: &ExpectedHash) -> PathBuf {
+        let hex = hash.hex.to_ascii_lowercase();
+        let prefix = hex.get(..2).unwrap_or("00");
+        self.objects_dir()
+            .join(hash.algorithm.name().to_ascii_lowercase().replace('-', ""))
+            .join(prefix)
+            .join(&hex)
+    }
+
+    /// Whether the object with `hash` is already stored. Objects are only
+    /// written once verified, so presence implies integrity.
+    pub fn contains(&self, hash: &ExpectedHash) -> bool {
+        fs::metadata(self.object_path(hash)).is_ok_and(|meta| meta.len() > 0)
+    }
+
+    /// Makes the stored object with `hash` visible at `dest`, replacing
+    /// whatever is there. Blocking.
+    ///
+    /// Inside the store's own trees the object is shared ([`link_file`]).
+    /// Anywhere else, i.e. in an instance, `dest` gets its own copy
+    /// ([`copy_file`]): a write to it must never reach the object.
+    pub fn link(&self, hash: &ExpectedHash, dest: &Path) -> io::Result<LinkMethod> {
+        if self.shares(dest) {
+            link_file(&self.object_path(hash), dest)
+        } else {
+            copy_file(&self.object_path(hash), dest)
+        }
+    }
+
+    /// Whether [`Self::link`] shares the object with `dest` instead of
+    /// copying it, so damage to `dest` is damage to the object.
+    pub fn shares(&self, dest: &Path) -> bool {
+        dest.starts_with(&self.root)
+    }
+
+    /// Holds the object with `hash` until the guard is dropped, so
+    /// concurrent installs check and download it one at a time.
+    pub async fn lock_object(&self, hash: &ExpectedHash) -> OwnedMutexGuard<()> {
+        lock_path(&self.object_path(hash)).await
+    }
+}
+
+/// Holds `path` until the guard is dropped, so concurrent installs
+/// generating the same file (e.g. Forge processor outputs in the store's
+/// `libraries/` tree) write it one at a time.
+pub async fn lock_path(path: &Path) -> OwnedMutexGuard<()> {
+    PATH_LOCKS.lock(path).await
+}
+
+/// Links `object` at `dest`, trying each [`LinkMethod`] in order. Blocking.
+///
+/// `dest` shares the object's content: only for files nothing writes to.
+pub fn link_file(object: &Path, dest: &Path) -> io::Result<LinkMethod> {
+    clear_dest(dest)?;
+
+    if fs::hard_link(object, dest).is_ok() {
+        return Ok(LinkMethod::Hardlink);
+    }
+    if reflink_copy::reflink(object, dest).is_ok() {
+        return Ok(LinkMethod::Reflink);
+    }
+    if symlink(object, dest).is_ok() {
+        return Ok(LinkMethod::Symlink);
+    }
+    fs::copy(object, dest)?;
+    Ok(LinkMethod::Copy)
+}
+
+/// Copies `object` to `dest` as a file of its own, reflinked when the
+/// filesystem can. Blocking.
+pub fn copy_file(object: &Path, dest: &Path) -> io::Result<LinkMethod> {
+    clear_dest(dest)?;
+
+    if reflink_copy::reflink(object, dest).is_ok() {
+        return Ok(LinkMethod::Reflink);
+    }
+    fs::copy(object, dest)?;
+    Ok(LinkMethod::Copy)
+}
+
+/// Creates the parent of `dest` and removes whatever is at `dest`.
+fn clear_dest(dest: &Path) -> io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::symlink_metadata(dest) {
+        Ok(_) => fs::remove_file(dest),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(unix)]
+fn symlink(object: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(object, dest)
+}
+
+#[cfg(windows)]
+fn symlink(object: &Path, dest: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(object, dest)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_object: &Path, _dest: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "symlinks not supported"))
+}
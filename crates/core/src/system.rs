@@ -8,6 +8,7 @@
 
 use crate::errors::{SystemError, SystemResult};
 use serde::Deserialize;
+use once_cell::sync::Lazy;
 use std::fmt::Display;
 
 /// Current operating system, resolved at compile time.
@@ -200,3 +201,14 @@ impl Display for Architecture {
         }
     }
 }
+
+/// Version of the running OS (e.g. `"10.0.22631"` on Windows 11,
+/// `"14.4.1"` on macOS, the distribution release on Linux), as matched by the
+/// `os.version` regex of Mojang's argument rules. Empty when unknown.
+pub fn os_version() -> &'static str {
+    static VERSION: Lazy<String> = Lazy::new(|| match os_info::get().version() {
+        os_info::Version::Unknown => String::new(),
+        version => version.to_string(),
+    });
+    &VERSION
+}
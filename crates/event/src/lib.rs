@@ -55,9 +55,10 @@ pub use errors::{
     EventTryReceiveError, EventTryReceiveResult,
 };
 pub use module::{
-    AuthEvent, ConsoleOutputEvent, ConsoleStream, CoreEvent, InstanceDeletedEvent,
-    InstanceExitedEvent, InstanceLaunchedEvent, InstanceWindowAppearedEvent, JavaEvent,
-    LaunchEvent, LoaderEvent,
+    AuthEvent, ConsoleOutputEvent, ConsoleStream, CoreEvent, CrashCategory, GameLogEvent, GameLogLevel,
+    InstanceCrashedEvent, InstanceDeletedEvent,
+    InstanceExitedEvent, InstanceLaunchedEvent, InstanceReadyEvent, InstanceShutdownEvent, InstanceWindowAppearedEvent,
+    JavaEvent, LaunchEvent, LoaderEvent, ShutdownStage,
 };
 
 /// Event bus for broadcasting events to multiple listeners
@@ -143,8 +144,12 @@ pub enum Event {
     Core(CoreEvent),
     InstanceLaunched(InstanceLaunchedEvent),
     InstanceWindowAppeared(InstanceWindowAppearedEvent),
+    InstanceReady(InstanceReadyEvent),
+    InstanceShutdown(InstanceShutdownEvent),
     InstanceExited(InstanceExitedEvent),
+    InstanceCrashed(InstanceCrashedEvent),
     ConsoleOutput(ConsoleOutputEvent),
+    GameLog(GameLogEvent),
     InstanceDeleted(InstanceDeletedEvent),
 }
 
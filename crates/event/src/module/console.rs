@@ -31,6 +31,26 @@ pub struct InstanceWindowAppearedEvent {
     pub timestamp: SystemTime,
 }
 
+/// Event emitted when a game instance printed a "game ready" marker, or
+/// when none showed up before the timeout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceReadyEvent {
+    /// Process ID of the instance
+    pub pid: u32,
+    /// Name of the instance
+    pub instance_name: String,
+    /// Console line that matched (None if timed out)
+    pub marker: Option<String>,
+    /// Time since launch
+    pub elapsed: std::time::Duration,
+    /// Whether the timeout expired first; a later marker emits this event
+    /// again with `timed_out: false`
+    pub timed_out: bool,
+    /// Timestamp when readiness was detected
+    #[serde(with = "system_time_serializer")]
+    pub timestamp: SystemTime,
+}
+
 /// Event emitted when a game instance exits
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceExitedEvent {
@@ -45,6 +65,57 @@ pub struct InstanceExitedEvent {
     pub timestamp: SystemTime,
 }
 
+/// Event emitted after [`InstanceExitedEvent`] when the instance exited
+/// abnormally without being asked to stop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceCrashedEvent {
+    /// Process ID of the crashed instance
+    pub pid: u32,
+    /// Name of the instance
+    pub instance_name: String,
+    /// Exit code (None if terminated by a signal)
+    pub exit_code: Option<i32>,
+    /// What made the game crash
+    pub category: CrashCategory,
+    /// Crash report or hs_err_pid*.log the game wrote, if any
+    pub report_path: Option<std::path::PathBuf>,
+    /// One-line summary (exception, crashing native frame or last error)
+    pub summary: String,
+    /// Mod IDs or mixin configs the crash points at
+    pub suspected_mods: Vec<String>,
+    /// What the player can try
+    pub suggested_fix: String,
+    /// Timestamp when the crash was analyzed
+    #[serde(with = "system_time_serializer")]
+    pub timestamp: SystemTime,
+}
+
+/// Event emitted at each stage of closing an instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceShutdownEvent {
+    /// Process ID of the instance
+    pub pid: u32,
+    /// Name of the instance
+    pub instance_name: String,
+    /// Stage just entered
+    pub stage: ShutdownStage,
+    /// Time left to the process before it is killed
+    pub grace_period: std::time::Duration,
+    /// Timestamp when the stage started
+    #[serde(with = "system_time_serializer")]
+    pub timestamp: SystemTime,
+}
+
+/// Shutdown escalation stage
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ShutdownStage {
+    /// Graceful stop requested (SIGTERM, or `taskkill` without `/F`)
+    Terminate,
+    /// Grace period expired; the process group is force-killed
+    Kill,
+}
+
 /// Event emitted for each line of console output
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsoleOutputEvent {
@@ -61,6 +132,30 @@ pub struct ConsoleOutputEvent {
     pub timestamp: SystemTime,
 }
 
+/// Event emitted for each structured log record of the game (versions
+/// started with a log4j XML configuration); the raw lines still arrive
+/// as [`ConsoleOutputEvent`]s
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameLogEvent {
+    /// Process ID of the instance
+    pub pid: u32,
+    /// Name of the instance
+    pub instance_name: String,
+    /// Severity
+    pub level: GameLogLevel,
+    /// Thread name (e.g., "Render thread")
+    pub thread: String,
+    /// Logger name (e.g., "net.minecraft.client.Minecraft")
+    pub logger: String,
+    /// Log message
+    pub message: String,
+    /// Exception and stack trace, if any
+    pub throwable: Option<String>,
+    /// Timestamp when the game logged the record
+    #[serde(with = "system_time_serializer")]
+    pub timestamp: SystemTime,
+}
+
 /// Event emitted when an instance is deleted
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceDeletedEvent {
@@ -81,6 +176,40 @@ pub enum ConsoleStream {
     Stderr,
 }
 
+/// Crash classification
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCategory {
+    /// Java heap or native memory exhausted
+    OutOfMemory,
+    /// A mod needs another mod that is not installed
+    MissingModDependency,
+    /// The same mod is installed twice
+    DuplicateMod,
+    /// A mod failed to patch the game (Mixin)
+    MixinFailure,
+    /// The game or a mod was built for a newer Java version
+    IncompatibleJava,
+    /// The graphics driver crashed or offers no usable OpenGL
+    GpuDriver,
+    /// The Java runtime itself crashed
+    JvmCrash,
+    /// Unclassified
+    Unknown,
+}
+
+/// Severity of a game log record
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum GameLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
 /// Module for serializing/deserializing SystemTime
 mod system_time_serializer {
     use serde::{Deserialize, Deserializer, Serializer};
@@ -16,6 +16,7 @@ pub use java::JavaEvent;
 pub use launch::LaunchEvent;
 pub use loader::LoaderEvent;
 pub use console::{
-    ConsoleOutputEvent, ConsoleStream, InstanceDeletedEvent, InstanceExitedEvent,
-    InstanceLaunchedEvent, InstanceWindowAppearedEvent,
+    ConsoleOutputEvent, ConsoleStream, CrashCategory, GameLogEvent, GameLogLevel, InstanceCrashedEvent,
+    InstanceDeletedEvent, InstanceExitedEvent,
+    InstanceLaunchedEvent, InstanceReadyEvent, InstanceShutdownEvent, InstanceWindowAppearedEvent, ShutdownStage,
 };
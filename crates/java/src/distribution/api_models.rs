@@ -8,15 +8,45 @@
 
 use serde::Deserialize;
 
+/// Adoptium assets API response item
+/// Only deserializes the binary package
+#[derive(Debug, Deserialize)]
+pub(super) struct AdoptiumAsset {
+    pub binary: AdoptiumBinary,
+    // Other asset fields are ignored
+}
+
+/// Binary object in an Adoptium asset
+#[derive(Debug, Deserialize)]
+pub(super) struct AdoptiumBinary {
+    pub package: AdoptiumPackage,
+    // Installers and other fields are ignored
+}
+
+/// Archive of an Adoptium binary
+/// Only deserializes its SHA-256
+#[derive(Debug, Deserialize)]
+pub(super) struct AdoptiumPackage {
+    pub checksum: Option<String>,
+}
+
 /// Zulu API response structure
-/// Only deserializes the download_url and name fields, ignoring all other fields
+/// Only deserializes the download_url, name and package_uuid fields, ignoring all other fields
 #[derive(Debug, Deserialize)]
 pub(super) struct ZuluPackage {
     pub download_url: String,
     pub name: String,
+    pub package_uuid: String,
     // All other fields are ignored automatically by serde
 }
 
+/// Zulu package details structure
+/// Only deserializes the archive's SHA-256
+#[derive(Debug, Deserialize)]
+pub(super) struct ZuluPackageDetails {
+    pub sha256_hash: Option<String>,
+}
+
 /// Foojay API response structure (for Liberica)
 /// Only deserializes the result array
 #[derive(Debug, Deserialize)]
@@ -34,9 +64,26 @@ pub(super) struct FoojayPackage {
 }
 
 /// Links object in Foojay package
-/// Only deserializes the download redirect URL
+/// Only deserializes the download redirect and package info URLs
 #[derive(Debug, Deserialize)]
 pub(super) struct FoojayLinks {
     pub pkg_download_redirect: String,
+    pub pkg_info_uri: String,
     // Other link fields are ignored
 }
+
+/// Foojay package info response structure
+#[derive(Debug, Deserialize)]
+pub(super) struct FoojayInfoResponse {
+    pub result: Vec<FoojayPackageInfo>,
+}
+
+/// Package info in Foojay response
+/// Only deserializes the checksum and its algorithm (e.g. `"sha256"`)
+#[derive(Debug, Deserialize)]
+pub(super) struct FoojayPackageInfo {
+    #[serde(default)]
+    pub checksum: String,
+    #[serde(default)]
+    pub checksum_type: String,
+}
@@ -9,17 +9,43 @@
 mod api_models;
 mod providers;
 
+use lighty_core::{ExpectedHash, HashAlgorithm};
+
 use crate::errors::DistributionResult;
 use crate::JavaDistribution;
 
-/// Gets download URL for the distribution
+/// Runtime archive of a distribution, as published by its provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JavaPackage {
+    /// Download URL of the archive
+    pub url: String,
+    /// Checksum the provider publishes for the archive, if any
+    pub checksum: Option<ExpectedHash>,
+}
+
+/// Gets the runtime archive for the distribution
 ///
-/// Queries the respective API or builds direct download URLs for each distribution.
-pub(crate) async fn get_download_url(distribution: &JavaDistribution, jre_version: &u8) -> DistributionResult<String> {
+/// Queries the respective API, or builds direct download URLs for each distribution.
+pub(crate) async fn get_package(distribution: &JavaDistribution, jre_version: &u8) -> DistributionResult<JavaPackage> {
     match distribution {
-        JavaDistribution::Temurin => providers::build_temurin_url(jre_version),
-        JavaDistribution::GraalVM => providers::build_graalvm_url(jre_version),
-        JavaDistribution::Zulu => providers::build_zulu_url(jre_version).await,
-        JavaDistribution::Liberica => providers::build_liberica_url(jre_version).await,
+        JavaDistribution::Temurin => providers::fetch_temurin_package(jre_version).await,
+        JavaDistribution::GraalVM => providers::fetch_graalvm_package(jre_version).await,
+        JavaDistribution::Zulu => providers::fetch_zulu_package(jre_version).await,
+        JavaDistribution::Liberica => providers::fetch_liberica_package(jre_version).await,
     }
 }
+
+/// Builds the expected hash from a provider's checksum and algorithm name
+///
+/// Returns `None` for an empty checksum or an algorithm the launcher
+/// cannot verify.
+fn published_checksum(algorithm: &str, hex: &str) -> Option<ExpectedHash> {
+    let algorithm = match algorithm.to_ascii_lowercase().replace('-', "").as_str() {
+        "sha1" => HashAlgorithm::Sha1,
+        "sha256" => HashAlgorithm::Sha256,
+        "sha512" => HashAlgorithm::Sha512,
+        _ => return None,
+    };
+    let hex = hex.trim();
+    (hex.len() == algorithm.hex_len()).then(|| ExpectedHash::new(algorithm, hex))
+}
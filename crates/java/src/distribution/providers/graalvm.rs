@@ -7,13 +7,16 @@
 //! Note: Oracle only provides JDK for GraalVM, no JRE available
 
 use crate::errors::{DistributionError, DistributionResult};
+use crate::distribution::{published_checksum, JavaPackage};
 use lighty_core::system::{ARCHITECTURE, OS};
+use lighty_core::hosts::http_client;
 
-/// Builds GraalVM download URL
+/// Fetches the GraalVM package
 ///
 /// Note: Only JDK is available from Oracle, no separate JRE distribution
-/// Supports Java 17+ only
-pub fn build_graalvm_url(version: &u8) -> DistributionResult<String> {
+/// Supports Java 17+ only. Oracle publishes the SHA-256 at `<url>.sha256`,
+/// used when it can be fetched.
+pub async fn fetch_graalvm_package(version: &u8) -> DistributionResult<JavaPackage> {
     let os_name = OS.get_graal_name()?;
     let arch = ARCHITECTURE.get_simple_name()?;
     let archive_type = OS.get_archive_type()?;
@@ -35,5 +38,25 @@ pub fn build_graalvm_url(version: &u8) -> DistributionResult<String> {
         });
     };
 
-    Ok(url)
+    // Best effort: without the checksum file the archive is still usable
+    let checksum = async {
+        http_client()
+            .get(format!("{}.sha256", url))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+    .await
+    .ok();
+
+    Ok(JavaPackage {
+        // The file may hold "<hash>  <file name>"
+        checksum: checksum
+            .as_deref()
+            .and_then(|text| text.split_whitespace().next())
+            .and_then(|hex| published_checksum("sha256", hex)),
+        url,
+    })
 }
@@ -6,14 +6,16 @@
 //! Uses Foojay API: https://api.foojay.io/
 
 use crate::errors::{DistributionError, DistributionResult};
-use crate::distribution::api_models::FoojayResponse;
+use crate::distribution::api_models::{FoojayInfoResponse, FoojayResponse};
+use crate::distribution::{published_checksum, JavaPackage};
 use lighty_core::system::{ARCHITECTURE, OS};
-use lighty_core::hosts::HTTP_CLIENT;
+use lighty_core::hosts::http_client;
 
-/// Builds BellSoft Liberica download URL using Foojay API
+/// Fetches the BellSoft Liberica JRE package using Foojay API
 ///
-/// Queries the Foojay API to get the latest Liberica JRE package
-pub async fn build_liberica_url(version: &u8) -> DistributionResult<String> {
+/// Queries the Foojay API to get the latest Liberica JRE package, then
+/// its package info for the archive's checksum
+pub async fn fetch_liberica_package(version: &u8) -> DistributionResult<JavaPackage> {
     let os_name = OS.get_zulu_name()?;
     let arch = ARCHITECTURE.get_simple_name()?;
     let ext = OS.get_archive_type()?;
@@ -23,9 +25,8 @@ pub async fn build_liberica_url(version: &u8) -> DistributionResult<String> {
         version, os_name, arch, ext
     );
 
-    let response = HTTP_CLIENT
+    let response = http_client()
         .get(&api_url)
-        .header("User-Agent", "Lighty-Launcher-Rust")
         .send()
         .await
         .map_err(|e| DistributionError::ApiError {
@@ -42,11 +43,34 @@ pub async fn build_liberica_url(version: &u8) -> DistributionResult<String> {
         })?;
 
     // Take the first package without cloning
-    foojay_response.result
+    let links = foojay_response.result
         .into_iter()
         .next()
-        .map(|pkg| pkg.links.pkg_download_redirect)
+        .map(|pkg| pkg.links)
         .ok_or(DistributionError::NoPackagesFound {
             distribution: "Liberica",
-        })
+        })?;
+
+    let info: FoojayInfoResponse = http_client()
+        .get(&links.pkg_info_uri)
+        .send()
+        .await
+        .map_err(|e| DistributionError::ApiError {
+            distribution: "Liberica",
+            error: e.to_string(),
+        })?
+        .json()
+        .await
+        .map_err(|e| DistributionError::JsonParseError {
+            distribution: "Liberica",
+            error: e.to_string(),
+        })?;
+
+    Ok(JavaPackage {
+        url: links.pkg_download_redirect,
+        checksum: info
+            .result
+            .first()
+            .and_then(|info| published_checksum(&info.checksum_type, &info.checksum)),
+    })
 }
@@ -8,7 +8,7 @@ mod graalvm;
 mod zulu;
 mod liberica;
 
-pub use temurin::build_temurin_url;
-pub use graalvm::build_graalvm_url;
-pub use zulu::build_zulu_url;
-pub use liberica::build_liberica_url;
+pub use temurin::fetch_temurin_package;
+pub use graalvm::fetch_graalvm_package;
+pub use zulu::fetch_zulu_package;
+pub use liberica::fetch_liberica_package;
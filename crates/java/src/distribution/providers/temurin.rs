@@ -5,19 +5,78 @@
 //!
 //! API Documentation: https://api.adoptium.net/q/swagger-ui/
 
-use crate::errors::DistributionResult;
+use crate::errors::{DistributionError, DistributionResult};
+use crate::distribution::api_models::AdoptiumAsset;
+use crate::distribution::{published_checksum, JavaPackage};
 use lighty_core::system::{ARCHITECTURE, OS};
+use lighty_core::hosts::http_client;
+use lighty_core::ExpectedHash;
 
-/// Builds Adoptium Temurin download URL
+/// Fetches the latest Adoptium Temurin JRE package
 ///
-/// Temurin provides comprehensive JRE support for all Java versions
-pub fn build_temurin_url(version: &u8) -> DistributionResult<String> {
+/// Temurin provides comprehensive JRE support for all Java versions.
+/// The archive comes from the binary endpoint of `api.adoptium.net`, so a
+/// `LIGHTY_MIRROR_ADOPTIUM` mirror applies to it; the assets API only
+/// supplies its SHA-256.
+pub async fn fetch_temurin_package(version: &u8) -> DistributionResult<JavaPackage> {
     let os_name = OS.get_adoptium_name()?;
     let arch = ARCHITECTURE.get_simple_name()?;
 
-    Ok(format!(
+    let url = format!(
         "https://api.adoptium.net/v3/binary/latest/{}/ga/{}/{}/jre/hotspot/normal/eclipse?project=jdk",
         version, os_name, arch
-    ))
+    );
 
+    Ok(JavaPackage {
+        checksum: fetch_temurin_checksum(version, os_name, arch).await?,
+        url,
+    })
+}
+
+/// SHA-256 of the latest archive, from the assets API
+///
+/// `None` when the API can't be reached or parsed: the download may still
+/// work through a mirror.
+async fn fetch_temurin_checksum(
+    version: &u8,
+    os_name: &str,
+    arch: &str,
+) -> DistributionResult<Option<ExpectedHash>> {
+    let api_url = format!(
+        "https://api.adoptium.net/v3/assets/latest/{}/hotspot?architecture={}&image_type=jre&os={}&vendor=eclipse",
+        version, arch, os_name
+    );
+
+    let assets: Vec<AdoptiumAsset> = match async {
+        http_client()
+            .get(&api_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+    .await
+    {
+        Ok(assets) => assets,
+        Err(_e) => {
+            lighty_core::trace_warn!("[Java] Temurin checksum unavailable: {}", _e);
+            return Ok(None);
+        }
+    };
+
+    assets
+        .into_iter()
+        .next()
+        .map(|asset| {
+            asset
+                .binary
+                .package
+                .checksum
+                .as_deref()
+                .and_then(|hex| published_checksum("sha256", hex))
+        })
+        .ok_or(DistributionError::NoPackagesFound {
+            distribution: "Temurin",
+        })
 }
@@ -6,14 +6,16 @@
 //! API Documentation: https://api.azul.com/metadata/v1/docs/
 
 use crate::errors::{DistributionError, DistributionResult};
-use crate::distribution::api_models::ZuluPackage;
+use crate::distribution::api_models::{ZuluPackage, ZuluPackageDetails};
+use crate::distribution::{published_checksum, JavaPackage};
 use lighty_core::system::{ARCHITECTURE, OS};
-use lighty_core::hosts::HTTP_CLIENT;
+use lighty_core::hosts::http_client;
 
-/// Builds Zulu download URL using their API
+/// Fetches the Zulu JRE package using their API
 ///
-/// Queries the Azul API to get the latest JRE package for the specified version
-pub async fn build_zulu_url(version: &u8) -> DistributionResult<String> {
+/// Queries the Azul API to get the latest JRE package for the specified version,
+/// then its details for the archive's SHA-256
+pub async fn fetch_zulu_package(version: &u8) -> DistributionResult<JavaPackage> {
     use lighty_core::system::{Architecture, OperatingSystem};
 
     let os_name = OS.get_zulu_name()?;
@@ -35,9 +37,8 @@ pub async fn build_zulu_url(version: &u8) -> DistributionResult<String> {
     );
 
     // Fetch from API
-    let response = HTTP_CLIENT
+    let response = http_client()
         .get(&api_url)
-        .header("User-Agent", "Lighty-Launcher-Rust")
         .send()
         .await
         .map_err(|e| DistributionError::ApiError {
@@ -54,11 +55,33 @@ pub async fn build_zulu_url(version: &u8) -> DistributionResult<String> {
         })?;
 
     // Find the first package without JavaFX (-fx-)
-    packages
+    let package = packages
         .into_iter()
         .find(|pkg| !pkg.name.contains("-fx-"))
-        .map(|pkg| pkg.download_url)
         .ok_or(DistributionError::NoPackagesFound {
             distribution: "Zulu",
-        })
+        })?;
+
+    let details: ZuluPackageDetails = http_client()
+        .get(format!("https://api.azul.com/metadata/v1/zulu/packages/{}", package.package_uuid))
+        .send()
+        .await
+        .map_err(|e| DistributionError::ApiError {
+            distribution: "Zulu",
+            error: e.to_string(),
+        })?
+        .json()
+        .await
+        .map_err(|e| DistributionError::JsonParseError {
+            distribution: "Zulu",
+            error: e.to_string(),
+        })?;
+
+    Ok(JavaPackage {
+        url: package.download_url,
+        checksum: details
+            .sha256_hash
+            .as_deref()
+            .and_then(|hex| published_checksum("sha256", hex)),
+    })
 }
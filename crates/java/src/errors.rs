@@ -23,6 +23,12 @@ pub enum JreError {
 
     #[error("Extraction failed: {0}")]
     Extraction(String),
+
+    #[error(transparent)]
+    OfflineUnavailable(#[from] lighty_core::OfflineUnavailable),
+
+    #[error(transparent)]
+    Cancelled(#[from] lighty_core::Cancelled),
 }
 
 /// Errors related to Java runtime execution
@@ -5,21 +5,27 @@
 //!
 //! This module handles downloading and extracting Java Runtime Environments.
 //! Implementation is based on standard Rust async patterns and public APIs.
+//!
+//! Inside a [`with_cancellation`](lighty_core::cancel::with_cancellation)
+//! scope the archive download can be paused or cancelled; extraction, once
+//! started, always runs to completion so no half-extracted runtime is left.
 
-use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
 use crate::errors::{JreError, JreResult};
 use path_absolutize::Absolutize;
 use tokio::fs;
+use tokio::io::BufReader;
 use tokio::time::{sleep, Duration};
 
-use lighty_core::download::download_file;
+use lighty_core::calculate_sha1_bytes;
+use lighty_core::download::{download_to_path, DownloadExpectation};
 use lighty_core::extract::{tar_gz_extract, zip_extract};
+use lighty_core::offline::ensure_online;
 use lighty_core::system::{OperatingSystem, OS};
 use lighty_core::DownloadError;
 
-use super::JavaDistribution;
+use super::{JavaDistribution, JavaPackage};
 
 #[cfg(feature = "events")]
 use lighty_event::{Event, EventBus, JavaEvent};
@@ -68,19 +74,23 @@ where
         .get_fallback(*version)
         .unwrap_or_else(|| distribution.clone());
 
+    // Provider APIs and archives are remote: nothing to do without network.
+    ensure_online(|| format!("{} Java {} runtime", effective_distribution.get_name(), version))?;
+
     let runtime_dir = build_runtime_path(runtimes_folder, &effective_distribution, version);
     prepare_installation_directory_with_retry(&runtime_dir).await?;
 
-    let download_urls = build_download_candidates(&effective_distribution, version)
+    let packages = build_download_candidates(&effective_distribution, version)
         .await
         .map_err(|e| JreError::Download(format!("Failed to get download URL: {}", e)))?;
-    let primary_url = download_urls
+    let primary_url = &packages
         .first()
-        .ok_or_else(|| JreError::Download("No download URLs available".to_string()))?;
+        .ok_or_else(|| JreError::Download("No download URLs available".to_string()))?
+        .url;
 
     let mut expected_total_bytes = 0;
     if let Some(bus) = event_bus {
-        let response = lighty_core::hosts::HTTP_CLIENT
+        let response = lighty_core::hosts::http_client()
             .get(primary_url)
             .header("accept-encoding", "identity")
             .send()
@@ -108,7 +118,7 @@ where
         }));
     }
 
-    let archive_bytes = {
+    let archive_path = {
         let event_bus_ref = event_bus;
         let progress_cb = |current: u64, total: u64| {
             on_progress(current, total);
@@ -130,7 +140,7 @@ where
             }
         };
 
-        download_with_retries(&download_urls, &progress_cb).await?
+        download_with_retries(runtimes_folder, &effective_distribution, version, &packages, &progress_cb).await?
     };
 
     if let Some(bus) = event_bus {
@@ -144,7 +154,7 @@ where
         }));
     }
 
-    extract_archive(&archive_bytes, &runtime_dir, event_bus).await?;
+    extract_archive(&archive_path, &runtime_dir, event_bus).await?;
     let binary_path = find_java_binary(runtimes_folder, &effective_distribution, version).await?;
 
     if let Some(bus) = event_bus {
@@ -173,22 +183,25 @@ where
         .get_fallback(*version)
         .unwrap_or_else(|| distribution.clone());
 
+    // Provider APIs and archives are remote: nothing to do without network.
+    ensure_online(|| format!("{} Java {} runtime", effective_distribution.get_name(), version))?;
+
     let runtime_dir = build_runtime_path(runtimes_folder, &effective_distribution, version);
     prepare_installation_directory_with_retry(&runtime_dir).await?;
 
-    let download_urls = build_download_candidates(&effective_distribution, version)
+    let packages = build_download_candidates(&effective_distribution, version)
         .await
         .map_err(|e| JreError::Download(format!("Failed to get download URL: {}", e)))?;
 
-    let archive_bytes = {
+    let archive_path = {
         let progress_cb = |current: u64, total: u64| {
             on_progress(current, total);
         };
 
-        download_with_retries(&download_urls, &progress_cb).await?
+        download_with_retries(runtimes_folder, &effective_distribution, version, &packages, &progress_cb).await?
     };
 
-    extract_archive(&archive_bytes, &runtime_dir).await?;
+    extract_archive(&archive_path, &runtime_dir).await?;
     find_java_binary(runtimes_folder, &effective_distribution, version).await
 }
 
@@ -199,26 +212,26 @@ where
 async fn build_download_candidates(
     distribution: &JavaDistribution,
     version: &u8,
-) -> JreResult<Vec<String>> {
-    let mut urls = Vec::new();
+) -> JreResult<Vec<JavaPackage>> {
+    let mut packages = Vec::new();
 
     let primary = distribution
-        .get_download_url(version)
+        .get_package(version)
         .await
         .map_err(|e| JreError::Download(format!("Failed to get download URL: {}", e)))?;
-    urls.push(primary);
+    packages.push(primary);
 
     if matches!(distribution, JavaDistribution::Temurin) {
         for candidate in [JavaDistribution::Zulu, JavaDistribution::Liberica] {
             if candidate.supports_version(*version) {
-                if let Ok(url) = candidate.get_download_url(version).await {
-                    urls.push(url);
+                if let Ok(package) = candidate.get_package(version).await {
+                    packages.push(package);
                 }
             }
         }
     }
 
-    Ok(urls)
+    Ok(packages)
 }
 
 fn build_runtime_path(
@@ -267,7 +280,37 @@ async fn prepare_installation_directory_with_retry(runtime_dir: &Path) -> JreRes
     }))
 }
 
-async fn download_with_retries<F>(download_urls: &[String], on_progress: &F) -> JreResult<Vec<u8>>
+/// Path the archive downloaded from `url` is streamed to, next to the runtime directory.
+///
+/// One file per source URL, so a partial download left by an interrupted
+/// run is only ever resumed from the same source.
+fn build_archive_path(
+    runtimes_folder: &Path,
+    distribution: &JavaDistribution,
+    version: &u8,
+    url: &str,
+) -> PathBuf {
+    let extension = match OS {
+        OperatingSystem::WINDOWS => "zip",
+        _ => "tar.gz",
+    };
+    let url_hash = calculate_sha1_bytes(url.as_bytes());
+    runtimes_folder.join(format!(
+        "{}_{}-{}.{}",
+        distribution.get_name(),
+        version,
+        &url_hash[..12],
+        extension
+    ))
+}
+
+async fn download_with_retries<F>(
+    runtimes_folder: &Path,
+    distribution: &JavaDistribution,
+    version: &u8,
+    packages: &[JavaPackage],
+    on_progress: &F,
+) -> JreResult<PathBuf>
 where
     F: Fn(u64, u64),
 {
@@ -276,12 +319,31 @@ where
 
     let mut last_error: Option<DownloadError> = None;
 
-    for url in download_urls {
+    for package in packages {
+        let url = &package.url;
+        let archive_path = build_archive_path(runtimes_folder, distribution, version, url);
+        // A corrupted or tampered archive is rejected before extraction
+        let expectation = match &package.checksum {
+            Some(checksum) => DownloadExpectation::hash(checksum.clone()),
+            None => {
+                lighty_core::trace_warn!("[Java] No published checksum for {}, archive not verified", url);
+                DownloadExpectation::default()
+            }
+        };
+
         for attempt in 1..=MAX_IO_RETRIES {
-            let result = download_file(url, |current, total| on_progress(current, total)).await;
+            let result = download_to_path(
+                url,
+                &archive_path,
+                &expectation,
+                |current, total| on_progress(current, total),
+            )
+            .await;
 
             match result {
-                Ok(bytes) => return Ok(bytes),
+                Ok(()) => return Ok(archive_path),
+                // The part file is kept and resumed next time
+                Err(DownloadError::Cancelled(cancelled)) => return Err(cancelled.into()),
                 Err(err) => {
                     let should_retry = matches!(err, DownloadError::Io(ref io) if is_transient_installation_io_error(io));
 
@@ -315,53 +377,47 @@ where
 /// Extracts the JRE archive based on the operating system (with events feature)
 #[cfg(feature = "events")]
 async fn extract_archive(
-    archive_bytes: &[u8],
+    archive_path: &Path,
     destination: &Path,
     event_bus: Option<&EventBus>,
 ) -> JreResult<()> {
-    let cursor = Cursor::new(archive_bytes);
+    let archive = BufReader::new(fs::File::open(archive_path).await?);
 
-    match OS {
-        OperatingSystem::WINDOWS => {
-            zip_extract(cursor, destination, event_bus)
-                .await
-                .map_err(|e| JreError::Extraction(format!("ZIP extraction failed: {}", e)))?;
-        }
-        OperatingSystem::LINUX | OperatingSystem::OSX => {
-            tar_gz_extract(cursor, destination, event_bus)
-                .await
-                .map_err(|e| JreError::Extraction(format!("TAR.GZ extraction failed: {}", e)))?;
-        }
-        OperatingSystem::UNKNOWN => {
-            return Err(JreError::UnsupportedOS);
-        }
-    }
+    let result = match OS {
+        OperatingSystem::WINDOWS => zip_extract(archive, destination, event_bus)
+            .await
+            .map_err(|e| JreError::Extraction(format!("ZIP extraction failed: {}", e))),
+        OperatingSystem::LINUX | OperatingSystem::OSX => tar_gz_extract(archive, destination, event_bus)
+            .await
+            .map_err(|e| JreError::Extraction(format!("TAR.GZ extraction failed: {}", e))),
+        OperatingSystem::UNKNOWN => Err(JreError::UnsupportedOS),
+    };
 
-    Ok(())
+    // The archive is only a transfer artifact: drop it whether or not it
+    // extracted, a corrupt one must not be reused on the next attempt
+    let _ = fs::remove_file(archive_path).await;
+    result
 }
 
 /// Extracts the JRE archive based on the operating system (without events feature)
 #[cfg(not(feature = "events"))]
-async fn extract_archive(archive_bytes: &[u8], destination: &Path) -> JreResult<()> {
-    let cursor = Cursor::new(archive_bytes);
-
-    match OS {
-        OperatingSystem::WINDOWS => {
-            zip_extract(cursor, destination)
-                .await
-                .map_err(|e| JreError::Extraction(format!("ZIP extraction failed: {}", e)))?;
-        }
-        OperatingSystem::LINUX | OperatingSystem::OSX => {
-            tar_gz_extract(cursor, destination)
-                .await
-                .map_err(|e| JreError::Extraction(format!("TAR.GZ extraction failed: {}", e)))?;
-        }
-        OperatingSystem::UNKNOWN => {
-            return Err(JreError::UnsupportedOS);
-        }
-    }
+async fn extract_archive(archive_path: &Path, destination: &Path) -> JreResult<()> {
+    let archive = BufReader::new(fs::File::open(archive_path).await?);
 
-    Ok(())
+    let result = match OS {
+        OperatingSystem::WINDOWS => zip_extract(archive, destination)
+            .await
+            .map_err(|e| JreError::Extraction(format!("ZIP extraction failed: {}", e))),
+        OperatingSystem::LINUX | OperatingSystem::OSX => tar_gz_extract(archive, destination)
+            .await
+            .map_err(|e| JreError::Extraction(format!("TAR.GZ extraction failed: {}", e))),
+        OperatingSystem::UNKNOWN => Err(JreError::UnsupportedOS),
+    };
+
+    // The archive is only a transfer artifact: drop it whether or not it
+    // extracted, a corrupt one must not be reused on the next attempt
+    let _ = fs::remove_file(archive_path).await;
+    result
 }
 
 /// Locates the java binary within the extracted JRE directory
@@ -10,7 +10,7 @@
 //! - Support for multiple Java distributions (Temurin, GraalVM, Zulu, Liberica)
 //! - Cross-platform JRE download and installation
 //! - Java process execution with I/O streaming
-//! - File size verification for download integrity
+//! - Checksum verification of runtime archives against the providers' published hashes
 //!
 //! ## License
 //! This implementation is original work licensed under MIT.
@@ -32,6 +32,7 @@ pub use errors::{
     JavaRuntimeError, JavaRuntimeResult,
     DistributionError, DistributionResult,
 };
+pub use distribution::JavaPackage;
 
 // ============================================================================
 // Public Types
@@ -131,6 +132,12 @@ impl JavaDistribution {
     ///
     /// Queries the respective API or builds direct download URLs for each distribution.
     pub async fn get_download_url(&self, jre_version: &u8) -> DistributionResult<String> {
-        distribution::get_download_url(self, jre_version).await
+        self.get_package(jre_version).await.map(|package| package.url)
+    }
+
+    /// Gets the runtime archive for the distribution, with the checksum its
+    /// provider publishes
+    pub async fn get_package(&self, jre_version: &u8) -> DistributionResult<JavaPackage> {
+        distribution::get_package(self, jre_version).await
     }
 }
\ No newline at end of file
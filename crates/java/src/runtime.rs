@@ -16,6 +16,19 @@ use tokio::sync::oneshot::Receiver;
 /// Wrapper around a Java binary path for process execution
 pub struct JavaRuntime(pub PathBuf);
 
+/// How [`JavaRuntime::execute_with`] spawns the process, on top of the
+/// binary and its arguments
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    /// Command the Java binary is run through (e.g. `["gamemoderun"]`),
+    /// program first
+    pub wrapper: Vec<String>,
+    /// Variables set on top of the inherited environment
+    pub env: Vec<(String, String)>,
+    /// Inherited variables to unset
+    pub env_remove: Vec<String>,
+}
+
 impl JavaRuntime {
     /// Creates a new JavaRuntime from a binary path
     pub fn new(path: PathBuf) -> Self {
@@ -34,6 +47,20 @@ impl JavaRuntime {
     /// # Errors
     /// Returns an error if the binary doesn't exist or the spawn fails
     pub async fn execute(&self, arguments: Vec<String>, game_dir: &Path) -> JavaRuntimeResult<Child> {
+        self.execute_with(arguments, game_dir, &ProcessOptions::default()).await
+    }
+
+    /// Like [`Self::execute`], through a wrapper command and with a
+    /// modified environment
+    ///
+    /// Removals are applied before the variables in `options.env`, so a
+    /// variable both removed and set ends up set.
+    pub async fn execute_with(
+        &self,
+        arguments: Vec<String>,
+        game_dir: &Path,
+        options: &ProcessOptions,
+    ) -> JavaRuntimeResult<Child> {
         // Validate binary exists
         if !self.0.exists() {
             return Err(JavaRuntimeError::NotFound {
@@ -43,15 +70,32 @@ impl JavaRuntime {
 
         lighty_core::trace_debug!("Spawning Java process: {:?}", &self.0);
 
-        // Build command
-        let mut command = Command::new(&self.0);
+        // Build command, through the wrapper if any
+        let mut command = match options.wrapper.split_first() {
+            Some((program, wrapper_args)) => {
+                lighty_core::trace_debug!("Wrapping Java process with {:?}", options.wrapper);
+                let mut command = Command::new(program);
+                command.args(wrapper_args).arg(&self.0);
+                command
+            }
+            None => Command::new(&self.0),
+        };
+        for key in &options.env_remove {
+            command.env_remove(key);
+        }
         command
+            .envs(options.env.iter().map(|(key, value)| (key, value)))
             .current_dir(game_dir)
             .args(arguments)
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        // On Unix, lead a new process group so the whole tree (wrappers,
+        // child JVMs) can be signalled at once
+        #[cfg(unix)]
+        command.process_group(0);
+
         // On Windows, hide the console window
         #[cfg(windows)]
         {
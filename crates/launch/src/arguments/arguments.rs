@@ -7,6 +7,9 @@ use lighty_loaders::types::VersionInfo;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
+use super::rules::{allowed_arguments, LaunchFeature};
+use crate::installer::ressources::logging::config_path as logging_config_path;
+
 // Public placeholder keys used in the launch-argument variable map.
 //
 // These match the `${...}` tokens found inside `arguments.game` and
@@ -49,6 +52,18 @@ pub const KEY_LAUNCHER_VERSION: &str = "launcher_version";
 pub const KEY_CLASSPATH: &str = "classpath";
 /// OS-specific classpath separator (`${classpath_separator}`).
 pub const KEY_CLASSPATH_SEPARATOR: &str = "classpath_separator";
+/// Window width with [`LaunchFeature::CustomResolution`] (`${resolution_width}`).
+pub const KEY_RESOLUTION_WIDTH: &str = "resolution_width";
+/// Window height with [`LaunchFeature::CustomResolution`] (`${resolution_height}`).
+pub const KEY_RESOLUTION_HEIGHT: &str = "resolution_height";
+/// Quick Play log file with [`LaunchFeature::QuickPlaySupport`] (`${quickPlayPath}`).
+pub const KEY_QUICK_PLAY_PATH: &str = "quickPlayPath";
+/// World to join with [`LaunchFeature::QuickPlaySingleplayer`] (`${quickPlaySingleplayer}`).
+pub const KEY_QUICK_PLAY_SINGLEPLAYER: &str = "quickPlaySingleplayer";
+/// Server to join with [`LaunchFeature::QuickPlayMultiplayer`] (`${quickPlayMultiplayer}`).
+pub const KEY_QUICK_PLAY_MULTIPLAYER: &str = "quickPlayMultiplayer";
+/// Realm to join with [`LaunchFeature::QuickPlayRealms`] (`${quickPlayRealms}`).
+pub const KEY_QUICK_PLAY_REALMS: &str = "quickPlayRealms";
 
 // Default values used when no real session data is available
 const DEFAULT_ACCESS_TOKEN: &str = "0";
@@ -56,6 +71,9 @@ const DEFAULT_XUID: &str = "0";
 const DEFAULT_USER_TYPE: &str = "legacy";
 const DEFAULT_USER_PROPERTIES: &str = "{}";
 const DEFAULT_VERSION_TYPE: &str = "release";
+// Mojang launcher's default window size
+const DEFAULT_RESOLUTION_WIDTH: &str = "854";
+const DEFAULT_RESOLUTION_HEIGHT: &str = "480";
 const CP_FLAG: &str = "-cp";
 
 /// Maps an `AuthProvider` to the `${user_type}` string the game expects.
@@ -73,6 +91,24 @@ fn user_type_for(provider: &AuthProvider) -> &'static str {
     }
 }
 
+/// Caller-supplied changes to the manifest's arguments, as set on a
+/// `LaunchBuilder`.
+#[derive(Debug, Clone, Default)]
+pub struct ArgumentOptions {
+    /// Features tested by the manifest's argument rules
+    pub features: HashSet<LaunchFeature>,
+    /// Placeholder values (`KEY_*`) replacing the defaults
+    pub arg_overrides: HashMap<String, String>,
+    /// Game arguments to drop
+    pub arg_removals: HashSet<String>,
+    /// JVM options to add or replace (without the `-` prefix)
+    pub jvm_overrides: HashMap<String, String>,
+    /// JVM options to drop
+    pub jvm_removals: HashSet<String>,
+    /// Arguments appended after the game arguments
+    pub raw_args: Vec<String>,
+}
+
 /// Builds the final argv (JVM args + main class + game args + raw args)
 /// from a resolved [`Version`] plus runtime overrides and removals.
 ///
@@ -80,23 +116,22 @@ fn user_type_for(provider: &AuthProvider) -> &'static str {
 /// invokes [`Self::build_arguments`] directly — `LaunchBuilder::run`
 /// does it internally.
 pub trait Arguments {
-    /// Constructs the launch argv for `builder` using the supplied
-    /// overrides and removals.
+    /// Constructs the launch argv for `builder` using the overrides and
+    /// removals of `options`.
     ///
     /// Pass the authenticated `profile` so auth-derived placeholders
     /// (`${auth_player_name}`, `${auth_uuid}`, `${auth_access_token}`,
     /// `${auth_xuid}`, `${user_type}`) are populated from real session
     /// data. `None` keeps the legacy hardcoded defaults (`"0"`,
     /// `"legacy"`, …) — useful for dry-run argv inspection or tests.
+    ///
+    /// Conditional manifest arguments are kept when their rules allow them
+    /// on this OS/architecture with `options.features` enabled.
     fn build_arguments(
         &self,
         builder: &Version,
         profile: Option<&UserProfile>,
-        arg_overrides: &HashMap<String, String>,
-        arg_removals: &HashSet<String>,
-        jvm_overrides: &HashMap<String, String>,
-        jvm_removals: &HashSet<String>,
-        raw_args: &[String],
+        options: &ArgumentOptions,
     ) -> Vec<String>;
 }
 
@@ -105,12 +140,17 @@ impl<T: VersionInfo> Arguments for T {
         &self,
         builder: &Version,
         profile: Option<&UserProfile>,
-        arg_overrides: &HashMap<String, String>,
-        arg_removals: &HashSet<String>,
-        jvm_overrides: &HashMap<String, String>,
-        jvm_removals: &HashSet<String>,
-        raw_args: &[String],
+        options: &ArgumentOptions,
     ) -> Vec<String> {
+        let ArgumentOptions {
+            features,
+            arg_overrides,
+            arg_removals,
+            jvm_overrides,
+            jvm_removals,
+            raw_args,
+        } = options;
+
         // Build the placeholder substitution map
         let mut variables = create_variable_map(self, builder, profile);
 
@@ -130,14 +170,24 @@ impl<T: VersionInfo> Arguments for T {
             self.runtime_dir().display().to_string(),
         );
 
-        // Substitute `${...}` placeholders inside the game arguments
-        let game_args = replace_variables_in_vec(&builder.arguments.game, &variables);
+        // Substitute `${...}` placeholders inside the game arguments;
+        // rule-gated ones go last, where the manifest lists them
+        let mut game_args = replace_variables_in_vec(&builder.arguments.game, &variables);
+        game_args.extend(replace_variables_in_vec(
+            &allowed_arguments(&builder.arguments.conditional_game, features),
+            &variables,
+        ));
 
         let mut jvm_args = builder.arguments.jvm
             .as_ref()
             .map(|jvm| replace_variables_in_vec(jvm, &variables))
             .unwrap_or_else(|| build_default_jvm_args(&variables));
 
+        // Rule-gated JVM arguments (`-XstartOnFirstThread`, `-Xss1M`, ...)
+        // come first, where the manifest lists them
+        let conditional_jvm = allowed_arguments(&builder.arguments.conditional_jvm, features);
+        jvm_args.splice(0..0, replace_variables_in_vec(&conditional_jvm, &variables));
+
         // Make sure critical JVM args are always present
 
         // 0. macOS: -XstartOnFirstThread is MANDATORY for LWJGL/OpenGL
@@ -163,7 +213,15 @@ impl<T: VersionInfo> Arguments for T {
             jvm_args.insert(0, format!("-Dminecraft.launcher.version={}", launcher_version));
         }
 
-        // 3. Classpath (must be the last JVM arg before the main class)
+        // 3. Log4j configuration (official XML layout, parsed from stdout)
+        if let Some(logging) = &builder.logging {
+            if !jvm_args.iter().any(|arg| arg.starts_with("-Dlog4j.configurationFile=")) {
+                let path = logging_config_path(self, logging).display().to_string();
+                jvm_args.insert(0, logging.argument.replace("${path}", &path));
+            }
+        }
+
+        // 4. Classpath (must be the last JVM arg before the main class)
         let module_path_opt = jvm_args
             .iter()
             .position(|arg| arg == "-p")
@@ -203,13 +261,13 @@ impl<T: VersionInfo> Arguments for T {
             }
         }
 
-        // 4. Apply JVM overrides
+        // 5. Apply JVM overrides
         apply_jvm_overrides(&mut jvm_args, jvm_overrides);
 
-        // 5. Apply JVM removals
+        // 6. Apply JVM removals
         apply_jvm_removals(&mut jvm_args, jvm_removals);
 
-        // 6. Apply arg removals (filter game arguments)
+        // 7. Apply arg removals (filter game arguments)
         let game_args = apply_arg_removals(game_args, arg_removals);
 
         // Build the full argv: JVM + MainClass + Game + raw args
@@ -271,13 +329,17 @@ fn create_variable_map<T: VersionInfo>(
         map.insert(KEY_VERSION_NAME.into(), version.name().into());
         map.insert(KEY_VERSION_TYPE.into(), DEFAULT_VERSION_TYPE.into());
 
+        // Window size, used with `LaunchFeature::CustomResolution`
+        map.insert(KEY_RESOLUTION_WIDTH.into(), DEFAULT_RESOLUTION_WIDTH.into());
+        map.insert(KEY_RESOLUTION_HEIGHT.into(), DEFAULT_RESOLUTION_HEIGHT.into());
+
         // Directories — `runtime_dir()` is the single source of truth
         // shared with the install pipeline (mods land where the game
         // actually scans for them).
         map.insert(KEY_GAME_DIRECTORY.into(), version.runtime_dir().display().to_string());
-        map.insert(KEY_ASSETS_ROOT.into(), version.game_dirs().join("assets").display().to_string());
+        map.insert(KEY_ASSETS_ROOT.into(), version.assets_dir().display().to_string());
         map.insert(KEY_NATIVES_DIRECTORY.into(), version.game_dirs().join("natives").display().to_string());
-        map.insert(KEY_LIBRARY_DIRECTORY.into(), version.game_dirs().join("libraries").display().to_string());
+        map.insert(KEY_LIBRARY_DIRECTORY.into(), version.libraries_dir().display().to_string());
 
         // Assets index
         let assets_index_name = builder.assets_index
@@ -305,7 +367,7 @@ fn build_classpath<T: VersionInfo>(version: &T, libraries: &[lighty_loaders::typ
         #[cfg(not(target_os = "windows"))]
         let separator = ":";
 
-        let lib_dir = version.game_dirs().join("libraries");
+        let lib_dir = version.libraries_dir();
 
         let mut classpath_entries: Vec<String> = libraries
             .iter()
@@ -536,3 +598,109 @@ fn get_path_separator() -> &'static str {
         ":"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighty_core::app_state::LauncherPaths;
+    use lighty_core::system::{Architecture, ARCHITECTURE, OS};
+    use lighty_core::AppState;
+    use lighty_version::VersionBuilder;
+
+    fn instance() -> VersionBuilder {
+        let _ = AppState::init_with_paths(LauncherPaths::under_root("LightyTest", std::env::temp_dir()));
+        VersionBuilder::new("arguments-test", (), "", "1.21")
+    }
+
+    /// 1.21 manifest arguments, rule-gated entries included.
+    fn version() -> Version {
+        let this_os = OS.get_vanilla_os().unwrap();
+        serde_json::from_value(serde_json::json!({
+            "main_class": { "main_class": "net.minecraft.client.main.Main" },
+            "java_version": { "major_version": 21 },
+            "arguments": {
+                "game": ["--username", "${auth_player_name}", "--version", "${version_name}"],
+                "jvm": ["-Djava.library.path=${natives_directory}", "-cp", "${classpath}"],
+                "conditional_game": [
+                    { "rules": [{ "action": "allow", "features": { "is_demo_user": true } }], "value": ["--demo"] },
+                    {
+                        "rules": [{ "action": "allow", "features": { "has_custom_resolution": true } }],
+                        "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]
+                    }
+                ],
+                "conditional_jvm": [
+                    { "rules": [{ "action": "allow", "os": { "name": "osx" } }], "value": ["-XstartOnFirstThread"] },
+                    { "rules": [{ "action": "allow", "os": { "arch": "x86" } }], "value": ["-Xss1M"] },
+                    { "rules": [{ "action": "allow", "os": { "name": this_os } }], "value": ["-Dthis.os=true"] }
+                ]
+            },
+            "libraries": [],
+            "mods": null,
+            "natives": null,
+            "client": null,
+            "assets_index": null,
+            "assets": null
+        }))
+        .unwrap()
+    }
+
+    fn build(options: &ArgumentOptions) -> Vec<String> {
+        instance().build_arguments(&version(), None, options)
+    }
+
+    fn game_args(args: &[String]) -> &[String] {
+        let main = args.iter().position(|arg| arg == "net.minecraft.client.main.Main").unwrap();
+        &args[main + 1..]
+    }
+
+    #[test]
+    fn rule_gated_game_arguments_follow_features() {
+        let options = ArgumentOptions {
+            arg_overrides: HashMap::from([(KEY_AUTH_PLAYER_NAME.to_string(), "Steve".to_string())]),
+            ..ArgumentOptions::default()
+        };
+        let args = build(&options);
+        assert_eq!(game_args(&args), ["--username", "Steve", "--version", "arguments-test"].map(String::from));
+
+        let options = ArgumentOptions {
+            features: [LaunchFeature::CustomResolution, LaunchFeature::Demo].into_iter().collect(),
+            arg_overrides: HashMap::from([(KEY_RESOLUTION_WIDTH.to_string(), "1920".to_string())]),
+            ..ArgumentOptions::default()
+        };
+        let args = build(&options);
+        assert_eq!(
+            &game_args(&args)[4..],
+            ["--demo", "--width", "1920", "--height", DEFAULT_RESOLUTION_HEIGHT].map(String::from)
+        );
+    }
+
+    #[test]
+    fn rule_gated_jvm_arguments_follow_os_and_arch() {
+        let args = build(&ArgumentOptions::default());
+        let has = |flag: &str| args.iter().any(|arg| arg == flag);
+
+        assert!(has("-Dthis.os=true"));
+        assert_eq!(has("-XstartOnFirstThread"), OS.get_vanilla_os().unwrap() == "osx");
+        assert_eq!(has("-Xss1M"), ARCHITECTURE == Architecture::X86);
+
+        // Before the manifest's own JVM arguments, `-cp` still last
+        let this_os = args.iter().position(|arg| arg == "-Dthis.os=true").unwrap();
+        let library_path = args.iter().position(|arg| arg.starts_with("-Djava.library.path=")).unwrap();
+        let classpath = args.iter().position(|arg| arg == CP_FLAG).unwrap();
+        assert!(this_os < library_path);
+        assert_eq!(args[classpath + 2], "net.minecraft.client.main.Main");
+    }
+
+    #[test]
+    fn removals_apply_to_rule_gated_arguments() {
+        let options = ArgumentOptions {
+            features: [LaunchFeature::Demo].into_iter().collect(),
+            arg_removals: HashSet::from(["--demo".to_string()]),
+            jvm_removals: HashSet::from(["Dthis.os".to_string()]),
+            ..ArgumentOptions::default()
+        };
+        let args = build(&options);
+        assert!(!args.iter().any(|arg| arg == "--demo"));
+        assert!(!args.iter().any(|arg| arg.starts_with("-Dthis.os")));
+    }
+}
@@ -2,5 +2,8 @@
 // Licensed under the MIT License
 
 mod arguments;
+mod rules;
 
 pub use arguments::*;
+pub use rules::LaunchFeature;
+pub(crate) use rules::supports_feature;
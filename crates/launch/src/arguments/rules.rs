@@ -0,0 +1,252 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Evaluation of Mojang argument rules.
+//!
+//! Conditional manifest arguments carry `rules` gated on the OS name,
+//! OS version (a regex), CPU architecture and launch features. They are
+//! kept as-is by the loaders and evaluated here, at launch, against the
+//! running system and the features enabled on the
+//! [`LaunchBuilder`](crate::launch::LaunchBuilder).
+
+use std::collections::HashSet;
+
+use lighty_core::system::{os_version, ARCHITECTURE, OS};
+use lighty_loaders::types::version_metadata::{ArgumentRule, ConditionalArgument, OsRule, Version};
+
+/// Launch feature tested by the `features` of an argument rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LaunchFeature {
+    /// `is_demo_user`: start the game in demo mode (`--demo`)
+    Demo,
+    /// `has_custom_resolution`: pass `--width` / `--height`
+    CustomResolution,
+    /// `has_quick_plays_support`: log Quick Play sessions to `${quickPlayPath}`
+    QuickPlaySupport,
+    /// `is_quick_play_singleplayer`: join the world `${quickPlaySingleplayer}`
+    QuickPlaySingleplayer,
+    /// `is_quick_play_multiplayer`: join the server `${quickPlayMultiplayer}`
+    QuickPlayMultiplayer,
+    /// `is_quick_play_realms`: join the realm `${quickPlayRealms}`
+    QuickPlayRealms,
+}
+
+impl LaunchFeature {
+    /// Name used in the manifest
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Demo => "is_demo_user",
+            Self::CustomResolution => "has_custom_resolution",
+            Self::QuickPlaySupport => "has_quick_plays_support",
+            Self::QuickPlaySingleplayer => "is_quick_play_singleplayer",
+            Self::QuickPlayMultiplayer => "is_quick_play_multiplayer",
+            Self::QuickPlayRealms => "is_quick_play_realms",
+        }
+    }
+}
+
+/// Values of the conditional arguments whose rules allow them, in order.
+pub(crate) fn allowed_arguments(arguments: &[ConditionalArgument], features: &HashSet<LaunchFeature>) -> Vec<String> {
+    let enabled: HashSet<&str> = features.iter().map(|feature| feature.name()).collect();
+
+    arguments
+        .iter()
+        .filter(|argument| rules_allow(&argument.rules, &enabled))
+        .flat_map(|argument| argument.value.iter().cloned())
+        .collect()
+}
+
+/// Whether any conditional game argument of `version` tests `feature`.
+pub(crate) fn supports_feature(version: &Version, feature: LaunchFeature) -> bool {
+    version
+        .arguments
+        .conditional_game
+        .iter()
+        .flat_map(|argument| &argument.rules)
+        .any(|rule| rule.features.contains_key(feature.name()))
+}
+
+/// The last matching rule decides; with none matching, nothing is allowed.
+fn rules_allow(rules: &[ArgumentRule], enabled: &HashSet<&str>) -> bool {
+    let mut allowed = false;
+
+    for rule in rules {
+        let matches_os = rule.os.as_ref().map_or(true, os_matches);
+        let matches_features = rule
+            .features
+            .iter()
+            .all(|(name, &expected)| enabled.contains(name.as_str()) == expected);
+
+        if matches_os && matches_features {
+            allowed = rule.action == "allow";
+        }
+    }
+
+    allowed
+}
+
+fn os_matches(os: &OsRule) -> bool {
+    if let Some(name) = &os.name {
+        if OS.get_vanilla_os().ok() != Some(name.as_str()) {
+            return false;
+        }
+    }
+
+    if let Some(arch) = &os.arch {
+        if ARCHITECTURE.get_simple_name().ok() != Some(arch.as_str()) {
+            return false;
+        }
+    }
+
+    if let Some(pattern) = &os.version {
+        match regex::Regex::new(pattern) {
+            Ok(regex) => {
+                if !regex.is_match(os_version()) {
+                    return false;
+                }
+            }
+            Err(_e) => {
+                lighty_core::trace_warn!(pattern = %pattern, error = %_e, "Invalid OS version pattern in argument rule");
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighty_core::system::Architecture;
+
+    fn arguments(json: serde_json::Value) -> Vec<ConditionalArgument> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn features(list: &[LaunchFeature]) -> HashSet<LaunchFeature> {
+        list.iter().copied().collect()
+    }
+
+    fn this_os() -> &'static str {
+        OS.get_vanilla_os().unwrap()
+    }
+
+    fn other_os() -> &'static str {
+        if this_os() == "windows" { "linux" } else { "windows" }
+    }
+
+    /// Game arguments of the 1.21 manifest.
+    fn game_arguments() -> Vec<ConditionalArgument> {
+        arguments(serde_json::json!([
+            { "rules": [{ "action": "allow", "features": { "is_demo_user": true } }], "value": ["--demo"] },
+            {
+                "rules": [{ "action": "allow", "features": { "has_custom_resolution": true } }],
+                "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "has_quick_plays_support": true } }],
+                "value": ["--quickPlayPath", "${quickPlayPath}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_singleplayer": true } }],
+                "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_multiplayer": true } }],
+                "value": ["--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+            }
+        ]))
+    }
+
+    #[test]
+    fn features_select_game_arguments() {
+        let game = game_arguments();
+        assert!(allowed_arguments(&game, &features(&[])).is_empty());
+        assert_eq!(allowed_arguments(&game, &features(&[LaunchFeature::Demo])), vec!["--demo"]);
+        assert_eq!(
+            allowed_arguments(&game, &features(&[LaunchFeature::QuickPlayMultiplayer, LaunchFeature::CustomResolution])),
+            vec!["--width", "${resolution_width}", "--height", "${resolution_height}", "--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+        );
+    }
+
+    #[test]
+    fn feature_set_to_false_requires_it_off() {
+        let args = arguments(serde_json::json!([
+            { "rules": [{ "action": "allow", "features": { "is_demo_user": false } }], "value": ["--full-game"] }
+        ]));
+        assert_eq!(allowed_arguments(&args, &features(&[])), vec!["--full-game"]);
+        assert!(allowed_arguments(&args, &features(&[LaunchFeature::Demo])).is_empty());
+    }
+
+    #[test]
+    fn os_name_selects_jvm_arguments() {
+        let jvm = arguments(serde_json::json!([
+            { "rules": [{ "action": "allow", "os": { "name": this_os() } }], "value": ["-Dthis.os=true"] },
+            { "rules": [{ "action": "allow", "os": { "name": other_os() } }], "value": ["-Dother.os=true"] }
+        ]));
+        assert_eq!(allowed_arguments(&jvm, &features(&[])), vec!["-Dthis.os=true"]);
+    }
+
+    #[test]
+    fn arch_selects_jvm_arguments() {
+        // 1.21 manifest: 32-bit JVMs get a larger thread stack
+        let jvm = arguments(serde_json::json!([
+            { "rules": [{ "action": "allow", "os": { "arch": "x86" } }], "value": ["-Xss1M"] }
+        ]));
+        let expected: Vec<String> = if ARCHITECTURE == Architecture::X86 { vec!["-Xss1M".into()] } else { Vec::new() };
+        assert_eq!(allowed_arguments(&jvm, &features(&[])), expected);
+
+        let this_arch = ARCHITECTURE.get_simple_name().unwrap();
+        let jvm = arguments(serde_json::json!([
+            { "rules": [{ "action": "allow", "os": { "name": this_os(), "arch": this_arch } }], "value": ["-Dmatch=true"] },
+            { "rules": [{ "action": "allow", "os": { "name": other_os(), "arch": this_arch } }], "value": ["-Dwrong.os=true"] }
+        ]));
+        assert_eq!(allowed_arguments(&jvm, &features(&[])), vec!["-Dmatch=true"]);
+    }
+
+    #[test]
+    fn os_version_is_a_regex() {
+        let jvm = arguments(serde_json::json!([
+            { "rules": [{ "action": "allow", "os": { "name": this_os(), "version": ".*" } }], "value": ["-Dany.version=true"] },
+            { "rules": [{ "action": "allow", "os": { "name": this_os(), "version": "^no such version$" } }], "value": ["-Dnone=true"] },
+            { "rules": [{ "action": "allow", "os": { "version": "(" } }], "value": ["-Dinvalid=true"] }
+        ]));
+        assert_eq!(allowed_arguments(&jvm, &features(&[])), vec!["-Dany.version=true"]);
+    }
+
+    #[test]
+    fn last_matching_rule_decides() {
+        let args = arguments(serde_json::json!([
+            {
+                "rules": [
+                    { "action": "allow" },
+                    { "action": "disallow", "os": { "name": this_os() } }
+                ],
+                "value": ["-Dnot.here=true"]
+            },
+            {
+                "rules": [
+                    { "action": "allow" },
+                    { "action": "disallow", "os": { "name": other_os() } }
+                ],
+                "value": ["-Deverywhere.else=true"]
+            },
+            {
+                "rules": [
+                    { "action": "allow", "os": { "name": this_os() } },
+                    { "action": "disallow", "features": { "is_demo_user": true } }
+                ],
+                "value": ["--not-in-demo"]
+            }
+        ]));
+        assert_eq!(allowed_arguments(&args, &features(&[])), vec!["-Deverywhere.else=true", "--not-in-demo"]);
+        assert_eq!(allowed_arguments(&args, &features(&[LaunchFeature::Demo])), vec!["-Deverywhere.else=true"]);
+    }
+
+    #[test]
+    fn argument_without_rules_is_left_out() {
+        let args = arguments(serde_json::json!([{ "rules": [], "value": ["--orphan"] }]));
+        assert!(allowed_arguments(&args, &features(&[LaunchFeature::Demo])).is_empty());
+    }
+}
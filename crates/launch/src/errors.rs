@@ -27,6 +27,15 @@ pub enum InstallerError {
     #[error("Download failed: {0}")]
     DownloadFailed(String),
 
+    #[error("Download error: {0}")]
+    Download(#[from] lighty_core::DownloadError),
+
+    #[error(transparent)]
+    OfflineUnavailable(#[from] lighty_core::OfflineUnavailable),
+
+    #[error(transparent)]
+    Cancelled(#[from] lighty_core::Cancelled),
+
     #[error("Zip error: {0}")]
     Zip(#[from] async_zip::error::ZipError),
 
@@ -34,4 +43,21 @@ pub enum InstallerError {
     NoPid,
 }
 
+impl InstallerError {
+    /// Whether the pipeline stopped because its
+    /// [`CancellationToken`](lighty_core::CancellationToken) was cancelled,
+    /// whichever layer noticed it first.
+    pub fn is_cancelled(&self) -> bool {
+        use lighty_core::DownloadError;
+        use lighty_loaders::utils::error::QueryError;
+
+        matches!(
+            self,
+            Self::Cancelled(_)
+                | Self::Download(DownloadError::Cancelled(_))
+                | Self::Query(QueryError::Cancelled(_))
+        )
+    }
+}
+
 pub type InstallerResult<T> = std::result::Result<T, InstallerError>;
@@ -3,33 +3,69 @@
 
 //! Configuration for the installer module
 
-use once_cell::sync::OnceCell;
+use std::sync::RwLock;
+
+use lighty_core::scheduler::{DownloadScheduler, SchedulerLimits};
+use once_cell::sync::Lazy;
 
 /// Tuning knobs for the parallel file downloader.
+///
+/// The transfer limits (`max_concurrent_downloads`,
+/// `max_connections_per_host`, `max_bytes_per_second`) are enforced by the
+/// process-wide [`DownloadScheduler`], so they hold across every install
+/// running at the same time.
 #[derive(Debug, Clone, Copy)]
 pub struct DownloaderConfig {
-    /// Maximum number of concurrent downloads (default: 50).
+    /// Maximum number of concurrent downloads, all installs combined (default: 50).
     pub max_concurrent_downloads: usize,
+    /// Maximum concurrent downloads from a single host; `0` means no cap (default: 0).
+    pub max_connections_per_host: usize,
+    /// Total download bandwidth in bytes per second; `None` means unlimited (default: `None`).
+    pub max_bytes_per_second: Option<u64>,
     /// Maximum retry attempts per failed download (default: 3).
     pub max_retries: u32,
     /// Initial retry delay in milliseconds; grows exponentially with jitter (default: 20).
     pub initial_delay_ms: u64,
+    /// Worker threads for the pre-install hash pass; `0` uses every core (default: 0).
+    pub verify_threads: usize,
+    /// Trust a persisted `(size, mtime, hash)` index so unchanged files are
+    /// not rehashed on each launch (default: false).
+    pub verify_index: bool,
 }
 
 impl Default for DownloaderConfig {
     fn default() -> Self {
         Self {
             max_concurrent_downloads: 50,
+            max_connections_per_host: 0,
+            max_bytes_per_second: None,
             max_retries: 3,
             initial_delay_ms: 20,
+            verify_threads: 0,
+            verify_index: false,
+        }
+    }
+}
+
+impl DownloaderConfig {
+    fn scheduler_limits(&self) -> SchedulerLimits {
+        SchedulerLimits {
+            max_concurrent_downloads: self.max_concurrent_downloads,
+            max_connections_per_host: self.max_connections_per_host,
+            max_bytes_per_second: self.max_bytes_per_second,
         }
     }
 }
 
-/// Global downloader configuration; populated once on startup.
-static DOWNLOADER_CONFIG: OnceCell<DownloaderConfig> = OnceCell::new();
+/// Global downloader configuration; defaults until replaced.
+static DOWNLOADER_CONFIG: Lazy<RwLock<DownloaderConfig>> =
+    Lazy::new(|| RwLock::new(DownloaderConfig::default()));
 
-/// Installs the downloader configuration. Call this once at startup.
+/// Installs the downloader configuration.
+///
+/// Can be called again at any time: transfer limits apply to queued
+/// downloads immediately, retry settings to the next attempt.
+/// `verify_threads` is read once, by the first verification pass.
 ///
 /// # Example
 ///
@@ -40,13 +76,31 @@ static DOWNLOADER_CONFIG: OnceCell<DownloaderConfig> = OnceCell::new();
 ///     max_concurrent_downloads: 100,
 ///     max_retries: 5,
 ///     initial_delay_ms: 50,
+///     verify_index: true,
+///     ..Default::default()
 /// });
 /// ```
 pub fn init_downloader_config(config: DownloaderConfig) {
-    DOWNLOADER_CONFIG.set(config).ok();
+    update_downloader_config(|current| *current = config);
+}
+
+/// Edits the active configuration in place, e.g. to throttle bandwidth
+/// while the game is running.
+///
+/// # Example
+///
+/// ```no_run
+/// use lighty_launch::installer::config::update_downloader_config;
+///
+/// update_downloader_config(|config| config.max_bytes_per_second = Some(512 * 1024));
+/// ```
+pub fn update_downloader_config(edit: impl FnOnce(&mut DownloaderConfig)) {
+    let mut config = DOWNLOADER_CONFIG.write().unwrap_or_else(|e| e.into_inner());
+    edit(&mut config);
+    DownloadScheduler::global().set_limits(config.scheduler_limits());
 }
 
 /// Returns the active downloader configuration (defaults if uninitialized).
-pub(crate) fn get_config() -> DownloaderConfig {
-    *DOWNLOADER_CONFIG.get_or_init(DownloaderConfig::default)
+pub fn get_config() -> DownloaderConfig {
+    *DOWNLOADER_CONFIG.read().unwrap_or_else(|e| e.into_inner())
 }
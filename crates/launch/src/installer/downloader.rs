@@ -3,17 +3,18 @@
 
 //! File download utilities with retry logic and concurrency control
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "events")]
 use std::sync::Arc;
 #[cfg(feature = "events")]
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio::fs;
-use tokio::io::{AsyncWriteExt, BufWriter};
-use tokio::sync::Semaphore;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use futures::future::try_join_all;
-use futures::StreamExt;
-use lighty_core::hosts::{HTTP_CLIENT as CLIENT, build_fallback_urls};
-use lighty_core::mkdir;
+use lighty_core::cancel::run_until_cancelled;
+use lighty_core::download::{download_to_path, DownloadExpectation};
+use lighty_core::DownloadError;
+use lighty_core::scheduler::{with_priority, DownloadPriority};
+use lighty_core::store::{shared_store, SharedStore};
+use lighty_core::ExpectedHash;
 use crate::errors::InstallerResult;
 use crate::errors::InstallerError;
 use super::config::get_config;
@@ -66,6 +67,32 @@ impl<'a> ProgressState<'a> {
     }
 }
 
+/// A single file to fetch into an instance.
+#[derive(Debug, Clone)]
+pub struct DownloadTask {
+    pub url: String,
+    pub dest: PathBuf,
+    /// Strongest hash from the version metadata, checked before the file
+    /// is moved into place.
+    pub hash: Option<ExpectedHash>,
+    /// Expected size from the version metadata.
+    pub size: Option<u64>,
+}
+
+impl DownloadTask {
+    pub fn new(url: String, dest: PathBuf, hash: Option<ExpectedHash>, size: Option<u64>) -> Self {
+        Self { url, dest, hash, size }
+    }
+
+    fn expectation(&self) -> DownloadExpectation {
+        DownloadExpectation {
+            // A zero size in metadata means "unknown"
+            size: self.size.filter(|size| *size > 0),
+            hash: self.hash.clone(),
+        }
+    }
+}
+
 /// Calculate exponential backoff delay with jitter to prevent thundering herd
 fn calculate_retry_delay(base_delay_ms: u64, attempt: u32) -> u64 {
     let exponential_delay = base_delay_ms * 2u64.pow(attempt - 1);
@@ -74,241 +101,120 @@ fn calculate_retry_delay(base_delay_ms: u64, attempt: u32) -> u64 {
     exponential_delay + jitter
 }
 
-/// Downloads small files (loaded entirely in memory)
-pub async fn download_small_file(
-    url: String,
-    dest: PathBuf,
+/// Downloads a single file with retries.
+///
+/// Each attempt streams to disk through [`download_to_path`], which walks the
+/// mirror candidates, resumes broken transfers and verifies the expected
+/// SHA1/size before the file is moved into place.
+///
+/// With a [shared store](lighty_core::store) enabled, a task with a known
+/// hash is downloaded into the store once and linked at its destination.
+pub async fn download_file(
+    task: &DownloadTask,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
-    let config = get_config();
-    let mut last_error = None;
-    let candidates = build_fallback_urls(&url);
+    let Some((store, hash)) = shared_store().zip(task.hash.clone()) else {
+        return download_with_retries(
+            task,
+            &task.dest,
+            #[cfg(feature = "events")]
+            event_bus,
+        )
+        .await;
+    };
 
-    for attempt in 1..=config.max_retries {
-        for candidate in &candidates {
-            match download_small_file_once(
-                candidate,
-                &dest,
+    {
+        // Another instance may be fetching the same object right now
+        let _object = store.lock_object(&hash).await;
+        if !store.contains(&hash) {
+            download_with_retries(
+                task,
+                &store.object_path(&hash),
                 #[cfg(feature = "events")]
                 event_bus,
             )
-            .await
-            {
-                Ok(_) => return Ok(()),
-                Err(e) => {
-                    last_error = Some(e);
-                    if candidates.len() > 1 && candidate != &url {
-                        lighty_core::trace_warn!(
-                            "[Mirror] Failed to download via {}: {}",
-                            candidate,
-                            last_error.as_ref().unwrap()
-                        );
-                    }
-                }
-            }
-        }
-
-        if attempt < config.max_retries {
-            let delay = calculate_retry_delay(config.initial_delay_ms, attempt);
-            lighty_core::trace_warn!(
-                "[Retry {}/{}] Failed to download {} after {} mirror(s). Retrying in {}ms...",
-                attempt,
-                config.max_retries,
-                url,
-                candidates.len(),
-                delay
-            );
-            tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+            .await?;
         }
     }
-
-    Err(last_error.unwrap_or_else(|| {
-        InstallerError::DownloadFailed(format!(
-            "Download failed after {} retries without specific error details: {}",
-            config.max_retries, url
-        ))
-    }))
+    link_from_store(store, hash, task.dest.clone()).await
 }
 
-async fn download_small_file_once(
-    url: &str,
-    dest: &PathBuf,
-    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
-) -> InstallerResult<()> {
-    let bytes = CLIENT.get(url).send().await?.bytes().await?;
-
-    // Emit install progress event for the entire file
-    #[cfg(feature = "events")]
-    if let Some(bus) = event_bus {
-        bus.emit(Event::Launch(LaunchEvent::InstallProgress {
-            bytes: bytes.len() as u64,
-        }));
-    }
-
-    if let Some(parent) = dest.parent() {
-        mkdir!(parent);
-    }
-
-    fs::write(dest, bytes).await?;
+/// Links the stored object with `hash` at `dest` on the blocking pool.
+async fn link_from_store(store: SharedStore, hash: ExpectedHash, dest: PathBuf) -> InstallerResult<()> {
+    tokio::task::spawn_blocking(move || store.link(&hash, &dest))
+        .await
+        .map_err(|e| InstallerError::DownloadFailed(format!("Store link task failed: {}", e)))??;
     Ok(())
 }
 
-/// Downloads large files with streaming (memory efficient)
-pub async fn download_large_file(
-    url: String,
-    dest: PathBuf,
+/// Fetches `task.url` into `dest`, retrying with exponential backoff.
+async fn download_with_retries(
+    task: &DownloadTask,
+    dest: &Path,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
     let config = get_config();
+    let expected = task.expectation();
     let mut last_error = None;
-    let candidates = build_fallback_urls(&url);
+
+    // Bytes already reported for this file, so resumed or restarted
+    // transfers don't count twice
+    #[cfg(feature = "events")]
+    let reported = AtomicU64::new(0);
 
     for attempt in 1..=config.max_retries {
-        for candidate in &candidates {
-            match download_large_file_once(
-                candidate,
-                &dest,
-                #[cfg(feature = "events")]
-                event_bus,
-            )
-            .await
-            {
-                Ok(_) => return Ok(()),
-                Err(e) => {
-                    last_error = Some(e);
-                    if candidates.len() > 1 && candidate != &url {
-                        lighty_core::trace_warn!(
-                            "[Mirror] Failed to download via {}: {}",
-                            candidate,
-                            last_error.as_ref().unwrap()
-                        );
-                    }
+        let result = download_to_path(&task.url, dest, &expected, |_current, _| {
+            #[cfg(feature = "events")]
+            if let Some(bus) = event_bus {
+                let previous = reported.fetch_max(_current, Ordering::Relaxed);
+                if _current > previous {
+                    bus.emit(Event::Launch(LaunchEvent::InstallProgress {
+                        bytes: _current - previous,
+                    }));
                 }
             }
+        })
+        .await;
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(DownloadError::Cancelled(e)) => return Err(e.into()),
+            Err(e) => last_error = Some(e),
         }
 
         if attempt < config.max_retries {
             let delay = calculate_retry_delay(config.initial_delay_ms, attempt);
             lighty_core::trace_warn!(
-                "[Retry {}/{}] Failed to download {} after {} mirror(s). Retrying in {}ms...",
+                "[Retry {}/{}] Failed to download {}: {}. Retrying in {}ms...",
                 attempt,
                 config.max_retries,
-                url,
-                candidates.len(),
+                task.url,
+                last_error.as_ref().unwrap(),
                 delay
             );
-            let _ = fs::remove_file(&dest).await;
-            tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+            run_until_cancelled(tokio::time::sleep(tokio::time::Duration::from_millis(delay))).await?;
         }
     }
 
-    Err(last_error.unwrap_or_else(|| {
+    Err(last_error.map(InstallerError::from).unwrap_or_else(|| {
         InstallerError::DownloadFailed(format!(
             "Download failed after {} retries without specific error details: {}",
-            config.max_retries, url
+            config.max_retries, task.url
         ))
     }))
 }
 
-async fn download_large_file_once(
-    url: &str,
-    dest: &PathBuf,
-    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
-) -> InstallerResult<()> {
-    let response = CLIENT.get(url).send().await?;
-
-    if !response.status().is_success() {
-        return Err(InstallerError::DownloadFailed(format!(
-            "HTTP {} for {}",
-            response.status(),
-            url
-        )));
-    }
-
-    if let Some(parent) = dest.parent() {
-        mkdir!(parent);
-    }
-
-    let file = fs::File::create(dest).await?;
-    let mut writer = BufWriter::with_capacity(256 * 1024, file);
-    let mut stream = response.bytes_stream();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        writer.write_all(&chunk).await?;
-
-        // Emit install progress event for this chunk
-        #[cfg(feature = "events")]
-        if let Some(bus) = event_bus {
-            bus.emit(Event::Launch(LaunchEvent::InstallProgress {
-                bytes: chunk.len() as u64,
-            }));
-        }
-    }
-
-    writer.flush().await?;
-    Ok(())
-}
-
-/// Downloads multiple large files with concurrency limit
+/// Downloads multiple files at `priority`.
+///
+/// Concurrency, per-host caps and bandwidth are enforced by the
+/// process-wide [`DownloadScheduler`](lighty_core::scheduler::DownloadScheduler),
+/// so concurrent installs share the same limits.
 pub async fn download_with_concurrency_limit(
-    tasks: Vec<(String, PathBuf)>,
+    tasks: Vec<DownloadTask>,
+    priority: DownloadPriority,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
     #[cfg(feature = "events")] progress_kind: Option<DownloadProgressKind>,
 ) -> InstallerResult<()> {
-    let config = get_config();
-    let semaphore = Arc::new(Semaphore::new(config.max_concurrent_downloads));
-    #[cfg(feature = "events")]
-    let progress = match (event_bus, progress_kind) {
-        (Some(bus), Some(kind)) if !tasks.is_empty() => Some(ProgressState {
-            bus,
-            kind,
-            total: tasks.len(),
-            current: Arc::new(AtomicUsize::new(0)),
-        }),
-        _ => None,
-    };
-    let futures: Vec<_> = tasks
-        .into_iter()
-        .map(|(url, dest)| {
-            let sem = semaphore.clone();
-            #[cfg(feature = "events")]
-            let progress = progress.clone();
-            async move {
-                let _permit = sem.acquire().await
-                    .map_err(|_| InstallerError::DownloadFailed(
-                        "Download concurrency semaphore closed".into()
-                    ))?;
-                download_large_file(
-                    url,
-                    dest,
-                    #[cfg(feature = "events")]
-                    event_bus,
-                )
-                .await?;
-
-                #[cfg(feature = "events")]
-                if let Some(progress) = &progress {
-                    progress.emit_progress();
-                }
-
-                Ok::<(), InstallerError>(())
-            }
-        })
-        .collect();
-
-    try_join_all(futures).await?;
-    Ok(())
-}
-
-/// Downloads multiple small files with concurrency limit
-pub async fn download_small_with_concurrency_limit(
-    tasks: Vec<(String, PathBuf)>,
-    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
-    #[cfg(feature = "events")] progress_kind: Option<DownloadProgressKind>,
-) -> InstallerResult<()> {
-    let config = get_config();
-    let semaphore = Arc::new(Semaphore::new(config.max_concurrent_downloads));
     #[cfg(feature = "events")]
     let progress = match (event_bus, progress_kind) {
         (Some(bus), Some(kind)) if !tasks.is_empty() => Some(ProgressState {
@@ -321,20 +227,17 @@ pub async fn download_small_with_concurrency_limit(
     };
     let futures: Vec<_> = tasks
         .into_iter()
-        .map(|(url, dest)| {
-            let sem = semaphore.clone();
+        .map(|task| {
             #[cfg(feature = "events")]
             let progress = progress.clone();
             async move {
-                let _permit = sem.acquire().await
-                    .map_err(|_| InstallerError::DownloadFailed(
-                        "Download concurrency semaphore closed".into()
-                    ))?;
-                download_small_file(
-                    url,
-                    dest,
-                    #[cfg(feature = "events")]
-                    event_bus,
+                with_priority(
+                    priority,
+                    download_file(
+                        &task,
+                        #[cfg(feature = "events")]
+                        event_bus,
+                    ),
                 )
                 .await?;
 
@@ -0,0 +1,464 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Garbage collection of files no instance references any more.
+//!
+//! Nothing in the install pipeline ever deletes a file: old library
+//! versions left by loader updates, asset objects dropped from every
+//! index, stale Forge/NeoForge installer JARs and unused JREs pile up.
+//! [`GarbageCollector`] resolves the [`Version`] of every instance the
+//! caller knows about, computes the set of files they reference, and
+//! reports (or deletes) everything else in the directories the launcher
+//! manages:
+//!
+//! | Area | Scanned | Kept |
+//! |------|---------|------|
+//! | Libraries | `libraries_dir()` of each instance | Libraries and natives of every instance, Forge-family processor files |
+//! | Assets | `assets_dir()/objects`, `assets_dir()/indexes` | Objects and index of every instance |
+//! | Natives | `game_dirs/natives` (re-extracted on each launch) | — |
+//! | Installers | `*-installer.jar` in `game_dirs/.forge`, `.neoforge` | The installer of the current loader version |
+//! | Java | `<distribution>_<major>` runtimes in `java_dirs()` | Every runtime of a major version some instance needs |
+//! | Store | `objects/` of the [shared store](lighty_core::store) | Every hash referenced above, mods included |
+//!
+//! Files belonging to a running instance in the instance manager are never
+//! touched: its game directory and Java runtime, and — when it is not one
+//! of the instances given to the collector, so its references are unknown —
+//! its libraries, assets and the whole shared store.
+//!
+//! ```rust,ignore
+//! use lighty_launch::installer::gc::{GarbageCollector, GcMode};
+//!
+//! let mut gc = GarbageCollector::new();
+//! gc.add_instance(&vanilla).await?;
+//! gc.add_instance(&fabric).await?;
+//!
+//! let report = gc.gc(GcMode::DryRun).await?;
+//! println!("{} reclaimable", report.reclaimable_bytes);
+//!
+//! gc.gc(GcMode::Delete).await?;
+//! ```
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use lighty_core::store::{shared_store, SharedStore};
+use lighty_core::ExpectedHash;
+use lighty_loaders::types::version_metadata::{Digests, Version, VersionMetaData};
+use lighty_loaders::types::{Loader, LoaderExtensions, VersionInfo};
+
+use crate::errors::{InstallerError, InstallerResult};
+use crate::instance::manager::GameInstance;
+use crate::instance::INSTANCE_MANAGER;
+
+/// Whether [`GarbageCollector::gc`] deletes anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcMode {
+    /// Only report what would be deleted.
+    DryRun,
+    /// Delete unreferenced files and report them.
+    Delete,
+}
+
+/// Category of a collected file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GcKind {
+    Library,
+    AssetObject,
+    AssetIndex,
+    Natives,
+    Installer,
+    JavaRuntime,
+    StoreObject,
+}
+
+/// One unreferenced file or directory.
+#[derive(Debug, Clone)]
+pub struct GcEntry {
+    pub path: PathBuf,
+    pub kind: GcKind,
+    /// Size on disk; the whole tree for directories.
+    pub bytes: u64,
+}
+
+/// Outcome of a [`GarbageCollector::gc`] pass.
+#[derive(Debug, Clone, Default)]
+pub struct GcReport {
+    /// Unreferenced entries (deleted ones in [`GcMode::Delete`]).
+    pub entries: Vec<GcEntry>,
+    /// Total size of `entries`.
+    pub reclaimable_bytes: u64,
+    /// Bytes actually freed; `0` in a dry run.
+    pub deleted_bytes: u64,
+    /// Unreferenced paths left alone because a running instance may use them.
+    pub skipped: Vec<PathBuf>,
+    /// Entries that could not be deleted, with the reason.
+    pub failures: Vec<(PathBuf, String)>,
+}
+
+/// Everything the known instances reference, plus where to look for garbage.
+#[derive(Debug, Default)]
+pub struct GarbageCollector {
+    instance_names: HashSet<String>,
+    keep_files: HashSet<PathBuf>,
+    /// Directories whose whole subtree is kept.
+    keep_trees: Vec<PathBuf>,
+    keep_hashes: HashSet<ExpectedHash>,
+    keep_java_majors: HashSet<u8>,
+    library_dirs: HashSet<PathBuf>,
+    asset_dirs: HashSet<PathBuf>,
+    game_dirs: HashSet<PathBuf>,
+    java_dirs: HashSet<PathBuf>,
+}
+
+impl GarbageCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves `version`'s metadata and records every file it references.
+    ///
+    /// Add every instance the launcher knows about: files only referenced
+    /// by an instance that was not added are collected.
+    pub async fn add_instance<V>(&mut self, version: &V) -> InstallerResult<()>
+    where
+        V: VersionInfo<LoaderType = Loader> + LoaderExtensions,
+    {
+        let metadata = version.get_metadata().await?;
+        let VersionMetaData::Version(version_data) = metadata.as_ref() else {
+            return Err(InstallerError::InvalidMetadata);
+        };
+
+        let libraries_dir = version.libraries_dir();
+        let assets_dir = version.assets_dir();
+
+        self.instance_names.insert(version.name().to_string());
+        self.library_dirs.insert(libraries_dir.clone());
+        self.asset_dirs.insert(assets_dir.clone());
+        self.game_dirs.insert(version.game_dirs().to_path_buf());
+        self.java_dirs.insert(version.java_dirs().to_path_buf());
+        self.keep_java_majors.insert(version_data.java_version.major_version);
+
+        self.keep_version(version_data, &libraries_dir, &assets_dir);
+
+        #[cfg(any(feature = "modrinth", feature = "curseforge"))]
+        for user_mod in crate::installer::ressources::mod_resolver::last_resolved_mods(version.game_dirs()).await {
+            self.keep_hashes.extend(user_mod.strongest_hash());
+        }
+
+        self.keep_installers(version).await?;
+        Ok(())
+    }
+
+    fn keep_version(&mut self, data: &Version, libraries_dir: &Path, assets_dir: &Path) {
+        for lib in &data.libraries {
+            if let Some(path) = &lib.path {
+                self.keep_files.insert(libraries_dir.join(path));
+            }
+            self.keep_hashes.extend(lib.strongest_hash());
+        }
+        for native in data.natives.iter().flatten() {
+            if let Some(path) = &native.path {
+                self.keep_files.insert(libraries_dir.join(path));
+            }
+            self.keep_hashes.extend(native.strongest_hash());
+        }
+        for mod_entry in data.mods.iter().flatten() {
+            self.keep_hashes.extend(mod_entry.strongest_hash());
+        }
+        if let Some(client) = &data.client {
+            self.keep_hashes.extend(client.strongest_hash());
+        }
+        if let Some(logging) = &data.logging {
+            self.keep_hashes.insert(ExpectedHash::sha1(logging.sha1.clone()));
+        }
+
+        if let Some(index) = &data.assets_index {
+            self.keep_files
+                .insert(assets_dir.join("indexes").join(format!("{}.json", index.id)));
+        }
+        for asset in data.assets.iter().flat_map(|assets| assets.objects.values()) {
+            let Some(prefix) = asset.hash.get(..2) else { continue };
+            self.keep_files
+                .insert(assets_dir.join("objects").join(prefix).join(&asset.hash));
+            self.keep_hashes.insert(ExpectedHash::sha1(asset.hash.clone()));
+        }
+    }
+
+    /// Keeps the current Forge/NeoForge installer and the files its
+    /// processors produced.
+    async fn keep_installers<V>(&mut self, version: &V) -> InstallerResult<()>
+    where
+        V: VersionInfo<LoaderType = Loader> + LoaderExtensions,
+    {
+        match version.loader() {
+            #[cfg(feature = "forge")]
+            Loader::Forge => {
+                use lighty_loaders::forge::forge::{installer_cache_path, ForgeRawData, FORGE, FORGE_EXTRACT_SUBDIR};
+
+                let installer_path = installer_cache_path(version);
+                self.keep_files.insert(installer_path.clone());
+                if let ForgeRawData::Modern { install_profile, .. } = FORGE.get_raw(version).await?.as_ref() {
+                    use lighty_loaders::forge::forge::extract_install_profile_libraries_modern;
+                    let libs = extract_install_profile_libraries_modern(install_profile);
+                    self.keep_forge_family(version, install_profile, &libs, installer_path, FORGE_EXTRACT_SUBDIR);
+                }
+            }
+            #[cfg(feature = "neoforge")]
+            Loader::NeoForge => {
+                use lighty_loaders::neoforge::neoforge::{
+                    extract_install_profile_libraries, installer_cache_path, NEOFORGE, NEOFORGE_EXTRACT_SUBDIR,
+                };
+
+                let installer_path = installer_cache_path(version);
+                self.keep_files.insert(installer_path.clone());
+                let install_profile = NEOFORGE.get_raw(version).await?;
+                let libs = extract_install_profile_libraries(install_profile.as_ref());
+                self.keep_forge_family(version, install_profile.as_ref(), &libs, installer_path, NEOFORGE_EXTRACT_SUBDIR);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    #[cfg(any(feature = "forge", feature = "neoforge"))]
+    fn keep_forge_family(
+        &mut self,
+        version: &impl VersionInfo,
+        install_profile: &lighty_loaders::utils::forge_installer::ForgeInstallProfile,
+        profile_libs: &[lighty_loaders::types::version_metadata::Library],
+        installer_path: PathBuf,
+        extract_subdir: &str,
+    ) {
+        use crate::installer::processors::processor::ProcessorContext;
+
+        let libraries_dir = version.libraries_dir();
+        for lib in profile_libs {
+            if let Some(path) = &lib.path {
+                self.keep_files.insert(libraries_dir.join(path));
+            }
+            self.keep_hashes.extend(lib.strongest_hash());
+        }
+
+        // No JVM is spawned; the Java path is never used
+        let context = ProcessorContext::new(version, installer_path, install_profile, "", extract_subdir, PathBuf::new());
+        self.keep_files.extend(context.produced_paths(install_profile));
+        self.keep_trees.push(context.extracts_dir());
+    }
+
+    /// Computes the unreferenced files and, in [`GcMode::Delete`], removes them.
+    pub async fn gc(&self, mode: GcMode) -> InstallerResult<GcReport> {
+        let plan = Plan {
+            keep_files: self.keep_files.clone(),
+            keep_trees: self.keep_trees.clone(),
+            keep_hashes: self.keep_hashes.clone(),
+            keep_java_majors: self.keep_java_majors.clone(),
+            library_dirs: self.library_dirs.clone(),
+            asset_dirs: self.asset_dirs.clone(),
+            game_dirs: self.game_dirs.clone(),
+            java_dirs: self.java_dirs.clone(),
+            instance_names: self.instance_names.clone(),
+            running: INSTANCE_MANAGER.snapshot(),
+            store: shared_store(),
+        };
+
+        tokio::task::spawn_blocking(move || plan.execute(mode))
+            .await
+            .map_err(|e| InstallerError::Io(std::io::Error::other(format!("GC task failed: {}", e))))
+    }
+}
+
+/// Owned copy of the collector state, processed on the blocking pool.
+struct Plan {
+    keep_files: HashSet<PathBuf>,
+    keep_trees: Vec<PathBuf>,
+    keep_hashes: HashSet<ExpectedHash>,
+    keep_java_majors: HashSet<u8>,
+    library_dirs: HashSet<PathBuf>,
+    asset_dirs: HashSet<PathBuf>,
+    game_dirs: HashSet<PathBuf>,
+    java_dirs: HashSet<PathBuf>,
+    instance_names: HashSet<String>,
+    running: Vec<GameInstance>,
+    store: Option<SharedStore>,
+}
+
+impl Plan {
+    fn execute(&self, mode: GcMode) -> GcReport {
+        let protected = self.protected_roots();
+        let mut report = GcReport::default();
+
+        let mut candidates = Vec::new();
+        for dir in &self.library_dirs {
+            self.collect_unkept(dir, GcKind::Library, &mut candidates);
+        }
+        for dir in &self.asset_dirs {
+            self.collect_unkept(&dir.join("objects"), GcKind::AssetObject, &mut candidates);
+            self.collect_unkept(&dir.join("indexes"), GcKind::AssetIndex, &mut candidates);
+        }
+        for game_dir in &self.game_dirs {
+            let natives = game_dir.join("natives");
+            if natives.is_dir() {
+                candidates.push((natives, GcKind::Natives));
+            }
+            for dot_dir in [".forge", ".neoforge"] {
+                for file in list_dir(&game_dir.join(dot_dir)) {
+                    let is_installer = file
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.ends_with("-installer.jar"));
+                    if is_installer && !self.keep_files.contains(&file) {
+                        candidates.push((file, GcKind::Installer));
+                    }
+                }
+            }
+        }
+        for java_dir in &self.java_dirs {
+            for runtime in list_dir(java_dir) {
+                if runtime.is_dir() && !self.keeps_runtime(&runtime) {
+                    candidates.push((runtime, GcKind::JavaRuntime));
+                }
+            }
+        }
+        // Last, so dry-run and delete agree: tree links are gone before their objects
+        if let Some(store) = &self.store {
+            let keep_objects: HashSet<PathBuf> =
+                self.keep_hashes.iter().map(|hash| store.object_path(hash)).collect();
+            let mut objects = Vec::new();
+            walk_files(&store.objects_dir(), &mut objects);
+            candidates.extend(
+                objects
+                    .into_iter()
+                    .filter(|path| !keep_objects.contains(path))
+                    .map(|path| (path, GcKind::StoreObject)),
+            );
+        }
+
+        for (path, kind) in candidates {
+            if protected.iter().any(|root| path.starts_with(root) || root.starts_with(&path)) {
+                report.skipped.push(path);
+                continue;
+            }
+
+            let bytes = disk_usage(&path);
+            if mode == GcMode::Delete {
+                let result = if path.is_dir() {
+                    std::fs::remove_dir_all(&path)
+                } else {
+                    std::fs::remove_file(&path)
+                };
+                match result {
+                    Ok(()) => report.deleted_bytes += bytes,
+                    Err(e) => {
+                        report.failures.push((path, e.to_string()));
+                        continue;
+                    }
+                }
+            }
+
+            report.reclaimable_bytes += bytes;
+            report.entries.push(GcEntry { path, kind, bytes });
+        }
+
+        if mode == GcMode::Delete {
+            for dir in self.library_dirs.iter().chain(&self.asset_dirs) {
+                remove_empty_dirs(dir);
+            }
+            if let Some(store) = &self.store {
+                remove_empty_dirs(&store.objects_dir());
+            }
+        }
+
+        lighty_core::trace_info!(
+            entries = report.entries.len(),
+            reclaimable = report.reclaimable_bytes,
+            deleted = report.deleted_bytes,
+            skipped = report.skipped.len(),
+            "[GC] Pass complete"
+        );
+        report
+    }
+
+    /// Paths a running instance may be using.
+    fn protected_roots(&self) -> Vec<PathBuf> {
+        let mut roots = Vec::new();
+        for instance in &self.running {
+            roots.push(instance.game_dir.clone());
+            // Runtime dir = first component below a java dir
+            for java_dir in &self.java_dirs {
+                if let Ok(relative) = instance.java_path.strip_prefix(java_dir) {
+                    if let Some(first) = relative.components().next() {
+                        roots.push(java_dir.join(first));
+                    }
+                }
+            }
+            if !self.instance_names.contains(&instance.instance_name) {
+                roots.push(instance.libraries_dir.clone());
+                roots.push(instance.assets_dir.clone());
+                if let Some(store) = &self.store {
+                    roots.push(store.root().to_path_buf());
+                }
+            }
+        }
+        roots
+    }
+
+    fn collect_unkept(&self, dir: &Path, kind: GcKind, out: &mut Vec<(PathBuf, GcKind)>) {
+        let mut files = Vec::new();
+        walk_files(dir, &mut files);
+        out.extend(
+            files
+                .into_iter()
+                .filter(|path| !self.keep_files.contains(path))
+                .filter(|path| !self.keep_trees.iter().any(|tree| path.starts_with(tree)))
+                .map(|path| (path, kind)),
+        );
+    }
+
+    /// Runtimes are named `<distribution>_<major>`; anything else is not ours.
+    fn keeps_runtime(&self, runtime: &Path) -> bool {
+        let major = runtime
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.rsplit_once('_'))
+            .and_then(|(_, major)| major.parse::<u8>().ok());
+        match major {
+            Some(major) => self.keep_java_majors.contains(&major),
+            None => true,
+        }
+    }
+}
+
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Collects regular files and symlinks under `dir`, without following links.
+fn walk_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    for path in list_dir(dir) {
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.is_dir() => walk_files(&path, out),
+            Ok(_) => out.push(path),
+            Err(_) => {}
+        }
+    }
+}
+
+fn disk_usage(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => list_dir(path).iter().map(|child| disk_usage(child)).sum(),
+        Ok(meta) if meta.is_file() => meta.len(),
+        _ => 0,
+    }
+}
+
+/// Removes directories left empty below `root` (but not `root` itself).
+fn remove_empty_dirs(root: &Path) {
+    for path in list_dir(root) {
+        if std::fs::symlink_metadata(&path).is_ok_and(|meta| meta.is_dir()) {
+            remove_empty_dirs(&path);
+            let _ = std::fs::remove_dir(&path);
+        }
+    }
+}
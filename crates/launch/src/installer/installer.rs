@@ -9,11 +9,19 @@
 //! - Client JAR (game executable)
 //! - Assets (textures, sounds, etc.)
 //! - Mods (optional modifications)
+//! - Logging configuration (log4j XML layout)
 
-use super::ressources::{libraries, natives, client, assets, mods};
+use super::ressources::{libraries, natives, client, assets, mods, logging};
+use super::verifier::Verifier;
+#[cfg(feature = "events")]
+use super::downloader::DownloadTask;
 use lighty_loaders::types::{VersionInfo, version_metadata::Version};
 use lighty_core::{mkdir, time_it};
-use lighty_core::hosts::HTTP_CLIENT;
+use lighty_core::cancel::checkpoint;
+use lighty_core::offline::ensure_online;
+#[cfg(feature = "events")]
+use lighty_core::hosts::http_client;
+#[cfg(feature = "events")]
 use reqwest::header::ACCEPT_ENCODING;
 use crate::errors::InstallerResult;
 
@@ -44,120 +52,166 @@ impl<T: VersionInfo> Installer for T {
         builder: &Version,
         #[cfg(feature = "events")] event_bus: Option<&EventBus>,
     ) -> InstallerResult<()> {
-        lighty_core::trace_info!("[Installer] Starting installation for {}", self.name());
-
-        create_directories(self).await;
-
-        // Phase 1: Collect all tasks (single SHA1 verification pass)
-        lighty_core::trace_info!("[Installer] Verifying installed files...");
-        let (library_tasks, client_task, asset_tasks, mod_tasks, (native_download_tasks, native_extract_paths)) = tokio::join!(
-            libraries::collect_library_tasks(self, &builder.libraries),
-            client::collect_client_task(self, builder.client.as_ref()),
-            assets::collect_asset_tasks(self, builder.assets.as_ref()),
-            mods::collect_mod_tasks(self, builder.mods.as_deref().unwrap_or(&[])),
-            natives::collect_native_tasks(self, builder.natives.as_deref().unwrap_or(&[])),
-        );
-
-        // Count total downloads needed
-        let total_downloads = library_tasks.len()
-            + client_task.as_ref().map(|_| 1).unwrap_or(0)
-            + asset_tasks.len()
-            + mod_tasks.len()
-            + native_download_tasks.len();
-
-        // Phase 2: Decide if installation is needed
-        if total_downloads == 0 {
-            // Everything is already installed, just need to extract natives
+        let verifier = Verifier::for_instance(self).await;
+        install_with(
+            self,
+            builder,
+            &verifier,
             #[cfg(feature = "events")]
-            if let Some(bus) = event_bus {
-                bus.emit(Event::Launch(LaunchEvent::IsInstalled {
-                    version: self.name().to_string(),
-                }));
-            }
+            event_bus,
+        )
+        .await
+    }
+}
 
-            lighty_core::trace_info!("[Installer] ✓ All files already up-to-date");
+/// [`Installer::install`] with a caller-provided [`Verifier`], so a repair
+/// pass can collect what it found.
+pub(crate) async fn install_with<T: VersionInfo>(
+    version: &T,
+    builder: &Version,
+    verifier: &Verifier,
+    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
+) -> InstallerResult<()> {
+    lighty_core::trace_info!("[Installer] Starting installation for {}", version.name());
 
-            // Still need to extract natives (they're cleaned on each run)
-            if !native_extract_paths.is_empty() {
-                natives::download_and_extract_natives(
-                    self,
-                    native_download_tasks,
-                    native_extract_paths,
-                    #[cfg(feature = "events")]
-                    event_bus,
-                )
-                    .await?;
-            }
+    create_directories(version).await;
 
-            lighty_core::trace_info!("[Installer] Installation completed successfully!");
-            return Ok(());
-        }
+    // Phase 1: Collect all tasks (single parallel hash verification pass)
+    lighty_core::trace_info!("[Installer] Verifying installed files...");
+    let (library_tasks, client_task, logging_task, asset_tasks, mod_tasks, (native_download_tasks, native_extract_paths)) = time_it!("Verification", tokio::join!(
+        libraries::collect_library_tasks(version, &builder.libraries, verifier),
+        client::collect_client_task(version, builder.client.as_ref(), verifier),
+        logging::collect_logging_task(version, builder.logging.as_ref(), verifier),
+        assets::collect_asset_tasks(version, builder.assets.as_ref(), verifier),
+        mods::collect_mod_tasks(version, builder.mods.as_deref().unwrap_or(&[]), verifier),
+        natives::collect_native_tasks(version, builder.natives.as_deref().unwrap_or(&[]), verifier),
+    ));
+    verifier.save().await;
+    checkpoint().await?;
 
-        // Phase 3: Download needed files
-        #[cfg(feature = "events")]
-        let total_bytes = calculate_download_size(
-            builder,
-            &library_tasks,
-            &client_task,
-            &asset_tasks,
-            &mod_tasks,
-            &native_download_tasks,
-        ).await;
+    // Count total downloads needed
+    let total_downloads = library_tasks.len()
+        + client_task.as_ref().map(|_| 1).unwrap_or(0)
+        + logging_task.as_ref().map(|_| 1).unwrap_or(0)
+        + asset_tasks.len()
+        + mod_tasks.len()
+        + native_download_tasks.len();
 
+    // Phase 2: Decide if installation is needed
+    if total_downloads == 0 {
+        // Everything is already installed, just need to extract natives
         #[cfg(feature = "events")]
         if let Some(bus) = event_bus {
-            bus.emit(Event::Launch(LaunchEvent::InstallStarted {
-                version: self.name().to_string(),
-                total_bytes,
+            bus.emit(Event::Launch(LaunchEvent::IsInstalled {
+                version: version.name().to_string(),
             }));
         }
 
-        lighty_core::trace_info!("[Installer] Downloading {} file(s)...", total_downloads);
+        lighty_core::trace_info!("[Installer] ✓ All files already up-to-date");
 
-        time_it!("Total installation", {
-            // Download and install in parallel
-            tokio::try_join!(
-                libraries::download_libraries(
-                    library_tasks,
-                    #[cfg(feature = "events")]
-                    event_bus
-                ),
-                natives::download_and_extract_natives(
-                    self,
-                    native_download_tasks,
-                    native_extract_paths,
-                    #[cfg(feature = "events")]
-                    event_bus
-                ),
-                mods::download_mods(
-                    mod_tasks,
-                    #[cfg(feature = "events")]
-                    event_bus
-                ),
-                client::download_client(
-                    client_task,
-                    #[cfg(feature = "events")]
-                    event_bus
-                ),
-                assets::download_assets(
-                    asset_tasks,
-                    #[cfg(feature = "events")]
-                    event_bus
-                ),
-            )?;
-        });
-
-        #[cfg(feature = "events")]
-        if let Some(bus) = event_bus {
-            bus.emit(Event::Launch(LaunchEvent::InstallCompleted {
-                version: self.name().to_string(),
-                total_bytes,
-            }));
+        // Still need to extract natives (they're cleaned on each run)
+        if !native_extract_paths.is_empty() {
+            natives::download_and_extract_natives(
+                version,
+                native_download_tasks,
+                native_extract_paths,
+                #[cfg(feature = "events")]
+                event_bus,
+            )
+                .await?;
         }
 
         lighty_core::trace_info!("[Installer] Installation completed successfully!");
-        Ok(())
+        return Ok(());
     }
+
+    // Offline: everything has to be on disk already
+    ensure_online(|| {
+        let first = library_tasks
+            .iter()
+            .chain(client_task.iter())
+            .chain(logging_task.iter())
+            .chain(&asset_tasks)
+            .chain(&mod_tasks)
+            .chain(&native_download_tasks)
+            .next()
+            .map(|task| task.dest.display().to_string())
+            .unwrap_or_default();
+        match total_downloads {
+            1 => first,
+            n => format!("{} (and {} other file(s))", first, n - 1),
+        }
+    })?;
+
+    // Phase 3: Download needed files
+    #[cfg(feature = "events")]
+    let total_bytes = calculate_download_size(
+        &library_tasks,
+        &client_task,
+        &logging_task,
+        &asset_tasks,
+        &mod_tasks,
+        &native_download_tasks,
+    ).await;
+
+    #[cfg(feature = "events")]
+    if let Some(bus) = event_bus {
+        bus.emit(Event::Launch(LaunchEvent::InstallStarted {
+            version: version.name().to_string(),
+            total_bytes,
+        }));
+    }
+
+    lighty_core::trace_info!("[Installer] Downloading {} file(s)...", total_downloads);
+
+    time_it!("Total installation", {
+        // Download and install in parallel
+        tokio::try_join!(
+            libraries::download_libraries(
+                library_tasks,
+                #[cfg(feature = "events")]
+                event_bus
+            ),
+            natives::download_and_extract_natives(
+                version,
+                native_download_tasks,
+                native_extract_paths,
+                #[cfg(feature = "events")]
+                event_bus
+            ),
+            mods::download_mods(
+                mod_tasks,
+                #[cfg(feature = "events")]
+                event_bus
+            ),
+            client::download_client(
+                client_task,
+                #[cfg(feature = "events")]
+                event_bus
+            ),
+            logging::download_logging_config(
+                logging_task,
+                #[cfg(feature = "events")]
+                event_bus
+            ),
+            assets::download_assets(
+                asset_tasks,
+                #[cfg(feature = "events")]
+                event_bus
+            ),
+        )?;
+    });
+
+    #[cfg(feature = "events")]
+    if let Some(bus) = event_bus {
+        bus.emit(Event::Launch(LaunchEvent::InstallCompleted {
+            version: version.name().to_string(),
+            total_bytes,
+        }));
+    }
+
+    lighty_core::trace_info!("[Installer] Installation completed successfully!");
+    Ok(())
 }
 
 /// Creates necessary installation directories
@@ -168,96 +222,38 @@ async fn create_directories(version: &impl VersionInfo) {
     // ré-écrire it via set_runtime_dir() to honour
     // arg_overrides[KEY_GAME_DIRECTORY].
     mkdir!(version.runtime_dir());
-    mkdir!(parent_path.join("libraries"));
+    // Both live in the shared store when one is enabled
+    mkdir!(version.libraries_dir());
     mkdir!(parent_path.join("natives"));
-    mkdir!(parent_path.join("assets").join("objects"));
+    mkdir!(version.assets_dir().join("objects"));
 }
 
 /// Calculates the total size of files that need to be downloaded (from tasks)
 #[cfg(feature = "events")]
 async fn calculate_download_size(
-    builder: &Version,
-    library_tasks: &[(String, std::path::PathBuf)],
-    client_task: &Option<(String, std::path::PathBuf)>,
-    asset_tasks: &[(String, std::path::PathBuf)],
-    mod_tasks: &[(String, std::path::PathBuf)],
-    native_download_tasks: &[(String, std::path::PathBuf)],
+    library_tasks: &[DownloadTask],
+    client_task: &Option<DownloadTask>,
+    logging_task: &Option<DownloadTask>,
+    asset_tasks: &[DownloadTask],
+    mod_tasks: &[DownloadTask],
+    native_download_tasks: &[DownloadTask],
 ) -> u64 {
     let mut total = 0u64;
 
-    // Libraries - match tasks with builder.libraries to get size
-    for (url, _) in library_tasks {
-        if let Some(lib) = builder.libraries.iter().find(|l| l.url.as_ref() == Some(url)) {
-            let size = lib.size.unwrap_or(0);
-            if size > 0 {
-                total += size;
-            } else {
-                // Try HEAD request to determine size
-                if let Ok(resp) = HTTP_CLIENT.head(url).header(ACCEPT_ENCODING, "identity").send().await {
-                    if let Some(len) = resp.content_length() {
-                        total += len;
-                    }
-                }
-            }
-        }
-    }
-
-    // Client
-    if client_task.is_some() {
-        if let Some(client_meta) = &builder.client {
-            let size = client_meta.size.unwrap_or(0);
-            if size > 0 {
-                total += size;
-            } else if let Some((url, _)) = client_task {
-                if let Ok(resp) = HTTP_CLIENT.head(url).header(ACCEPT_ENCODING, "identity").send().await {
-                    if let Some(len) = resp.content_length() {
-                        total += len;
-                    }
-                }
-            }
-        }
-    }
-
-    // Assets - match by URL
-    if let Some(assets) = &builder.assets {
-        for (url, _) in asset_tasks {
-            if let Some(asset) = assets.objects.values().find(|a| a.url.as_ref() == Some(url)) {
-                total += asset.size;
-            } else {
-                // try head
-                if let Ok(resp) = HTTP_CLIENT.head(url).header(ACCEPT_ENCODING, "identity").send().await {
-                    if let Some(len) = resp.content_length() {
-                        total += len;
-                    }
-                }
-            }
-        }
-    }
-
-    // Mods
-    if let Some(mods) = &builder.mods {
-        for (url, _) in mod_tasks {
-            if let Some(_mod) = mods.iter().find(|m| m.url.as_ref() == Some(url)) {
-                let size = _mod.size.unwrap_or(0);
-                if size > 0 {
-                    total += size;
-                } else if let Ok(resp) = HTTP_CLIENT.head(url).header(ACCEPT_ENCODING, "identity").send().await {
-                    if let Some(len) = resp.content_length() {
-                        total += len;
-                    }
-                }
-            }
-        }
-    }
+    let tasks = library_tasks
+        .iter()
+        .chain(client_task.iter())
+        .chain(logging_task.iter())
+        .chain(asset_tasks)
+        .chain(mod_tasks)
+        .chain(native_download_tasks);
 
-    // Natives
-    if let Some(natives) = &builder.natives {
-        for (url, _) in native_download_tasks {
-            if let Some(native) = natives.iter().find(|n| n.url.as_ref() == Some(url)) {
-                let size = native.size.unwrap_or(0);
-                if size > 0 {
-                    total += size;
-                } else if let Ok(resp) = HTTP_CLIENT.head(url).header(ACCEPT_ENCODING, "identity").send().await {
+    for task in tasks {
+        match task.size {
+            Some(size) if size > 0 => total += size,
+            // Size unknown from metadata: try HEAD request to determine size
+            _ => {
+                if let Ok(resp) = http_client().head(&task.url).header(ACCEPT_ENCODING, "identity").send().await {
                     if let Some(len) = resp.content_length() {
                         total += len;
                     }
@@ -3,8 +3,10 @@
 
 pub mod installer;
 pub mod config;
-mod downloader;
-mod verifier;
+pub(crate) mod downloader;
+pub(crate) mod verifier;
+// Cleanup of files no known instance references.
+pub mod gc;
 
 // Resource installers (libraries, natives, client, assets, mods).
 pub(crate) mod ressources;
@@ -15,3 +17,4 @@ pub(crate) mod processors;
 
 // Re-export the Installer trait
 pub use installer::Installer;
+pub use verifier::VerifyReport;
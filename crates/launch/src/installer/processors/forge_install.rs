@@ -8,7 +8,7 @@
 //! 2. Extract any `/maven/...` artifacts bundled in the installer
 //!    (Forge only; NeoForge doesn't ship them).
 //! 3. Check the SHA1 marker — skip if the processors already ran against
-//!    this exact installer.
+//!    this exact installer and every file they produce is still there.
 //! 4. Run the processors through the shared executor.
 //! 5. Write the marker on success.
 //!
@@ -18,13 +18,13 @@
 use std::path::PathBuf;
 
 use lighty_core::download::download_file_untracked;
-use lighty_core::mkdir;
+use lighty_core::{mkdir, DownloadError};
 use lighty_loaders::types::VersionInfo;
 use lighty_loaders::utils::error::QueryError;
 use lighty_loaders::utils::forge_installer::ForgeInstallProfile;
 use lighty_loaders::utils::maven::fetch_maven_sha1;
 
-use super::processor::run_processors;
+use super::processor::{run_processors, ProcessorContext};
 #[cfg(feature = "forge")]
 use super::processor::extract_maven_bundle_to_libraries;
 
@@ -46,6 +46,44 @@ fn processors_marker_path<V: VersionInfo>(version: &V, dot_dir: &str) -> PathBuf
     ))
 }
 
+/// Whether the processors already ran against the installer whose SHA1
+/// is `expected_sha1` and left all their outputs on disk.
+fn processors_done<V: VersionInfo>(
+    version: &V,
+    install_profile: &ForgeInstallProfile,
+    installer_path: &std::path::Path,
+    extract_subdir: &str,
+    marker_path: &std::path::Path,
+    expected_sha1: &str,
+) -> bool {
+    let Ok(existing) = std::fs::read_to_string(marker_path) else {
+        return false;
+    };
+    if existing.trim() != expected_sha1 {
+        return false;
+    }
+
+    // The Java path is never used to list outputs
+    let context = ProcessorContext::new(
+        version,
+        installer_path.to_path_buf(),
+        install_profile,
+        "",
+        extract_subdir,
+        PathBuf::new(),
+    );
+    match context.produced_paths(install_profile).into_iter().find(|path| !path.exists()) {
+        Some(_missing) => {
+            lighty_core::trace_warn!(
+                path = ?_missing,
+                "Processor output missing, running processors again"
+            );
+            false
+        }
+        None => true,
+    }
+}
+
 /// Runs the modern Forge install processors (≥ 1.13).
 ///
 /// The caller must have already downloaded the install_profile libraries
@@ -76,20 +114,25 @@ pub(crate) async fn run_forge_install_processors<V: VersionInfo>(
     // Some Forge versions ship runtime artifacts bundled at `/maven/...`
     // inside the installer (forge-shim.jar in 1.21+, forge-universal.jar
     // + forge.jar in 1.14, etc.). Idempotent.
-    let libraries_dir = version.game_dirs().join("libraries");
-    extract_maven_bundle_to_libraries(&installer_path, &libraries_dir)?;
+    let libraries_dir = version.libraries_dir();
+    extract_maven_bundle_to_libraries(&installer_path, &libraries_dir).await?;
 
     let installer_url = build_installer_url(version);
     let marker_path = processors_marker_path(version, ".forge");
     if let Some(expected_sha1) = fetch_maven_sha1(&installer_url).await {
-        if let Ok(existing) = std::fs::read_to_string(&marker_path) {
-            if existing.trim() == expected_sha1 {
-                lighty_core::trace_info!(
-                    loader = "forge",
-                    "Processors already executed for this installer, skipping"
-                );
-                return Ok(());
-            }
+        if processors_done(
+            version,
+            install_profile,
+            &installer_path,
+            FORGE_EXTRACT_SUBDIR,
+            &marker_path,
+            &expected_sha1,
+        ) {
+            lighty_core::trace_info!(
+                loader = "forge",
+                "Processors already executed for this installer, skipping"
+            );
+            return Ok(());
         }
     }
 
@@ -147,14 +190,19 @@ pub(crate) async fn run_neoforge_install_processors<V: VersionInfo>(
     let installer_url = build_installer_url(version);
     let marker_path = processors_marker_path(version, ".neoforge");
     if let Some(expected_sha1) = fetch_maven_sha1(&installer_url).await {
-        if let Ok(existing) = std::fs::read_to_string(&marker_path) {
-            if existing.trim() == expected_sha1 {
-                lighty_core::trace_info!(
-                    loader = "neoforge",
-                    "Processors already executed for this installer, skipping"
-                );
-                return Ok(());
-            }
+        if processors_done(
+            version,
+            install_profile,
+            &installer_path,
+            NEOFORGE_EXTRACT_SUBDIR,
+            &marker_path,
+            &expected_sha1,
+        ) {
+            lighty_core::trace_info!(
+                loader = "neoforge",
+                "Processors already executed for this installer, skipping"
+            );
+            return Ok(());
         }
     }
 
@@ -204,11 +252,14 @@ async fn ensure_installer_cached<V: VersionInfo>(
 
     download_file_untracked(&installer_url, &installer_path)
         .await
-        .map_err(|e| QueryError::Conversion {
-            message: format!(
-                "Installer JAR missing and could not be restored automatically: {}",
-                e
-            ),
+        .map_err(|e| match e {
+            DownloadError::Cancelled(cancelled) => cancelled.into(),
+            e => QueryError::Conversion {
+                message: format!(
+                    "Installer JAR missing and could not be restored automatically: {}",
+                    e
+                ),
+            },
         })?;
 
     let expected_sha1 = fetch_maven_sha1(&installer_url)
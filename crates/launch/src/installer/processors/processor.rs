@@ -19,8 +19,11 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 use zip::ZipArchive;
 
+use lighty_core::cancel::{checkpoint, run_until_cancelled};
 use lighty_core::download::download_file_untracked;
-use lighty_core::mkdir;
+use lighty_core::persist::write_atomic_sync;
+use lighty_core::store::{lock_path, shared_store};
+use lighty_core::{mkdir, DownloadError};
 use lighty_loaders::types::VersionInfo;
 use lighty_loaders::utils::error::QueryError;
 use lighty_loaders::utils::forge_installer::{ForgeInstallProfile, Processor};
@@ -65,7 +68,7 @@ impl ProcessorContext {
     ) -> Self {
         let side = "client".to_string();
         let game_dir = version.game_dirs();
-        let libraries_dir = game_dir.join("libraries");
+        let libraries_dir = version.libraries_dir();
 
         let mut data = HashMap::new();
         for (key, value) in &metadata.data {
@@ -185,6 +188,34 @@ impl ProcessorContext {
         Ok(path.to_string_lossy().to_string())
     }
 
+    /// Directory receiving the files extracted from the installer JAR.
+    pub fn extracts_dir(&self) -> PathBuf {
+        let mut dir = self.libraries_dir.clone();
+        for segment in self.extract_subdir.split('/').filter(|s| !s.is_empty()) {
+            dir.push(segment);
+        }
+        dir.join("installer-extracts").join(&self.minecraft_version)
+    }
+
+    /// Library paths the processors of `metadata` read or write: every
+    /// Maven-coordinate `data` value plus every declared output. They are
+    /// not in the version's library list, yet the processors are skipped
+    /// once they ran, so the files must stay.
+    pub fn produced_paths(&self, metadata: &ForgeInstallProfile) -> Vec<PathBuf> {
+        let data = self.data.values().map(String::as_str);
+        let outputs = metadata
+            .processors
+            .iter()
+            .flat_map(|processor| processor.outputs.keys())
+            .map(String::as_str);
+
+        data.chain(outputs)
+            .filter_map(|value| self.substitute(value).ok())
+            .filter(|path| Path::new(path).starts_with(&self.libraries_dir))
+            .map(PathBuf::from)
+            .collect()
+    }
+
     /// Builds a Maven download URL for the given coordinates on this
     /// context's configured [`maven_base_url`](Self::maven_base_url).
     pub fn build_maven_url(&self, maven_coords: &str) -> Result<String> {
@@ -255,12 +286,13 @@ impl ProcessorContext {
             mkdir!(parent);
         }
 
-        let mut output = File::create(output_path).map_err(|e| QueryError::Conversion {
-            message: format!("Failed to create output file: {}", e),
+        let mut bytes = Vec::with_capacity(zip_file.size() as usize);
+        zip_file.read_to_end(&mut bytes).map_err(|e| QueryError::Conversion {
+            message: format!("Failed to extract file: {}", e),
         })?;
 
-        std::io::copy(&mut zip_file, &mut output).map_err(|e| QueryError::Conversion {
-            message: format!("Failed to extract file: {}", e),
+        write_atomic_sync(output_path, &bytes).map_err(|e| QueryError::Conversion {
+            message: format!("Failed to write output file: {}", e),
         })?;
 
         Ok(())
@@ -280,7 +312,10 @@ impl ProcessorContext {
 /// Idempotent: skips entries whose target already exists with the right
 /// size. NeoForge installers carry no `/maven/` entries, so this is a
 /// no-op for them.
-pub(crate) fn extract_maven_bundle_to_libraries(
+///
+/// `libraries_dir` may be the shared store's tree: each file is locked and
+/// replaced through a rename, never rewritten in place.
+pub(crate) async fn extract_maven_bundle_to_libraries(
     installer_path: &Path,
     libraries_dir: &Path,
 ) -> Result<()> {
@@ -292,8 +327,10 @@ pub(crate) fn extract_maven_bundle_to_libraries(
         message: format!("Failed to open ZIP archive: {}", e),
     })?;
 
+    // Listed up front: a ZIP entry can't be held across the locks below
+    let mut bundled = Vec::new();
     for i in 0..archive.len() {
-        let mut entry = archive.by_index(i).map_err(|e| QueryError::Conversion {
+        let entry = archive.by_index(i).map_err(|e| QueryError::Conversion {
             message: format!("Failed to read ZIP entry {}: {}", i, e),
         })?;
 
@@ -302,11 +339,15 @@ pub(crate) fn extract_maven_bundle_to_libraries(
         }
         let Some(rel) = entry.enclosed_name() else { continue };
         let Ok(rel_path) = rel.strip_prefix("maven/") else { continue };
+        bundled.push((i, rel_path.to_path_buf(), entry.size()));
+    }
 
-        let dest = libraries_dir.join(rel_path);
+    for (i, rel_path, size) in bundled {
+        let dest = libraries_dir.join(&rel_path);
+        let _dest = lock_path(&dest).await;
 
         if let Ok(meta) = std::fs::metadata(&dest) {
-            if meta.len() == entry.size() {
+            if meta.len() == size {
                 continue;
             }
         }
@@ -319,12 +360,16 @@ pub(crate) fn extract_maven_bundle_to_libraries(
             }
         }
 
-        let mut out = File::create(&dest).map_err(|e| QueryError::Conversion {
-            message: format!("Failed to create {}: {}", dest.display(), e),
+        let mut entry = archive.by_index(i).map_err(|e| QueryError::Conversion {
+            message: format!("Failed to read ZIP entry {}: {}", i, e),
         })?;
-        std::io::copy(&mut entry, &mut out).map_err(|e| QueryError::Conversion {
+        let mut bytes = Vec::with_capacity(size as usize);
+        entry.read_to_end(&mut bytes).map_err(|e| QueryError::Conversion {
             message: format!("Failed to extract {}: {}", rel_path.display(), e),
         })?;
+        write_atomic_sync(&dest, &bytes).map_err(|e| QueryError::Conversion {
+            message: format!("Failed to write {}: {}", dest.display(), e),
+        })?;
 
         lighty_core::trace_debug!(
             path = %dest.display(),
@@ -390,6 +435,7 @@ pub(crate) async fn run_processors<V: VersionInfo>(
             jar = %processor.jar,
             "Executing processor"
         );
+        checkpoint().await?;
         execute_processor(&context, processor).await?;
     }
 
@@ -404,6 +450,33 @@ async fn execute_processor(context: &ProcessorContext, processor: &Processor) ->
         return Ok(());
     }
 
+    // Outputs land in the libraries tree, which every instance shares when
+    // the store is on: generate them one install at a time
+    let mut outputs: Vec<PathBuf> = processor
+        .outputs
+        .keys()
+        .filter_map(|pattern| context.substitute(pattern).ok())
+        .map(PathBuf::from)
+        .collect();
+    outputs.sort();
+    outputs.dedup();
+    let mut held = Vec::with_capacity(outputs.len());
+    for output in &outputs {
+        held.push(lock_path(output).await);
+    }
+    if should_skip_processor(context, processor)? {
+        lighty_core::trace_info!("Processor outputs produced by a concurrent install, skipping");
+        return Ok(());
+    }
+
+    // The JVM rewrites its outputs in place: unlink shared ones first so
+    // the write never reaches a store object through a hardlink
+    let store = shared_store();
+    let shared = |path: &Path| store.as_ref().is_some_and(|store| store.shares(path));
+    for output in outputs.iter().filter(|output| shared(output)) {
+        let _ = std::fs::remove_file(output);
+    }
+
     let jar_path = download_processor_jar(context, &processor.jar).await?;
     let mut classpath_paths = vec![jar_path.clone()];
 
@@ -499,12 +572,23 @@ async fn execute_processor(context: &ProcessorContext, processor: &Processor) ->
         c
     };
 
-    let output = tokio::process::Command::from(std_cmd)
-        .output()
-        .await
-        .map_err(|e| QueryError::Conversion {
+    let mut command = tokio::process::Command::from(std_cmd);
+    command.kill_on_drop(true);
+    let output = match run_until_cancelled(command.output()).await {
+        Ok(output) => output.map_err(|e| QueryError::Conversion {
             message: format!("Failed to execute processor: {}", e),
-        })?;
+        })?,
+        Err(cancelled) => {
+            // The JVM is killed with the dropped future; its outputs may be
+            // partial, and the next run must not trust them. Shared outputs
+            // stay for the other instances: their hash check reruns the
+            // processor anyway
+            for output in outputs.iter().filter(|output| !shared(output)) {
+                let _ = std::fs::remove_file(output);
+            }
+            return Err(cancelled.into());
+        }
+    };
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -517,6 +601,7 @@ async fn execute_processor(context: &ProcessorContext, processor: &Processor) ->
         });
     }
 
+    drop(held);
     lighty_core::trace_debug!("Processor completed successfully");
     Ok(())
 }
@@ -547,8 +632,11 @@ async fn download_processor_jar(
 
     download_file_untracked(&url, &path)
         .await
-        .map_err(|e| QueryError::Conversion {
-            message: format!("Failed to download {}: {}", maven_coords, e),
+        .map_err(|e| match e {
+            DownloadError::Cancelled(cancelled) => cancelled.into(),
+            e => QueryError::Conversion {
+                message: format!("Failed to download {}: {}", maven_coords, e),
+            },
         })?;
 
     Ok(path)
@@ -564,15 +652,9 @@ async fn extract_and_resolve(context: &ProcessorContext, internal_path: &str) ->
             message: format!("Invalid internal path: {}", internal_path),
         })?;
 
-    let mut output_path = context.libraries_dir.clone();
-    for segment in context.extract_subdir.split('/').filter(|s| !s.is_empty()) {
-        output_path.push(segment);
-    }
-    output_path = output_path
-        .join("installer-extracts")
-        .join(&context.minecraft_version)
-        .join(file_name);
+    let output_path = context.extracts_dir().join(file_name);
 
+    let _output = lock_path(&output_path).await;
     if !output_path.exists() {
         context
             .extract_installer_file(internal_path, &output_path)
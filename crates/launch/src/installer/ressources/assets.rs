@@ -4,10 +4,11 @@
 //! Assets installation module
 
 use lighty_loaders::types::{VersionInfo, version_metadata::AssetsFile};
-use lighty_core::time_it;
+use lighty_core::{time_it, ExpectedHash};
 use crate::errors::InstallerResult;
-use crate::installer::verifier::needs_download;
-use crate::installer::downloader::download_small_with_concurrency_limit;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_with_concurrency_limit, DownloadTask};
+use lighty_core::DownloadPriority;
 
 #[cfg(feature = "events")]
 use super::super::downloader::DownloadProgressKind;
@@ -19,12 +20,13 @@ use lighty_event::EventBus;
 pub async fn collect_asset_tasks(
     version: &impl VersionInfo,
     assets: Option<&AssetsFile>,
-) -> Vec<(String, std::path::PathBuf)> {
+    verifier: &Verifier,
+) -> Vec<DownloadTask> {
     let Some(assets) = assets else {
         return Vec::new();
     };
 
-    let parent_path = version.game_dirs().join("assets").join("objects");
+    let parent_path = version.assets_dir().join("objects");
     let mut tasks = Vec::new();
 
     for asset in assets.objects.values() {
@@ -34,17 +36,16 @@ pub async fn collect_asset_tasks(
         let hash_prefix = &asset.hash[0..2];
         let path = parent_path.join(hash_prefix).join(&asset.hash);
 
-        if needs_download(&path, Some(&asset.hash), &asset.hash).await {
-            tasks.push((url.clone(), path));
-        }
+        let hash = ExpectedHash::sha1(asset.hash.clone());
+        tasks.push(DownloadTask::new(url.clone(), path, Some(hash), Some(asset.size)));
     }
 
-    tasks
+    verifier.retain_missing(tasks).await
 }
 
 /// Downloads assets from pre-collected tasks
 pub async fn download_assets(
-    tasks: Vec<(String, std::path::PathBuf)>,
+    tasks: Vec<DownloadTask>,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
     if tasks.is_empty() {
@@ -54,8 +55,9 @@ pub async fn download_assets(
 
     lighty_core::trace_info!("[Installer] Downloading {} new assets...", tasks.len());
     time_it!("Assets download", {
-        download_small_with_concurrency_limit(
+        download_with_concurrency_limit(
             tasks,
+            DownloadPriority::Low,
             #[cfg(feature = "events")]
             event_bus,
             #[cfg(feature = "events")]
@@ -3,11 +3,12 @@
 
 //! Client JAR installation module
 
-use lighty_loaders::types::{VersionInfo, version_metadata::Client};
+use lighty_loaders::types::{VersionInfo, version_metadata::{Digests, Client}};
 use lighty_core::time_it;
+use lighty_core::scheduler::{with_priority, DownloadPriority};
 use crate::errors::InstallerResult;
-use crate::installer::verifier::needs_download;
-use crate::installer::downloader::download_large_file;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_file, DownloadTask};
 
 #[cfg(feature = "events")]
 use lighty_event::EventBus;
@@ -16,24 +17,22 @@ use lighty_event::EventBus;
 pub async fn collect_client_task(
     version: &impl VersionInfo,
     client: Option<&Client>,
-) -> Option<(String, std::path::PathBuf)> {
+    verifier: &Verifier,
+) -> Option<DownloadTask> {
     let client = client?;
     let url = client.url.as_ref()?.clone();
     let client_path = version.game_dirs().join(format!("{}.jar", version.name()));
 
-    if needs_download(&client_path, client.sha1.as_ref(), "Client JAR").await {
-        Some((url, client_path))
-    } else {
-        None
-    }
+    let task = DownloadTask::new(url, client_path, client.strongest_hash(), client.size);
+    verifier.retain_missing(vec![task]).await.pop()
 }
 
 /// Downloads client JAR from pre-collected task
 pub async fn download_client(
-    task: Option<(String, std::path::PathBuf)>,
+    task: Option<DownloadTask>,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
-    let Some((url, client_path)) = task else {
+    let Some(task) = task else {
         lighty_core::trace_info!("[Installer] ✓ Client JAR already cached and verified");
         return Ok(());
     };
@@ -41,11 +40,13 @@ pub async fn download_client(
     lighty_core::trace_info!("[Installer] Downloading client JAR...");
     time_it!(
         "Client download",
-        download_large_file(
-            url,
-            client_path,
-            #[cfg(feature = "events")]
-            event_bus,
+        with_priority(
+            DownloadPriority::High,
+            download_file(
+                &task,
+                #[cfg(feature = "events")]
+                event_bus,
+            ),
         )
         .await?
     );
@@ -3,11 +3,12 @@
 
 //! Library installation module
 
-use lighty_loaders::types::{VersionInfo, version_metadata::Library};
+use lighty_loaders::types::{VersionInfo, version_metadata::{Digests, Library}};
 use lighty_core::time_it;
 use crate::errors::InstallerResult;
-use crate::installer::verifier::needs_download;
-use crate::installer::downloader::download_with_concurrency_limit;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_with_concurrency_limit, DownloadTask};
+use lighty_core::DownloadPriority;
 
 #[cfg(feature = "events")]
 use super::super::downloader::DownloadProgressKind;
@@ -19,8 +20,9 @@ use lighty_event::EventBus;
 pub async fn collect_library_tasks(
     version: &impl VersionInfo,
     libraries: &[Library],
-) -> Vec<(String, std::path::PathBuf)> {
-    let parent_path = version.game_dirs().join("libraries");
+    verifier: &Verifier,
+) -> Vec<DownloadTask> {
+    let parent_path = version.libraries_dir();
     let mut tasks = Vec::new();
 
     for lib in libraries {
@@ -37,17 +39,15 @@ pub async fn collect_library_tasks(
 
         let path = parent_path.join(path_str);
 
-        if needs_download(&path, lib.sha1.as_ref(), &lib.name).await {
-            tasks.push((url.clone(), path));
-        }
+        tasks.push(DownloadTask::new(url.clone(), path, lib.strongest_hash(), lib.size));
     }
 
-    tasks
+    verifier.retain_missing(tasks).await
 }
 
 /// Downloads libraries from pre-collected tasks
 pub async fn download_libraries(
-    tasks: Vec<(String, std::path::PathBuf)>,
+    tasks: Vec<DownloadTask>,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
     if tasks.is_empty() {
@@ -59,6 +59,7 @@ pub async fn download_libraries(
     time_it!("Libraries download", {
         download_with_concurrency_limit(
             tasks,
+            DownloadPriority::High,
             #[cfg(feature = "events")]
             event_bus,
             #[cfg(feature = "events")]
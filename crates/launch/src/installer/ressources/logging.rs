@@ -0,0 +1,60 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Log4j configuration installation module
+
+use std::path::PathBuf;
+
+use lighty_loaders::types::{VersionInfo, version_metadata::LoggingConfig};
+use lighty_core::ExpectedHash;
+use lighty_core::scheduler::{with_priority, DownloadPriority};
+use crate::errors::InstallerResult;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_file, DownloadTask};
+
+#[cfg(feature = "events")]
+use lighty_event::EventBus;
+
+/// Where the configuration file lives, next to the assets like the
+/// official launcher (`assets/log_configs/client-1.12.xml`)
+pub(crate) fn config_path(version: &impl VersionInfo, logging: &LoggingConfig) -> PathBuf {
+    version.assets_dir().join("log_configs").join(&logging.id)
+}
+
+/// Collects the logging configuration task if it needs to be downloaded
+pub async fn collect_logging_task(
+    version: &impl VersionInfo,
+    logging: Option<&LoggingConfig>,
+    verifier: &Verifier,
+) -> Option<DownloadTask> {
+    let logging = logging?;
+    let task = DownloadTask::new(
+        logging.url.clone(),
+        config_path(version, logging),
+        Some(ExpectedHash::sha1(logging.sha1.clone())),
+        Some(logging.size),
+    );
+    verifier.retain_missing(vec![task]).await.pop()
+}
+
+/// Downloads the logging configuration from a pre-collected task
+pub async fn download_logging_config(
+    task: Option<DownloadTask>,
+    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
+) -> InstallerResult<()> {
+    let Some(task) = task else {
+        return Ok(());
+    };
+
+    with_priority(
+        DownloadPriority::High,
+        download_file(
+            &task,
+            #[cfg(feature = "events")]
+            event_bus,
+        ),
+    )
+    .await?;
+    lighty_core::trace_info!("[Installer] ✓ Logging configuration installed");
+    Ok(())
+}
@@ -1,7 +1,8 @@
 // Copyright (c) 2025 Hamadi
 // Licensed under the MIT License
 
-//! Resource-installer steps: libraries, natives, client JAR, assets, mods.
+//! Resource-installer steps: libraries, natives, client JAR, assets, mods,
+//! logging configuration.
 
 // pub(crate) so the launch pipeline can feed Forge-family
 // install_profile libraries through the same parallel-download/retry/SHA1
@@ -11,6 +12,7 @@ pub(crate) mod mods;
 pub(crate) mod natives;
 pub(crate) mod client;
 pub(crate) mod assets;
+pub(crate) mod logging;
 // User-attached mod resolver (Modrinth + CurseForge). Compiled only
 // when at least one source feature is enabled — gated at the module
 // boundary so disabling both lops it out of the binary cleanly.
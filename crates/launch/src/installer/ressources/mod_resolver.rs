@@ -9,34 +9,126 @@
 //! - it produces the pivot `Vec<Mods>` that the launch crate then
 //!   merges into [`Version.mods`] before [`super::mods::collect_mod_tasks`]
 //!   takes over.
+//!
+//! Each successful resolution is snapshotted in the instance directory so
+//! [offline mode](lighty_core::offline) can replay it without the APIs.
 
 #![cfg(any(feature = "modrinth", feature = "curseforge"))]
 
+use std::borrow::Cow;
+use std::path::Path;
+
+use lighty_core::offline::is_offline;
+use lighty_core::persist::write_atomic;
+use lighty_core::OfflineUnavailable;
 use lighty_loaders::mods::resolver::{resolve, ResolveCallbacks};
-use lighty_loaders::mods::request::ModRequest;
+use lighty_loaders::mods::request::{ModKey, ModRequest};
 use lighty_loaders::types::version_metadata::Mods;
 use lighty_loaders::types::Loader;
+use serde::{Deserialize, Serialize};
 
 use crate::errors::InstallerResult;
 
 #[cfg(feature = "events")]
 use lighty_event::{Event, EventBus, LaunchEvent};
 
+/// Snapshot of the last resolution, stored under the instance's game directory.
+const RESOLUTION_FILE: &str = ".lighty-mods.json";
+
+/// Resolved mod list plus the inputs it was resolved from.
+#[derive(Serialize, Deserialize)]
+struct ResolutionSnapshot {
+    fingerprint: String,
+    mods: Vec<Mods>,
+}
+
+/// Inputs of a resolution, serialized into [`ResolutionSnapshot::fingerprint`].
+#[derive(Serialize)]
+struct ResolutionKey<'a> {
+    minecraft: &'a str,
+    loader: &'static str,
+    requests: Vec<RequestKey<'a>>,
+}
+
+/// One [`ModRequest`], pin included.
+#[derive(Serialize)]
+struct RequestKey<'a> {
+    source: &'static str,
+    id: String,
+    version: Option<Cow<'a, str>>,
+}
+
+impl<'a> ResolutionKey<'a> {
+    fn new(mc_version: &'a str, loader: &Loader, requests: &'a [ModRequest]) -> Self {
+        let loader = match loader {
+            Loader::Fabric => "fabric",
+            Loader::NeoForge => "neoforge",
+            Loader::Optifine => "optifine",
+            Loader::Quilt => "quilt",
+            Loader::Vanilla => "vanilla",
+            Loader::Forge => "forge",
+            Loader::LightyUpdater => "lighty_updater",
+        };
+        let requests = requests
+            .iter()
+            .map(|request| {
+                let key = ModKey::from(request);
+                let version = match request {
+                    ModRequest::Modrinth { version, .. } => version.as_deref().map(Cow::Borrowed),
+                    ModRequest::CurseForge { file_id, .. } => file_id.map(|id| Cow::Owned(id.to_string())),
+                };
+                RequestKey { source: key.source.as_str(), id: key.id, version }
+            })
+            .collect();
+        Self { minecraft: mc_version, loader, requests }
+    }
+
+    fn fingerprint(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
 /// Resolves every user-attached [`ModRequest`] for an instance,
 /// transitively pulling required dependencies, and returns the flat
 /// list of pivot [`Mods`] entries ready for the standard mods installer.
 ///
 /// Returns an empty vec when `requests` is empty (no network calls).
+/// In offline mode the snapshot left in `game_dirs` by the last online
+/// resolution is returned instead, provided the requests, Minecraft
+/// version and loader are unchanged.
 pub(crate) async fn resolve_user_mods(
     requests: &[ModRequest],
     mc_version: &str,
     loader: &Loader,
+    game_dirs: &Path,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<Vec<Mods>> {
     if requests.is_empty() {
         return Ok(Vec::new());
     }
 
+    let snapshot_path = game_dirs.join(RESOLUTION_FILE);
+    let fingerprint = ResolutionKey::new(mc_version, loader, requests).fingerprint();
+
+    if is_offline() {
+        return match load_snapshot(&snapshot_path, &fingerprint).await {
+            Some(mods) => {
+                lighty_core::trace_info!("[Installer] Offline: reusing {} previously resolved mod(s)", mods.len());
+                Ok(mods)
+            }
+            None => {
+                let key = ModKey::from(&requests[0]);
+                Err(OfflineUnavailable::new(format!(
+                    "resolved mod list for {}:{} ({} request(s))",
+                    key.source.as_str(),
+                    key.id,
+                    requests.len()
+                ))
+                .into())
+            }
+        };
+    }
+
     lighty_core::trace_info!(
         "[Installer] Resolving {} user mod request(s)...",
         requests.len()
@@ -96,6 +188,38 @@ pub(crate) async fn resolve_user_mods(
         resolved.len()
     );
 
+    save_snapshot(&snapshot_path, fingerprint, &resolved).await;
+
     Ok(resolved)
 }
 
+/// Returns the snapshot's mods if it exists and matches `fingerprint`.
+async fn load_snapshot(path: &Path, fingerprint: &str) -> Option<Vec<Mods>> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    let snapshot: ResolutionSnapshot = serde_json::from_slice(&bytes).ok()?;
+    (snapshot.fingerprint == fingerprint).then_some(snapshot.mods)
+}
+
+/// Mods of the last resolution stored in `game_dirs`, whatever the
+/// inputs it was made from. Empty when there is none.
+pub(crate) async fn last_resolved_mods(game_dirs: &Path) -> Vec<Mods> {
+    let Ok(bytes) = tokio::fs::read(game_dirs.join(RESOLUTION_FILE)).await else {
+        return Vec::new();
+    };
+    serde_json::from_slice::<ResolutionSnapshot>(&bytes)
+        .map(|snapshot| snapshot.mods)
+        .unwrap_or_default()
+}
+
+/// Best-effort write: a missing snapshot only matters in offline mode.
+async fn save_snapshot(path: &Path, fingerprint: String, mods: &[Mods]) {
+    let snapshot = ResolutionSnapshot { fingerprint, mods: mods.to_vec() };
+    let result = match serde_json::to_vec(&snapshot) {
+        Ok(bytes) => write_atomic(path, &bytes).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(_e) = result {
+        lighty_core::trace_warn!("[Installer] Failed to save mod resolution snapshot: {}", _e);
+    }
+}
+
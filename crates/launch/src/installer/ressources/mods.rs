@@ -3,11 +3,12 @@
 
 //! Mod installation module
 
-use lighty_loaders::types::{VersionInfo, version_metadata::Mods};
+use lighty_loaders::types::{VersionInfo, version_metadata::{Digests, Mods}};
 use lighty_core::time_it;
 use crate::errors::InstallerResult;
-use crate::installer::verifier::needs_download;
-use crate::installer::downloader::download_with_concurrency_limit;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_with_concurrency_limit, DownloadTask};
+use lighty_core::DownloadPriority;
 
 #[cfg(feature = "events")]
 use super::super::downloader::DownloadProgressKind;
@@ -19,7 +20,8 @@ use lighty_event::EventBus;
 pub async fn collect_mod_tasks(
     version: &impl VersionInfo,
     mods: &[Mods],
-) -> Vec<(String, std::path::PathBuf)> {
+    verifier: &Verifier,
+) -> Vec<DownloadTask> {
     // Don't create mods directory if there are no mods
     if mods.is_empty() {
         return Vec::new();
@@ -42,17 +44,15 @@ pub async fn collect_mod_tasks(
 
         let path = parent_path.join(path_str);
 
-        if needs_download(&path, _mod.sha1.as_ref(), &_mod.name).await {
-            tasks.push((url.clone(), path));
-        }
+        tasks.push(DownloadTask::new(url.clone(), path, _mod.strongest_hash(), _mod.size));
     }
 
-    tasks
+    verifier.retain_missing(tasks).await
 }
 
 /// Downloads mods from pre-collected tasks
 pub async fn download_mods(
-    tasks: Vec<(String, std::path::PathBuf)>,
+    tasks: Vec<DownloadTask>,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
     if tasks.is_empty() {
@@ -64,6 +64,7 @@ pub async fn download_mods(
     time_it!("Mods download", {
         download_with_concurrency_limit(
             tasks,
+            DownloadPriority::Normal,
             #[cfg(feature = "events")]
             event_bus,
             #[cfg(feature = "events")]
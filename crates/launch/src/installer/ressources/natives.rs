@@ -7,14 +7,15 @@ use std::path::PathBuf;
 use async_zip::tokio::read::seek::ZipFileReader;
 use tokio::fs;
 use tokio::io::BufReader;
-use lighty_loaders::types::{VersionInfo, version_metadata::Native};
+use lighty_loaders::types::{VersionInfo, version_metadata::{Digests, Native}};
 use lighty_core::{mkdir, time_it};
 use futures::future::try_join_all;
 use futures_util::io;
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 use crate::errors::{InstallerError, InstallerResult};
-use crate::installer::verifier::needs_download;
-use crate::installer::downloader::download_with_concurrency_limit;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_with_concurrency_limit, DownloadTask};
+use lighty_core::DownloadPriority;
 
 #[cfg(feature = "events")]
 use super::super::downloader::DownloadProgressKind;
@@ -26,12 +27,13 @@ use lighty_event::EventBus;
 pub async fn collect_native_tasks(
     version: &impl VersionInfo,
     natives: &[Native],
-) -> (Vec<(String, PathBuf)>, Vec<PathBuf>) {
+    verifier: &Verifier,
+) -> (Vec<DownloadTask>, Vec<PathBuf>) {
     if natives.is_empty() {
         return (Vec::new(), Vec::new());
     }
 
-    let libraries_path = version.game_dirs().join("libraries");
+    let libraries_path = version.libraries_dir();
     let mut download_tasks = Vec::new();
     let mut extract_paths = Vec::new();
 
@@ -41,20 +43,17 @@ pub async fn collect_native_tasks(
 
         let jar_path = libraries_path.join(path_str);
 
-        if needs_download(&jar_path, native.sha1.as_ref(), &native.name).await {
-            download_tasks.push((url.clone(), jar_path.clone()));
-        }
-
+        download_tasks.push(DownloadTask::new(url.clone(), jar_path.clone(), native.strongest_hash(), native.size));
         extract_paths.push(jar_path);
     }
 
-    (download_tasks, extract_paths)
+    (verifier.retain_missing(download_tasks).await, extract_paths)
 }
 
 /// Downloads and extracts natives from pre-collected tasks
 pub async fn download_and_extract_natives(
     version: &impl VersionInfo,
-    download_tasks: Vec<(String, PathBuf)>,
+    download_tasks: Vec<DownloadTask>,
     extract_paths: Vec<PathBuf>,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()> {
@@ -72,6 +71,7 @@ pub async fn download_and_extract_natives(
         time_it!("Natives download", {
             download_with_concurrency_limit(
                 download_tasks,
+                DownloadPriority::High,
                 #[cfg(feature = "events")]
                 event_bus,
                 #[cfg(feature = "events")]
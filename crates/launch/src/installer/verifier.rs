@@ -2,45 +2,396 @@
 // Licensed under the MIT License
 
 //! File verification and cache checking utilities
+//!
+//! [`Verifier`] runs the pre-install hash pass. Files are hashed in parallel
+//! on tokio's blocking pool with memory-mapped reads, and an optional
+//! per-instance `(size, mtime, hash)` index lets files that have not changed
+//! since their last successful check skip hashing altogether. Missing files
+//! already present in the [shared store](lighty_core::store) are linked into
+//! place instead of being downloaded again.
+//!
+//! A [repair](Verifier::for_repair) pass hashes every file regardless of the
+//! index and records what it found in a [`VerifyReport`].
 
-use std::path::PathBuf;
-use tokio::fs;
-use lighty_core::verify_file_sha1;
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::UNIX_EPOCH;
 
-/// Returns whether the file at `path` needs to be (re-)downloaded.
+use futures::future::join_all;
+use lighty_core::cancel::checkpoint;
+use lighty_core::store::{shared_store, SharedStore};
+use lighty_core::{calculate_file_hash_mmap, ExpectedHash};
+use lighty_loaders::types::VersionInfo;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use super::config::get_config;
+use super::downloader::DownloadTask;
+
+/// Index file name, stored at the root of the instance's game directory.
+const INDEX_FILE: &str = ".lighty-verify-index.json";
+
+/// Files handed to a blocking worker at once. Keeps per-task overhead low
+/// for asset sets with thousands of small objects.
+const CHUNK_SIZE: usize = 64;
+
+/// Caps the number of blocking workers hashing at the same time, shared by
+/// every verification pass in the process.
+static VERIFY_PERMITS: Lazy<Arc<Semaphore>> = Lazy::new(|| {
+    let threads = match get_config().verify_threads {
+        0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+        n => n,
+    };
+    Arc::new(Semaphore::new(threads))
+});
+
+/// Size and modification time of a file when it was last verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Stamp {
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+}
+
+impl Stamp {
+    /// `None` when the platform can't report a modification time.
+    fn of(meta: &Metadata) -> Option<Self> {
+        let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            size: meta.len(),
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    #[serde(flatten)]
+    stamp: Stamp,
+    hash: ExpectedHash,
+}
+
+/// Outcome of a repair pass, see [`LaunchBuilder::verify`](crate::launch::LaunchBuilder::verify).
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    /// Files checked (libraries, natives, client, assets, mods).
+    pub checked: usize,
+    /// Files that were not on disk.
+    pub missing: Vec<PathBuf>,
+    /// Files that were empty or failed their hash check; deleted.
+    pub corrupted: Vec<PathBuf>,
+    /// Missing or corrupted files that have been put back, either
+    /// downloaded again or linked from the shared store.
+    pub replaced: Vec<PathBuf>,
+}
+
+impl VerifyReport {
+    /// Whether every file was already present and valid.
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.corrupted.is_empty()
+    }
+}
+
+/// State of a file on disk before the install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileState {
+    Valid,
+    Missing,
+    Corrupted,
+}
+
+/// Persisted `(size, mtime, hash)` records, keyed by path relative to `root`.
+#[derive(Debug)]
+struct VerifyIndex {
+    root: PathBuf,
+    entries: HashMap<String, IndexEntry>,
+    dirty: bool,
+}
+
+impl VerifyIndex {
+    fn key(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Whether `path` was verified against `hash` and hasn't been touched since.
+    fn trusts(&self, path: &Path, stamp: &Stamp, hash: &ExpectedHash) -> bool {
+        self.entries.get(&self.key(path)).is_some_and(|entry| {
+            entry.stamp == *stamp
+                && entry.hash.algorithm == hash.algorithm
+                && hash.matches(&entry.hash.hex)
+        })
+    }
+
+    fn insert(&mut self, path: &Path, stamp: Stamp, hash: &ExpectedHash) {
+        let key = self.key(path);
+        self.entries.insert(key, IndexEntry { stamp, hash: hash.clone() });
+        self.dirty = true;
+    }
+
+    fn remove(&mut self, path: &Path) {
+        let key = self.key(path);
+        if self.entries.remove(&key).is_some() {
+            self.dirty = true;
+        }
+    }
+}
+
+/// Parallel verification pass over the files of one instance.
 ///
-/// `true` if the file is missing, zero-byte (corrupt — a stale empty
-/// stub from a previously-failed download), or the SHA1 doesn't match
-/// `sha1`. `false` if the file exists, has non-zero size, and either
-/// has no expected hash or matches the expected one.
-pub async fn needs_download(path: &PathBuf, sha1: Option<&String>, name: &str) -> bool {
-    if !path.exists() {
-        return true;
+/// Create one per install with [`Verifier::for_instance`], filter the
+/// candidate tasks of every resource type through [`Verifier::retain_missing`]
+/// and call [`Verifier::save`] once the pass is done so the index (when
+/// enabled through [`DownloaderConfig::verify_index`](super::config::DownloaderConfig::verify_index))
+/// is persisted for the next launch.
+///
+/// Freshly downloaded files are not recorded; they are hashed once on the
+/// next launch and trusted from then on.
+pub(crate) struct Verifier {
+    index: Option<Arc<Mutex<VerifyIndex>>>,
+    index_path: PathBuf,
+    /// Set in repair mode: the index is refreshed but never trusted.
+    audit: Option<Arc<Mutex<VerifyReport>>>,
+}
+
+impl Verifier {
+    /// Builds a verifier for `version`, loading its index if enabled.
+    pub(crate) async fn for_instance(version: &impl VersionInfo) -> Self {
+        let root = version.game_dirs().to_path_buf();
+        let index_path = root.join(INDEX_FILE);
+
+        if !get_config().verify_index {
+            return Self { index: None, index_path, audit: None };
+        }
+
+        // A missing or unreadable index just means everything gets hashed.
+        let entries = match tokio::fs::read(&index_path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|_e| {
+                lighty_core::trace_warn!("[Installer] Ignoring corrupt verify index: {}", _e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Self {
+            index: Some(Arc::new(Mutex::new(VerifyIndex { root, entries, dirty: false }))),
+            index_path,
+            audit: None,
+        }
     }
 
+    /// Builds a verifier that hashes every file, ignoring the index, and
+    /// records its findings for [`Self::report`].
+    pub(crate) async fn for_repair(version: &impl VersionInfo) -> Self {
+        Self {
+            audit: Some(Arc::default()),
+            ..Self::for_instance(version).await
+        }
+    }
+
+    /// Findings of a repair pass so far; empty for a regular verifier.
+    /// Everything flagged counts as replaced, so only call this once the
+    /// install succeeded.
+    pub(crate) fn report(&self) -> VerifyReport {
+        let Some(audit) = &self.audit else {
+            return VerifyReport::default();
+        };
+        let mut report = audit.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        report.replaced = report.missing.iter().chain(&report.corrupted).cloned().collect();
+        report
+    }
+
+    /// Returns the tasks whose destination is missing, zero-byte or fails
+    /// its expected hash. Invalid files are deleted along the way.
+    pub(crate) async fn retain_missing(&self, tasks: Vec<DownloadTask>) -> Vec<DownloadTask> {
+        if tasks.is_empty() {
+            return tasks;
+        }
+
+        let jobs: Vec<_> = tasks
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| {
+                let files: Vec<(PathBuf, Option<ExpectedHash>)> = chunk
+                    .iter()
+                    .map(|task| (task.dest.clone(), task.hash.clone()))
+                    .collect();
+                let index = self.index.clone();
+                let audit = self.audit.clone();
+                let store = shared_store();
+
+                async move {
+                    let _permit = VERIFY_PERMITS.clone().acquire_owned().await.ok();
+                    let len = files.len();
+                    // Unchecked files count as needed; the installer stops
+                    // at its next checkpoint anyway
+                    if checkpoint().await.is_err() {
+                        return vec![true; len];
+                    }
+                    tokio::task::spawn_blocking(move || {
+                        // Repair mode re-hashes everything but still refreshes the index
+                        let trust_index = audit.is_none();
+                        files
+                            .iter()
+                            .map(|(path, hash)| {
+                                let state =
+                                    check_file(path, hash.as_ref(), index.as_deref(), trust_index, store.as_ref());
+                                if let Some(audit) = &audit {
+                                    let mut audit = audit.lock().unwrap_or_else(|e| e.into_inner());
+                                    audit.checked += 1;
+                                    match state {
+                                        FileState::Valid => {}
+                                        FileState::Missing => audit.missing.push(path.clone()),
+                                        FileState::Corrupted => audit.corrupted.push(path.clone()),
+                                    }
+                                }
+                                state != FileState::Valid
+                                    && !linked_from_store(store.as_ref(), path, hash.as_ref())
+                            })
+                            .collect::<Vec<bool>>()
+                    })
+                    .await
+                    // A panicked worker leaves its files unverified: download them again.
+                    .unwrap_or_else(|_| vec![true; len])
+                }
+            })
+            .collect();
+
+        let verdicts = join_all(jobs).await.into_iter().flatten();
+
+        tasks
+            .into_iter()
+            .zip(verdicts)
+            .filter_map(|(task, needed)| needed.then_some(task))
+            .collect()
+    }
+
+    /// Writes the index back to disk if this pass changed it.
+    pub(crate) async fn save(&self) {
+        let Some(index) = &self.index else { return };
+
+        let bytes = {
+            let mut index = lock(index);
+            if !index.dirty {
+                return;
+            }
+            index.dirty = false;
+            match serde_json::to_vec(&index.entries) {
+                Ok(bytes) => bytes,
+                Err(_e) => {
+                    lighty_core::trace_warn!("[Installer] Failed to serialize verify index: {}", _e);
+                    return;
+                }
+            }
+        };
+
+        if let Err(_e) = lighty_core::persist::write_atomic(&self.index_path, &bytes).await {
+            lighty_core::trace_warn!("[Installer] Failed to save verify index: {}", _e);
+        }
+    }
+}
+
+/// Checks the file at `path`, deleting it when invalid. Blocking.
+///
+/// [`FileState::Missing`] if the file doesn't exist, [`FileState::Corrupted`]
+/// if it is zero-byte (a stale empty stub from a previously-failed
+/// download) or its digest doesn't match `hash`. [`FileState::Valid`] if it
+/// exists, has non-zero size, and either has no expected hash, matches the
+/// expected one, or is vouched for by `index` (when `trust_index` is set).
+///
+/// Callers pass the strongest hash the source published (see
+/// `Digests::strongest_hash`), so SHA-512/SHA-256 are checked when known.
+/// On mismatch the matching `store` object is dropped as well, since a
+/// hardlinked file shares its bytes.
+fn check_file(
+    path: &Path,
+    hash: Option<&ExpectedHash>,
+    index: Option<&Mutex<VerifyIndex>>,
+    trust_index: bool,
+    store: Option<&SharedStore>,
+) -> FileState {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return FileState::Missing;
+    };
+
     // Empty files are a stale artifact of a previous failed download
     // (server returned 200 with empty body, or the writer crashed mid-stream).
-    if let Ok(meta) = fs::metadata(path).await {
-        if meta.len() == 0 {
+    if meta.len() == 0 {
+        lighty_core::trace_warn!(
+            "[Installer] Zero-byte cached file {}, re-downloading...",
+            path.display()
+        );
+        let _ = std::fs::remove_file(path);
+        return FileState::Corrupted;
+    }
+
+    let Some(hash) = hash else {
+        return FileState::Valid;
+    };
+
+    let stamp = Stamp::of(&meta);
+
+    if let (Some(index), Some(stamp), true) = (index, stamp.as_ref(), trust_index) {
+        if lock(index).trusts(path, stamp, hash) {
+            return FileState::Valid;
+        }
+    }
+
+    match calculate_file_hash_mmap(path, hash.algorithm) {
+        Ok(actual) if hash.matches(&actual) => {
+            if let (Some(index), Some(stamp)) = (index, stamp) {
+                lock(index).insert(path, stamp, hash);
+            }
+            FileState::Valid
+        }
+        _ => {
             lighty_core::trace_warn!(
-                "[Installer] Zero-byte cached file for {}, re-downloading...",
-                name
+                "[Installer] {} mismatch for {}, re-downloading...",
+                hash.algorithm,
+                path.display()
             );
-            let _ = fs::remove_file(path).await;
-            return true;
+            if let Some(index) = index {
+                lock(index).remove(path);
+            }
+            let _ = std::fs::remove_file(path);
+            // Only a shared link can have corrupted the object itself
+            if let Some(store) = store.filter(|store| store.shares(path)) {
+                let _ = std::fs::remove_file(store.object_path(hash));
+            }
+            FileState::Corrupted
         }
     }
+}
 
-    if let Some(hash) = sha1 {
-        match verify_file_sha1(path, hash).await {
-            Ok(true) => false,
-            _ => {
-                lighty_core::trace_warn!("[Installer] SHA1 mismatch for {}, re-downloading...", name);
-                let _ = fs::remove_file(path).await;
-                true
-            }
+/// Links the stored copy of a missing file into place. Blocking.
+///
+/// Returns whether the file is now present, i.e. no download is needed.
+fn linked_from_store(store: Option<&SharedStore>, path: &Path, hash: Option<&ExpectedHash>) -> bool {
+    let (Some(store), Some(hash)) = (store, hash) else {
+        return false;
+    };
+    if !store.contains(hash) {
+        return false;
+    }
+
+    match store.link(hash, path) {
+        Ok(_method) => {
+            lighty_core::trace_debug!("[Installer] Linked {} from store ({:?})", path.display(), _method);
+            true
+        }
+        Err(_e) => {
+            lighty_core::trace_warn!("[Installer] Failed to link {} from store: {}", path.display(), _e);
+            false
         }
-    } else {
-        false
     }
 }
+
+/// Locks the index. It is only a cache, so a poisoned lock still holds usable data.
+fn lock(index: &Mutex<VerifyIndex>) -> MutexGuard<'_, VerifyIndex> {
+    index.lock().unwrap_or_else(|e| e.into_inner())
+}
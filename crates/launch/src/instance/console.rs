@@ -1,89 +1,145 @@
-#[cfg(feature = "events")]
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::Child;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
 
 #[cfg(feature = "events")]
 use lighty_event::EventBus;
 
+use super::crash;
+use super::handle::{ConsoleLine, ConsoleSource, HandleLink};
+use super::log4j::{Log4jParser, LogRecord};
+use super::manager::kill_process_group;
+use super::ready::{Readiness, ReadyDetector};
+use super::INSTANCE_MANAGER;
+
+/// Last console lines kept for crash analysis.
+const TAIL_LINES: usize = 200;
+
+/// Time the readers get to drain the pipes after a crash.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where [`forward_lines`] sends what it reads.
+#[derive(Clone)]
+struct Outputs {
+    console: broadcast::Sender<ConsoleLine>,
+    records: broadcast::Sender<LogRecord>,
+    /// Last [`TAIL_LINES`] lines of both pipes
+    tail: Arc<Mutex<VecDeque<String>>>,
+    ready: Arc<watch::Sender<Readiness>>,
+}
+
 /// Handle console streams (stdout/stderr) from a running game instance
 ///
 /// This function spawns asynchronous tasks to:
-/// - Read and emit stdout lines (Minecraft includes its own timestamps in the log text)
-/// - Read and emit stderr lines
-/// - Wait for the process to exit and emit exit event
-/// - Unregister the instance when done
+/// - Read stdout lines and forward them to the [`GameHandle`](super::GameHandle)
+///   and the event bus (Minecraft includes its own timestamps in the log text)
+/// - Rebuild log4j XML events from those lines into [`LogRecord`]s, forwarded
+///   the same way
+/// - Read and forward stderr lines the same way
+/// - Match stdout against the "game ready" markers, emitting `InstanceReady`
+///   on the first one, or once the ready timeout expires without any
+/// - Wait for the process to exit (or kill it on request) and emit exit event
+/// - On a crash, analyze it from the crash report, `hs_err_pid*.log` and the
+///   last console lines, and emit `InstanceCrashed`
+/// - Unregister the instance, then publish the exit status to the handle
 ///
 /// Note: Frontend should not display the event timestamp for stdout as Minecraft
 /// already includes timestamps in its log format
 pub(crate) async fn handle_console_streams(
     pid: u32,
-    instance_name: String,
+    _instance_name: String,
     mut child: Child,
+    link: HandleLink,
+    ready: ReadyDetector,
     #[cfg(feature = "events")] event_bus: Option<EventBus>,
 ) {
-    // Without the events feature there is no consumer for stdout/stderr,
-    // so don't bother spawning the reader tasks.
-    #[cfg(feature = "events")]
-    {
-        let stdout = child.stdout.take();
-        let stderr = child.stderr.take();
-
-        // Handler stdout
-        if let Some(stdout) = stdout {
-            let instance_name = instance_name.clone();
-            let event_bus_clone = event_bus.clone();
-
-            tokio::spawn(async move {
-                let reader = BufReader::new(stdout);
-                let mut lines = reader.lines();
-
-                while let Ok(Some(line)) = lines.next_line().await {
-                    use lighty_event::{ConsoleOutputEvent, ConsoleStream, Event};
-                    use std::time::SystemTime;
-
-                    if let Some(ref bus) = event_bus_clone {
-                        bus.emit(Event::ConsoleOutput(ConsoleOutputEvent {
-                            pid,
-                            instance_name: instance_name.clone(),
-                            stream: ConsoleStream::Stdout,
-                            line,
-                            timestamp: SystemTime::now(),
-                        }));
-                    }
+    let outputs = Outputs {
+        console: link.console,
+        records: link.records,
+        tail: Arc::new(Mutex::new(VecDeque::with_capacity(TAIL_LINES))),
+        ready: Arc::new(link.ready),
+    };
+    let tail = Arc::clone(&outputs.tail);
+    let readiness = Arc::clone(&outputs.ready);
+
+    tokio::spawn(ready_timeout(
+        ready.timeout,
+        Arc::clone(&readiness),
+        #[cfg(feature = "events")]
+        pid,
+        #[cfg(feature = "events")]
+        _instance_name.clone(),
+        #[cfg(feature = "events")]
+        event_bus.clone(),
+    ));
+
+    let mut readers: Vec<JoinHandle<()>> = Vec::new();
+
+    // The pipes are always drained, even without a consumer, so a chatty
+    // game never blocks on a full pipe.
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(tokio::spawn(forward_lines(
+            stdout,
+            ConsoleSource::Stdout,
+            outputs.clone(),
+            Some(ready),
+            #[cfg(feature = "events")]
+            pid,
+            #[cfg(feature = "events")]
+            _instance_name.clone(),
+            #[cfg(feature = "events")]
+            event_bus.clone(),
+        )));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(tokio::spawn(forward_lines(
+            stderr,
+            ConsoleSource::Stderr,
+            outputs.clone(),
+            None,
+            #[cfg(feature = "events")]
+            pid,
+            #[cfg(feature = "events")]
+            _instance_name.clone(),
+            #[cfg(feature = "events")]
+            event_bus.clone(),
+        )));
+    }
+    // Readers hold the remaining senders: the console ends with the pipes
+    drop(outputs);
+
+    // Wait for process to exit, killing it if the handle asks to
+    let result = loop {
+        tokio::select! {
+            result = child.wait() => break result,
+            _ = link.kill.notified() => {
+                INSTANCE_MANAGER.request_stop(pid);
+                // The whole group, then the child itself in case that failed
+                let _ = kill_process_group(pid);
+                if let Err(_e) = child.start_kill() {
+                    lighty_core::trace_warn!(pid = pid, error = %_e, "Failed to kill process");
+                } else {
+                    lighty_core::trace_info!(pid = pid, "Instance killed");
                 }
-            });
+            }
         }
+    };
 
-        // Handler stderr
-        if let Some(stderr) = stderr {
-            let instance_name = instance_name.clone();
-            let event_bus_clone = event_bus.clone();
-
-            tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                let mut lines = reader.lines();
-
-                while let Ok(Some(line)) = lines.next_line().await {
-                    use lighty_event::{ConsoleOutputEvent, ConsoleStream, Event};
-                    use std::time::SystemTime;
-
-                    if let Some(ref bus) = event_bus_clone {
-                        bus.emit(Event::ConsoleOutput(ConsoleOutputEvent {
-                            pid,
-                            instance_name: instance_name.clone(),
-                            stream: ConsoleStream::Stderr,
-                            line,
-                            timestamp: SystemTime::now(),
-                        }));
-                    }
-                }
-            });
+    readiness.send_if_modified(|state| {
+        if state.is_settled() {
+            return false;
         }
-    }
+        *state = Readiness::Exited;
+        true
+    });
 
-    // Wait for process to exit
-    match child.wait().await {
-        Ok(status) => {
+    match &result {
+        Ok(_status) => {
             #[cfg(feature = "events")]
             {
                 use lighty_event::{Event, InstanceExitedEvent};
@@ -92,8 +148,8 @@ pub(crate) async fn handle_console_streams(
                 if let Some(ref bus) = event_bus {
                     bus.emit(Event::InstanceExited(InstanceExitedEvent {
                         pid,
-                        instance_name: instance_name.clone(),
-                        exit_code: status.code(),
+                        instance_name: _instance_name.clone(),
+                        exit_code: _status.code(),
                         timestamp: SystemTime::now(),
                     }));
                 }
@@ -101,22 +157,250 @@ pub(crate) async fn handle_console_streams(
 
             lighty_core::trace_info!(
                 pid = pid,
-                instance = %instance_name,
-                exit_code = ?status.code(),
+                instance = %_instance_name,
+                exit_code = ?_status.code(),
                 "Instance exited"
             );
         }
-        Err(e) => {
+        Err(_e) => {
             lighty_core::trace_error!(
                 pid = pid,
-                instance = %instance_name,
-                error = %e,
+                instance = %_instance_name,
+                error = %_e,
                 "Error waiting for instance"
             );
         }
     }
 
-    // Cleanup
-    use super::INSTANCE_MANAGER;
-    let _ = INSTANCE_MANAGER.unregister_instance(pid).await;
+    // History, then cleanup
+    let (exit_code, abnormal) = match &result {
+        Ok(status) => (status.code(), !status.success()),
+        Err(_) => (None, false),
+    };
+    let crash = match INSTANCE_MANAGER.record_exit(pid, exit_code, abnormal).await {
+        Some(instance) => {
+            // The last lines may still be in the pipes
+            for reader in readers {
+                let _ = tokio::time::timeout(DRAIN_TIMEOUT, reader).await;
+            }
+            let tail: Vec<String> = tail.lock().unwrap().iter().cloned().collect();
+            let report = crash::analyze(&instance.runtime_dir, instance.started_at, exit_code, &tail).await;
+
+            lighty_core::trace_warn!(
+                pid = pid,
+                instance = %_instance_name,
+                kind = ?report.kind,
+                summary = %report.summary,
+                "Instance crashed"
+            );
+
+            #[cfg(feature = "events")]
+            if let Some(ref bus) = event_bus {
+                use super::crash::CrashKind;
+                use lighty_event::{CrashCategory, Event, InstanceCrashedEvent};
+                use std::time::SystemTime;
+
+                bus.emit(Event::InstanceCrashed(InstanceCrashedEvent {
+                    pid,
+                    instance_name: _instance_name.clone(),
+                    exit_code,
+                    category: match report.kind {
+                        CrashKind::OutOfMemory => CrashCategory::OutOfMemory,
+                        CrashKind::MissingModDependency => CrashCategory::MissingModDependency,
+                        CrashKind::DuplicateMod => CrashCategory::DuplicateMod,
+                        CrashKind::MixinFailure => CrashCategory::MixinFailure,
+                        CrashKind::IncompatibleJava => CrashCategory::IncompatibleJava,
+                        CrashKind::GpuDriver => CrashCategory::GpuDriver,
+                        CrashKind::JvmCrash => CrashCategory::JvmCrash,
+                        CrashKind::Unknown => CrashCategory::Unknown,
+                    },
+                    report_path: report.report_path.clone(),
+                    summary: report.summary.clone(),
+                    suspected_mods: report.suspected_mods.clone(),
+                    suggested_fix: report.suggested_fix.clone(),
+                    timestamp: SystemTime::now(),
+                }));
+            }
+            Some(report)
+        }
+        None => None,
+    };
+    INSTANCE_MANAGER.unregister_instance(pid).await;
+
+    if let Some(report) = crash {
+        let _ = link.crash.set(report);
+    }
+
+    link.exit.send_replace(Some(result.map_err(|e| e.to_string())));
+}
+
+/// Settles readiness as [`Readiness::TimedOut`] if no ready marker
+/// showed up within `timeout`.
+async fn ready_timeout(
+    timeout: Duration,
+    readiness: Arc<watch::Sender<Readiness>>,
+    #[cfg(feature = "events")] pid: u32,
+    #[cfg(feature = "events")] instance_name: String,
+    #[cfg(feature = "events")] event_bus: Option<EventBus>,
+) {
+    tokio::time::sleep(timeout).await;
+
+    let timed_out = readiness.send_if_modified(|state| {
+        if state.is_settled() {
+            return false;
+        }
+        *state = Readiness::TimedOut;
+        true
+    });
+    if !timed_out {
+        return;
+    }
+
+    lighty_core::trace_warn!(timeout = ?timeout, "No game ready marker before the timeout");
+
+    #[cfg(feature = "events")]
+    if let Some(ref bus) = event_bus {
+        use lighty_event::{Event, InstanceReadyEvent};
+        use std::time::SystemTime;
+
+        bus.emit(Event::InstanceReady(InstanceReadyEvent {
+            pid,
+            instance_name,
+            marker: None,
+            elapsed: timeout,
+            timed_out: true,
+            timestamp: SystemTime::now(),
+        }));
+    }
+}
+
+/// Reads `pipe` line by line into the handle's console and the event bus,
+/// along with the log records the lines complete. With a `detector`, also
+/// moves the readiness along the markers it matches.
+async fn forward_lines<R: AsyncRead + Unpin>(
+    pipe: R,
+    source: ConsoleSource,
+    outputs: Outputs,
+    detector: Option<ReadyDetector>,
+    #[cfg(feature = "events")] pid: u32,
+    #[cfg(feature = "events")] instance_name: String,
+    #[cfg(feature = "events")] event_bus: Option<EventBus>,
+) {
+    let mut lines = BufReader::new(pipe).lines();
+    let mut parser = Log4jParser::default();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let record = parser.push(&line);
+        {
+            let mut tail = outputs.tail.lock().unwrap();
+            if tail.len() == TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line.clone());
+        }
+
+        if let Some(ref detector) = detector {
+            advance_readiness(
+                detector,
+                &line,
+                &outputs.ready,
+                #[cfg(feature = "events")]
+                pid,
+                #[cfg(feature = "events")]
+                &instance_name,
+                #[cfg(feature = "events")]
+                event_bus.as_ref(),
+            );
+        }
+
+        #[cfg(feature = "events")]
+        if let Some(ref bus) = event_bus {
+            use lighty_event::{ConsoleOutputEvent, ConsoleStream, Event};
+            use std::time::SystemTime;
+
+            bus.emit(Event::ConsoleOutput(ConsoleOutputEvent {
+                pid,
+                instance_name: instance_name.clone(),
+                stream: match source {
+                    ConsoleSource::Stdout => ConsoleStream::Stdout,
+                    ConsoleSource::Stderr => ConsoleStream::Stderr,
+                },
+                line: line.clone(),
+                timestamp: SystemTime::now(),
+            }));
+        }
+
+        // No subscriber left is fine; the pipe still has to be drained
+        let _ = outputs.console.send(ConsoleLine { source, line });
+
+        let Some(record) = record else { continue };
+
+        #[cfg(feature = "events")]
+        if let Some(ref bus) = event_bus {
+            use super::log4j::LogLevel;
+            use lighty_event::{Event, GameLogEvent, GameLogLevel};
+
+            bus.emit(Event::GameLog(GameLogEvent {
+                pid,
+                instance_name: instance_name.clone(),
+                level: match record.level {
+                    LogLevel::Trace => GameLogLevel::Trace,
+                    LogLevel::Debug => GameLogLevel::Debug,
+                    LogLevel::Info => GameLogLevel::Info,
+                    LogLevel::Warn => GameLogLevel::Warn,
+                    LogLevel::Error => GameLogLevel::Error,
+                    LogLevel::Fatal => GameLogLevel::Fatal,
+                },
+                thread: record.thread.clone(),
+                logger: record.logger.clone(),
+                message: record.message.clone(),
+                throwable: record.throwable.clone(),
+                timestamp: record.timestamp,
+            }));
+        }
+
+        let _ = outputs.records.send(record);
+    }
+}
+
+/// Moves the readiness to the state `line` reaches, if any.
+fn advance_readiness(
+    detector: &ReadyDetector,
+    line: &str,
+    readiness: &watch::Sender<Readiness>,
+    #[cfg(feature = "events")] pid: u32,
+    #[cfg(feature = "events")] instance_name: &str,
+    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
+) {
+    // Checked under the lock, so the timeout cannot slip in between
+    let mut reached = None;
+    readiness.send_if_modified(|state| match detector.check(line, state) {
+        Some(next) => {
+            *state = next.clone();
+            reached = Some(next);
+            true
+        }
+        None => false,
+    });
+
+    let Some(Readiness::Ready { marker: _marker, after: _after }) = reached else {
+        return;
+    };
+
+    lighty_core::trace_info!(after = ?_after, marker = %_marker, "Game ready");
+
+    #[cfg(feature = "events")]
+    if let Some(bus) = event_bus {
+        use lighty_event::{Event, InstanceReadyEvent};
+        use std::time::SystemTime;
+
+        bus.emit(Event::InstanceReady(InstanceReadyEvent {
+            pid,
+            instance_name: instance_name.to_string(),
+            marker: Some(_marker),
+            elapsed: _after,
+            timed_out: false,
+            timestamp: SystemTime::now(),
+        }));
+    }
 }
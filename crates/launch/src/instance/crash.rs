@@ -0,0 +1,521 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Crash detection and analysis.
+//!
+//! When a game exits abnormally without being asked to stop, the console
+//! task looks for what it left behind in its working directory — the
+//! newest `crash-reports/*.txt` and `hs_err_pid*.log` written since
+//! launch — together with the last console lines, and classifies the
+//! failure into a [`CrashReport`]. Read it through
+//! [`GameHandle::crash_report`](super::GameHandle::crash_report) or the
+//! `InstanceCrashed` event.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Suspected mods kept in a [`CrashReport`].
+const MAX_SUSPECTED_MODS: usize = 10;
+
+/// IDs that show up in stack traces and dependency lists but are never
+/// the culprit.
+const NOT_MODS: &[&str] = &["minecraft", "java", "java.base", "forge", "neoforge", "fabricloader", "fml", "mixin"];
+
+/// Native modules of graphics drivers, as named in an `hs_err` problematic frame.
+const GPU_DRIVER_MODULES: &[&str] = &[
+    "atio6axx", "atioglxx", "atig6pxx", "amdxx64", "nvoglv", "nvd3dum", "libnvidia-glcore", "nvidia_dri",
+    "ig7icd", "ig75icd", "ig8icd", "ig9icd", "igxelpicd", "igdumd", "iris_dri", "i965_dri", "radeonsi_dri",
+    "amdgpu_dri", "swrast_dri", "libgallium",
+];
+
+/// Console lines hinting at a driver without usable OpenGL.
+const GPU_DRIVER_MESSAGES: &[&str] = &[
+    "pixel format not accelerated",
+    "glfw error 65542",
+    "glfw error 65543",
+    "the driver does not appear to support opengl",
+    "no opengl context",
+];
+
+static FABRIC_REQUIRES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Mod '[^']*' \(([\w.\-]+)\) \S+ requires (?:any version|version \S+)(?: or later)? of (?:mod )?'?([^',!]+?)'?(?: \(([\w.\-]+)\))?,? which is missing").unwrap()
+});
+static FORGE_MISSING: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"Mod ID: '([\w.\-]+)', Requested by: '([\w.\-]+)'").unwrap());
+static MIXIN_CONFIG: Lazy<Regex> = Lazy::new(|| Regex::new(r"([\w\-]+)\.mixins?\.json").unwrap());
+static MIXIN_MOD: Lazy<Regex> = Lazy::new(|| Regex::new(r"Mixin apply for mod ([\w.\-]+) failed").unwrap());
+static TRANSFORMER_FRAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"TRANSFORMER/([\w.\-]+)@").unwrap());
+static MOD_ID_IN_PARENS: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(([\w.\-]+)\)").unwrap());
+
+/// What made the game crash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrashKind {
+    /// `java.lang.OutOfMemoryError`, or the JVM could not reserve memory
+    OutOfMemory,
+    /// A mod needs another mod that is not installed
+    MissingModDependency,
+    /// The same mod is installed twice
+    DuplicateMod,
+    /// A mod failed to patch the game (Mixin)
+    MixinFailure,
+    /// The game or a mod was built for a newer Java version
+    IncompatibleJava,
+    /// The graphics driver crashed or offers no usable OpenGL
+    GpuDriver,
+    /// The Java runtime itself crashed (`hs_err_pid*.log`)
+    JvmCrash,
+    /// None of the above
+    Unknown,
+}
+
+impl CrashKind {
+    /// What the player can try
+    pub fn suggested_fix(self) -> &'static str {
+        match self {
+            Self::OutOfMemory => "Give the game more memory (raise -Xmx), or remove heavy mods and resource packs",
+            Self::MissingModDependency => "Install the missing mods listed in the summary, or remove the mods requiring them",
+            Self::DuplicateMod => "Remove the duplicate copies of the listed mods from the mods folder",
+            Self::MixinFailure => "Update or remove the suspected mods; they are likely incompatible with this game or loader version",
+            Self::IncompatibleJava => "Launch with the Java version the game and its mods require",
+            Self::GpuDriver => "Update the graphics driver; on laptops, make sure the game runs on the dedicated GPU",
+            Self::JvmCrash => "Reinstall the Java runtime or try another distribution",
+            Self::Unknown => "Check the crash report or the last console lines for the first error",
+        }
+    }
+}
+
+/// Analysis of an abnormal exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashReport {
+    pub kind: CrashKind,
+    /// Exit code, `None` when killed by a signal
+    pub exit_code: Option<i32>,
+    /// Crash report or `hs_err_pid*.log` the game wrote, if any
+    pub report_path: Option<PathBuf>,
+    /// One line: the exception, the crashing native frame or the last error
+    pub summary: String,
+    /// Mod IDs or mixin configs the crash points at, most likely first
+    pub suspected_mods: Vec<String>,
+    /// What the player can try, from [`CrashKind::suggested_fix`]
+    pub suggested_fix: String,
+}
+
+/// Analyzes the crash of a game started at `started_at` from `runtime_dir`,
+/// with `console_tail` the last lines it printed.
+pub(crate) async fn analyze(
+    runtime_dir: &Path,
+    started_at: SystemTime,
+    exit_code: Option<i32>,
+    console_tail: &[String],
+) -> CrashReport {
+    let crash_report = newest_since(&runtime_dir.join("crash-reports"), started_at, |name| {
+        name.starts_with("crash-") && name.ends_with(".txt")
+    })
+    .await;
+    let hs_err = newest_since(runtime_dir, started_at, |name| {
+        name.starts_with("hs_err_pid") && name.ends_with(".log")
+    })
+    .await;
+
+    let mut report_text = String::new();
+    let mut report_path = None;
+    for path in [crash_report, hs_err].into_iter().flatten() {
+        if let Ok(bytes) = tokio::fs::read(&path).await {
+            report_text.push_str(&String::from_utf8_lossy(&bytes));
+            report_text.push('\n');
+            report_path.get_or_insert(path);
+        }
+    }
+    let text = format!("{}{}", report_text, console_tail.join("\n"));
+    let is_hs_err = report_path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("hs_err_pid"));
+
+    let mut suspected_mods = Vec::new();
+    let (kind, summary) = classify(&text, is_hs_err, &mut suspected_mods);
+    suspected_mods.extend(report_suspects(&report_text));
+
+    let mut seen = HashSet::new();
+    suspected_mods.retain(|id| !NOT_MODS.contains(&id.as_str()) && seen.insert(id.clone()));
+    suspected_mods.truncate(MAX_SUSPECTED_MODS);
+
+    CrashReport {
+        kind,
+        exit_code,
+        report_path,
+        summary: summary.unwrap_or_else(|| generic_summary(&report_text, console_tail)),
+        suspected_mods,
+        suggested_fix: kind.suggested_fix().to_string(),
+    }
+}
+
+/// Kind of crash, with a summary when the kind gives a better one than the
+/// report's description.
+fn classify(text: &str, is_hs_err: bool, suspects: &mut Vec<String>) -> (CrashKind, Option<String>) {
+    let lower = text.to_lowercase();
+
+    if lower.contains("unsupportedclassversionerror")
+        || lower.contains("unsupported class file major version")
+        || lower.contains("compiled by a more recent version of the java runtime")
+        || (lower.contains(" of java") && lower.contains("requires version"))
+    {
+        return (CrashKind::IncompatibleJava, first_line_containing(text, &["UnsupportedClassVersionError", "major version", "of java"]));
+    }
+
+    if lower.contains("java.lang.outofmemoryerror")
+        || lower.contains("there is insufficient memory for the java runtime environment")
+        || lower.contains("could not reserve enough space for")
+    {
+        return (CrashKind::OutOfMemory, first_line_containing(text, &["OutOfMemoryError", "insufficient memory", "Could not reserve"]));
+    }
+
+    let fabric_missing: Vec<_> = FABRIC_REQUIRES.captures_iter(text).collect();
+    let forge_missing: Vec<_> = FORGE_MISSING.captures_iter(text).collect();
+    if !fabric_missing.is_empty()
+        || !forge_missing.is_empty()
+        || lower.contains("missingmodsexception")
+        || lower.contains("missing or unsupported mandatory dependencies")
+    {
+        let mut missing = Vec::new();
+        for captures in &fabric_missing {
+            suspects.push(captures[1].to_string());
+            // The mod ID when given, its name otherwise
+            let missing_mod = captures.get(3).unwrap_or_else(|| captures.get(2).unwrap());
+            missing.push(missing_mod.as_str().trim().to_string());
+        }
+        for captures in &forge_missing {
+            suspects.push(captures[2].to_string());
+            missing.push(captures[1].to_string());
+        }
+        let mut seen = HashSet::new();
+        missing.retain(|id| seen.insert(id.clone()));
+        let summary = (!missing.is_empty()).then(|| format!("Missing mods: {}", missing.join(", ")));
+        return (CrashKind::MissingModDependency, summary);
+    }
+
+    if lower.contains("duplicate mods") || lower.contains("duplicatemodsfoundexception") || lower.contains("found duplicate mod") {
+        return (CrashKind::DuplicateMod, first_line_containing(text, &["duplicate mod"]));
+    }
+
+    if lower.contains("mixinapplyerror")
+        || lower.contains("mixintransformererror")
+        || lower.contains("invalidmixinexception")
+        || lower.contains("mixin apply failed")
+        || lower.contains("mixin apply for mod")
+    {
+        suspects.extend(MIXIN_MOD.captures_iter(text).map(|captures| captures[1].to_string()));
+        suspects.extend(MIXIN_CONFIG.captures_iter(text).map(|captures| captures[1].to_string()));
+        return (CrashKind::MixinFailure, first_line_containing(text, &["Mixin apply", "MixinApplyError", "MixinTransformerError", "InvalidMixinException"]));
+    }
+
+    let problematic_frame = problematic_frame(text);
+    let gpu_frame = problematic_frame.as_deref().is_some_and(|frame| {
+        let frame = frame.to_lowercase();
+        GPU_DRIVER_MODULES.iter().any(|module| frame.contains(module))
+    });
+    if gpu_frame || GPU_DRIVER_MESSAGES.iter().any(|message| lower.contains(message)) {
+        let summary = problematic_frame.or_else(|| first_line_containing(text, GPU_DRIVER_MESSAGES));
+        return (CrashKind::GpuDriver, summary);
+    }
+
+    if is_hs_err {
+        return (CrashKind::JvmCrash, problematic_frame);
+    }
+
+    (CrashKind::Unknown, None)
+}
+
+/// Native frame the JVM crashed in, from an `hs_err` log.
+fn problematic_frame(text: &str) -> Option<String> {
+    let mut lines = text.lines();
+    lines.find(|line| line.starts_with("# Problematic frame:"))?;
+    lines
+        .next()
+        .map(|frame| frame.trim_start_matches('#').trim().to_string())
+        .filter(|frame| !frame.is_empty())
+}
+
+/// Mods a Forge/NeoForge crash report suspects, then those whose
+/// transformed classes appear in its stack traces.
+fn report_suspects(report: &str) -> Vec<String> {
+    let mut suspects = Vec::new();
+
+    let mut lines = report.lines().peekable();
+    while let Some(line) = lines.next() {
+        if !line.trim_start().starts_with("Suspected Mod") {
+            continue;
+        }
+        while let Some(entry) = lines.next_if(|entry| entry.starts_with('\t') || entry.starts_with("  ")) {
+            let entry = entry.trim();
+            if entry.starts_with("at ") || entry.contains("URL:") {
+                continue;
+            }
+            if let Some(captures) = MOD_ID_IN_PARENS.captures(entry) {
+                suspects.push(captures[1].to_string());
+            }
+        }
+    }
+
+    suspects.extend(TRANSFORMER_FRAME.captures_iter(report).map(|captures| captures[1].to_string()));
+    suspects
+}
+
+/// Exception following the report's `Description:`, or the last error the
+/// game printed.
+fn generic_summary(report: &str, console_tail: &[String]) -> String {
+    let mut lines = report.lines();
+    if lines.by_ref().any(|line| line.starts_with("Description:")) {
+        if let Some(exception) = lines.map(str::trim).find(|line| !line.is_empty()) {
+            return exception.to_string();
+        }
+    }
+
+    console_tail
+        .iter()
+        .rev()
+        .find(|line| line.contains("Exception") || line.contains("Error") || line.contains("ERROR"))
+        .or_else(|| console_tail.iter().rev().find(|line| !line.trim().is_empty()))
+        .map(|line| line.trim().to_string())
+        .unwrap_or_else(|| "The game exited without a crash report".to_string())
+}
+
+/// First line containing one of `needles`, ignoring case.
+fn first_line_containing(text: &str, needles: &[&str]) -> Option<String> {
+    let needles: Vec<String> = needles.iter().map(|needle| needle.to_lowercase()).collect();
+    text.lines()
+        .find(|line| {
+            let line = line.to_lowercase();
+            needles.iter().any(|needle| line.contains(needle.as_str()))
+        })
+        .map(|line| line.trim().to_string())
+}
+
+/// Newest file of `dir` modified at or after `since` whose name passes `matches`.
+async fn newest_since(dir: &Path, since: SystemTime, matches: impl Fn(&str) -> bool) -> Option<PathBuf> {
+    let mut entries = tokio::fs::read_dir(dir).await.ok()?;
+    let mut newest: Option<(SystemTime, PathBuf)> = None;
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        if !name.to_str().is_some_and(&matches) {
+            continue;
+        }
+        let Ok(modified) = entry.metadata().await.and_then(|metadata| metadata.modified()) else {
+            continue;
+        };
+        if modified >= since && newest.as_ref().map_or(true, |(time, _)| modified > *time) {
+            newest = Some((modified, entry.path()));
+        }
+    }
+
+    newest.map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify_text(text: &str, is_hs_err: bool) -> (CrashKind, Option<String>, Vec<String>) {
+        let mut suspects = Vec::new();
+        let (kind, summary) = classify(text, is_hs_err, &mut suspects);
+        (kind, summary, suspects)
+    }
+
+    #[test]
+    fn newer_class_file_is_incompatible_java() {
+        let console = "Error: LinkageError occurred while loading main class net.minecraft.client.main.Main
+\tjava.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 61.0";
+
+        let (kind, summary, _) = classify_text(console, false);
+        assert_eq!(kind, CrashKind::IncompatibleJava);
+        assert!(summary.unwrap().starts_with("java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main"));
+    }
+
+    #[test]
+    fn heap_exhaustion_is_out_of_memory() {
+        let report = "---- Minecraft Crash Report ----
+// Uh... Did I do that?
+
+Time: 2024-06-13 18:02:11
+Description: Unexpected error
+
+java.lang.OutOfMemoryError: Java heap space
+\tat java.base/java.util.Arrays.copyOf(Arrays.java:3537)
+\tat net.minecraft.client.renderer.chunk.SectionRenderDispatcher.<init>(SectionRenderDispatcher.java:74)";
+
+        let (kind, summary, _) = classify_text(report, false);
+        assert_eq!(kind, CrashKind::OutOfMemory);
+        assert_eq!(summary.as_deref(), Some("java.lang.OutOfMemoryError: Java heap space"));
+
+        let console = "Error occurred during initialization of VM\nCould not reserve enough space for 8388608KB object heap";
+        assert_eq!(classify_text(console, false).0, CrashKind::OutOfMemory);
+    }
+
+    #[test]
+    fn fabric_unmet_dependencies_list_missing_mods() {
+        let console = "net.fabricmc.loader.impl.FormattedException: Mod resolution encountered an incompatible mod set!
+A potential solution has been determined:
+\t - Install fabric-api, any version.
+\t - Install sodium, version 0.5.0 or later.
+Unmet dependency listing:
+\t - Mod 'Mod Menu' (modmenu) 7.2.2 requires any version of fabric-api, which is missing!
+\t - Mod 'Iris' (iris) 1.6.4+1.20 requires version 0.5.0 or later of mod 'Sodium' (sodium), which is missing!";
+
+        let (kind, summary, suspects) = classify_text(console, false);
+        assert_eq!(kind, CrashKind::MissingModDependency);
+        assert_eq!(summary.as_deref(), Some("Missing mods: fabric-api, sodium"));
+        assert_eq!(suspects, ["modmenu", "iris"]);
+    }
+
+    #[test]
+    fn forge_missing_dependencies_list_missing_mods() {
+        let console = "[main/ERROR] [net.minecraftforge.fml.loading.ModSorter/LOADING]: Missing or unsupported mandatory dependencies:
+\tMod ID: 'architectury', Requested by: 'roughlyenoughitems', Expected range: '[9.1,)', Actual version: '[MISSING]'
+\tMod ID: 'cloth_config', Requested by: 'roughlyenoughitems', Expected range: '[11.1,)', Actual version: '[MISSING]'";
+
+        let (kind, summary, suspects) = classify_text(console, false);
+        assert_eq!(kind, CrashKind::MissingModDependency);
+        assert_eq!(summary.as_deref(), Some("Missing mods: architectury, cloth_config"));
+        assert_eq!(suspects, ["roughlyenoughitems", "roughlyenoughitems"]);
+    }
+
+    #[test]
+    fn duplicate_mods() {
+        let console = "net.minecraftforge.fml.loading.moddiscovery.DuplicateModsFoundException: Found duplicate mods: jei";
+        let (kind, summary, _) = classify_text(console, false);
+        assert_eq!(kind, CrashKind::DuplicateMod);
+        assert_eq!(summary.as_deref(), Some(console));
+    }
+
+    #[test]
+    fn mixin_failures_suspect_the_config_owner() {
+        let report = "Description: Initializing game
+
+java.lang.RuntimeException: org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: An unexpected critical error was encountered
+Caused by: org.spongepowered.asm.mixin.throwables.MixinApplyError: Mixin [sodium-extra.mixins.json:sodium.MixinSodiumWorldRenderer] from phase [DEFAULT] in config [sodium-extra.mixins.json] FAILED during APPLY
+Caused by: org.spongepowered.asm.mixin.transformer.throwables.InvalidMixinException: @Shadow field renderDistance was not located in the target class";
+
+        let (kind, summary, suspects) = classify_text(report, false);
+        assert_eq!(kind, CrashKind::MixinFailure);
+        assert!(summary.unwrap().contains("MixinTransformerError"));
+        assert!(suspects.iter().all(|id| id == "sodium-extra"));
+        assert!(!suspects.is_empty());
+
+        let console = "[main/ERROR]: Mixin apply for mod iris failed iris.mixins.json:MixinGameRenderer from mod iris -> net.minecraft.class_757";
+        let (kind, _, suspects) = classify_text(console, false);
+        assert_eq!(kind, CrashKind::MixinFailure);
+        assert_eq!(suspects[0], "iris");
+    }
+
+    #[test]
+    fn driver_frame_in_hs_err_is_gpu_driver() {
+        let hs_err = "#
+# A fatal error has been detected by the Java Runtime Environment:
+#
+#  EXCEPTION_ACCESS_VIOLATION (0xc0000005) at pc=0x00007ffd3a1a0f3c, pid=11332, tid=9876
+#
+# JRE version: OpenJDK Runtime Environment Temurin-17.0.8+7 (17.0.8+7) (build 17.0.8+7)
+# Problematic frame:
+# C  [atio6axx.dll+0x1a0f3c]
+#";
+
+        let (kind, summary, _) = classify_text(hs_err, true);
+        assert_eq!(kind, CrashKind::GpuDriver);
+        assert_eq!(summary.as_deref(), Some("C  [atio6axx.dll+0x1a0f3c]"));
+    }
+
+    #[test]
+    fn opengl_errors_are_gpu_driver() {
+        let console = "[Render thread/ERROR]: GLFW error 65542: WGL: The driver does not appear to support OpenGL";
+        let (kind, summary, _) = classify_text(console, false);
+        assert_eq!(kind, CrashKind::GpuDriver);
+        assert_eq!(summary.as_deref(), Some(console));
+    }
+
+    #[test]
+    fn other_hs_err_is_jvm_crash() {
+        let hs_err = "#  SIGSEGV (0xb) at pc=0x00007f1e2c6b8f2e, pid=4211, tid=4270
+# Problematic frame:
+# V  [libjvm.so+0x6b8f2e]  G1ParScanThreadState::trim_queue_to_threshold(unsigned int)+0x31e";
+
+        let (kind, summary, _) = classify_text(hs_err, true);
+        assert_eq!(kind, CrashKind::JvmCrash);
+        assert_eq!(
+            summary.as_deref(),
+            Some("V  [libjvm.so+0x6b8f2e]  G1ParScanThreadState::trim_queue_to_threshold(unsigned int)+0x31e")
+        );
+        assert_eq!(classify_text(hs_err, false).0, CrashKind::Unknown);
+    }
+
+    #[test]
+    fn forge_report_suspects() {
+        let report = "-- Head --
+Thread: Render thread
+Suspected Mods: 
+\tCreate (create), Version: 0.5.1.f
+\t\tIssue tracker URL: https://github.com/Creators-of-Create/Create/issues
+\t\tat TRANSFORMER/create@0.5.1.f/com.simibubi.create.content.kinetics.base.KineticBlockEntity.tick(KineticBlockEntity.java:102)
+\tFlywheel (flywheel), Version: 0.6.10-7
+Stacktrace:
+\tat TRANSFORMER/minecraft@1.20.1/net.minecraft.world.level.Level.tickBlockEntities(Level.java:480)";
+
+        assert_eq!(report_suspects(report), ["create", "flywheel", "create", "minecraft"]);
+    }
+
+    #[test]
+    fn generic_summary_prefers_the_report_description() {
+        let report = "Time: 2024-06-13 18:02:11
+Description: Rendering overlay
+
+java.lang.NullPointerException: Cannot invoke \"net.minecraft.client.renderer.ShaderInstance.apply()\" because \"shader\" is null
+\tat net.minecraft.client.renderer.GameRenderer.render(GameRenderer.java:906)";
+        assert_eq!(
+            generic_summary(report, &[]),
+            "java.lang.NullPointerException: Cannot invoke \"net.minecraft.client.renderer.ShaderInstance.apply()\" because \"shader\" is null"
+        );
+
+        let tail = ["[Render thread/ERROR]: Failed to load textures".to_string(), "Stopping!".to_string()];
+        assert_eq!(generic_summary("", &tail), "[Render thread/ERROR]: Failed to load textures");
+        assert_eq!(generic_summary("", &[]), "The game exited without a crash report");
+    }
+
+    #[tokio::test]
+    async fn analyze_reads_the_newest_crash_report() {
+        let dir = std::env::temp_dir().join(format!("lighty-crash-{}", std::process::id()));
+        let reports = dir.join("crash-reports");
+        tokio::fs::create_dir_all(&reports).await.unwrap();
+        let started_at = SystemTime::now() - std::time::Duration::from_secs(60);
+
+        let path = reports.join("crash-2024-06-13_18.02.11-client.txt");
+        tokio::fs::write(
+            &path,
+            "Description: Ticking block entity
+
+java.lang.ArrayIndexOutOfBoundsException: Index 4 out of bounds for length 4
+-- Head --
+Suspected Mods: 
+\tCreate (create), Version: 0.5.1.f
+Stacktrace:
+\tat TRANSFORMER/minecraft@1.20.1/net.minecraft.world.level.Level.tickBlockEntities(Level.java:480)
+\tat TRANSFORMER/create@0.5.1.f/com.simibubi.create.Create.tick(Create.java:12)",
+        )
+        .await
+        .unwrap();
+
+        let report = analyze(&dir, started_at, Some(-1), &["Stopping!".to_string()]).await;
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        assert_eq!(report.kind, CrashKind::Unknown);
+        assert_eq!(report.exit_code, Some(-1));
+        assert_eq!(report.report_path, Some(path));
+        assert_eq!(report.summary, "java.lang.ArrayIndexOutOfBoundsException: Index 4 out of bounds for length 4");
+        assert_eq!(report.suspected_mods, ["create"]);
+        assert_eq!(report.suggested_fix, CrashKind::Unknown.suggested_fix());
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Handle on a launched game process, returned by
+//! [`LaunchBuilder::run`](crate::launch::LaunchBuilder::run).
+//!
+//! The console task owns the [`Child`](tokio::process::Child); the handle
+//! talks to it through channels, so it can be moved around freely and
+//! dropping it leaves the game running.
+
+use std::process::ExitStatus;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::{broadcast, watch, Notify};
+
+use super::crash::CrashReport;
+use super::errors::{InstanceError, InstanceResult};
+use super::log4j::LogRecord;
+use super::ready::Readiness;
+use super::INSTANCE_MANAGER;
+
+/// Console lines buffered per subscriber before the oldest are dropped.
+const CONSOLE_CAPACITY: usize = 1024;
+
+/// Pipe a console line was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleSource {
+    Stdout,
+    Stderr,
+}
+
+/// One line of game output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleLine {
+    pub source: ConsoleSource,
+    pub line: String,
+}
+
+/// Console output of one game process.
+///
+/// Ends once both pipes are closed. A subscriber that falls more than
+/// 1024 lines behind skips the oldest ones.
+pub struct ConsoleLines {
+    receiver: broadcast::Receiver<ConsoleLine>,
+}
+
+impl ConsoleLines {
+    /// Next line, or `None` once the process closed its output.
+    pub async fn next(&mut self) -> Option<ConsoleLine> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(line) => return Some(line),
+                Err(broadcast::error::RecvError::Lagged(_skipped)) => {
+                    lighty_core::trace_warn!(skipped = _skipped, "Console subscriber lagging, lines dropped");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Structured log records of one game process.
+///
+/// Only versions started with a log4j XML configuration produce records;
+/// the others end without any once the process closed its output.
+pub struct LogRecords {
+    receiver: broadcast::Receiver<LogRecord>,
+}
+
+impl LogRecords {
+    /// Next record, or `None` once the process closed its output.
+    pub async fn next(&mut self) -> Option<LogRecord> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(record) => return Some(record),
+                Err(broadcast::error::RecvError::Lagged(_skipped)) => {
+                    lighty_core::trace_warn!(skipped = _skipped, "Log record subscriber lagging, records dropped");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// A running (or exited) game process.
+pub struct GameHandle {
+    pid: u32,
+    instance_name: String,
+    lines: ConsoleLines,
+    records: LogRecords,
+    exit: watch::Receiver<Option<Result<ExitStatus, String>>>,
+    ready: watch::Receiver<Readiness>,
+    crash: Arc<OnceLock<CrashReport>>,
+    kill: Arc<Notify>,
+}
+
+/// Console task side of a [`GameHandle`].
+pub(crate) struct HandleLink {
+    pub(crate) console: broadcast::Sender<ConsoleLine>,
+    pub(crate) records: broadcast::Sender<LogRecord>,
+    pub(crate) exit: watch::Sender<Option<Result<ExitStatus, String>>>,
+    pub(crate) ready: watch::Sender<Readiness>,
+    /// Set before the exit status is published
+    pub(crate) crash: Arc<OnceLock<CrashReport>>,
+    pub(crate) kill: Arc<Notify>,
+}
+
+impl GameHandle {
+    /// Creates a handle and the link the console task feeds it through.
+    ///
+    /// The handle subscribes to the console right away, so
+    /// [`Self::next_line`] and [`Self::next_record`] see the output from
+    /// the very first line.
+    pub(crate) fn new(pid: u32, instance_name: String) -> (Self, HandleLink) {
+        let (console, receiver) = broadcast::channel(CONSOLE_CAPACITY);
+        let (records, records_receiver) = broadcast::channel(CONSOLE_CAPACITY);
+        let (exit, exit_receiver) = watch::channel(None);
+        let (ready, ready_receiver) = watch::channel(Readiness::Starting);
+        let crash = Arc::new(OnceLock::new());
+        let kill = Arc::new(Notify::new());
+
+        let handle = Self {
+            pid,
+            instance_name,
+            lines: ConsoleLines { receiver },
+            records: LogRecords { receiver: records_receiver },
+            exit: exit_receiver,
+            ready: ready_receiver,
+            crash: Arc::clone(&crash),
+            kill: Arc::clone(&kill),
+        };
+        (handle, HandleLink { console, records, exit, ready, crash, kill })
+    }
+
+    /// Process ID
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Name of the launched instance
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    /// Next console line since launch, or `None` once the output is closed
+    pub async fn next_line(&mut self) -> Option<ConsoleLine> {
+        self.lines.next().await
+    }
+
+    /// Additional console subscriber, starting at the next line printed
+    pub fn console(&self) -> ConsoleLines {
+        ConsoleLines {
+            receiver: self.lines.receiver.resubscribe(),
+        }
+    }
+
+    /// Next structured log record since launch, or `None` once the output
+    /// is closed
+    pub async fn next_record(&mut self) -> Option<LogRecord> {
+        self.records.next().await
+    }
+
+    /// Additional log record subscriber, starting at the next record logged
+    pub fn log_records(&self) -> LogRecords {
+        LogRecords {
+            receiver: self.records.receiver.resubscribe(),
+        }
+    }
+
+    /// Waits for the process to exit
+    ///
+    /// The instance is already unregistered when this returns.
+    pub async fn wait(&self) -> InstanceResult<ExitStatus> {
+        let mut exit = self.exit.clone();
+        let outcome = exit
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| InstanceError::Io(std::io::Error::other("console task ended unexpectedly")))?
+            .clone();
+
+        match outcome {
+            Some(Ok(status)) => Ok(status),
+            Some(Err(e)) => Err(InstanceError::Io(std::io::Error::other(e))),
+            None => unreachable!("waited for Some"),
+        }
+    }
+
+    /// Exit status, if the process has already exited
+    pub fn try_wait(&self) -> Option<ExitStatus> {
+        match *self.exit.borrow() {
+            Some(Ok(status)) => Some(status),
+            _ => None,
+        }
+    }
+
+    /// Startup progress, from the console's "game ready" markers
+    pub fn readiness(&self) -> Readiness {
+        self.ready.borrow().clone()
+    }
+
+    /// Waits for the game to be ready, the ready timeout to expire or the
+    /// process to exit, whichever comes first
+    ///
+    /// Works the same on every OS; a launcher can hide its splash screen
+    /// once this returns.
+    pub async fn wait_ready(&self) -> Readiness {
+        let mut ready = self.ready.clone();
+        let readiness = match ready.wait_for(Readiness::is_settled).await {
+            Ok(readiness) => readiness.clone(),
+            Err(_) => Readiness::Exited,
+        };
+        readiness
+    }
+
+    /// Readiness subscriber for the window watcher
+    #[cfg(feature = "events")]
+    pub(crate) fn readiness_watch(&self) -> watch::Receiver<Readiness> {
+        self.ready.clone()
+    }
+
+    /// Crash analysis, once the process exited abnormally without being
+    /// asked to stop
+    ///
+    /// Available as soon as [`Self::wait`] returns.
+    pub fn crash_report(&self) -> Option<&CrashReport> {
+        self.crash.get()
+    }
+
+    /// Asks the game to shut down, giving it a chance to save
+    ///
+    /// Same as [`InstanceControl::close_instance`](super::InstanceControl::close_instance):
+    /// force-killed after [`DEFAULT_GRACE_PERIOD`](super::DEFAULT_GRACE_PERIOD).
+    pub async fn stop(&self) -> InstanceResult<()> {
+        self.stop_with_grace(super::DEFAULT_GRACE_PERIOD).await
+    }
+
+    /// Like [`Self::stop`], force-killing the game after `grace_period`
+    pub async fn stop_with_grace(&self, grace_period: Duration) -> InstanceResult<()> {
+        INSTANCE_MANAGER.close_instance(self.pid, grace_period).await
+    }
+
+    /// Kills the process group immediately; unsaved progress is lost
+    pub fn kill(&self) {
+        self.kill.notify_one();
+    }
+}
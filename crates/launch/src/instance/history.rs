@@ -0,0 +1,141 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Launch history and playtime statistics.
+//!
+//! Every session that ends is appended to `<game_dirs>/.lighty-history.json`
+//! together with running totals, so the history lives and dies with the
+//! instance directory. Query it through
+//! [`InstanceControl::launch_history`](super::InstanceControl::launch_history)
+//! and [`InstanceControl::instance_stats`](super::InstanceControl::instance_stats).
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use lighty_core::persist::write_atomic;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use super::manager::GameInstance;
+
+/// History file, stored under the instance's game directory.
+const HISTORY_FILE: &str = ".lighty-history.json";
+
+/// Sessions kept in the file; totals in [`InstanceStats`] cover every session.
+const MAX_SESSIONS: usize = 500;
+
+/// Serializes read-modify-write cycles of history files.
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// One finished game session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchRecord {
+    /// When the game was started
+    pub launched_at: SystemTime,
+    /// How long it ran
+    pub duration: Duration,
+    /// Exit code; `None` when killed by a signal or reattached after a
+    /// launcher restart (the exit code went to the previous launcher)
+    pub exit_code: Option<i32>,
+    /// Whether the game exited abnormally without being asked to stop
+    pub crashed: bool,
+    /// Account username
+    pub username: String,
+    /// Version string (e.g., "1.20.1-fabric-0.15.0")
+    pub version: String,
+}
+
+/// Totals over every recorded session of an instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceStats {
+    /// Number of finished sessions
+    pub launches: u64,
+    /// Sum of every session's duration
+    pub total_playtime: Duration,
+    /// When the last session ended
+    pub last_played: Option<SystemTime>,
+    /// Sessions that ended in a crash
+    pub crash_count: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryFile {
+    stats: InstanceStats,
+    /// Oldest first
+    sessions: Vec<LaunchRecord>,
+}
+
+/// Sessions of the instance at `game_dirs`, oldest first.
+pub(crate) async fn load_history(game_dirs: &Path) -> Vec<LaunchRecord> {
+    load(game_dirs).await.sessions
+}
+
+/// Totals of the instance at `game_dirs`.
+pub(crate) async fn load_stats(game_dirs: &Path) -> InstanceStats {
+    load(game_dirs).await.stats
+}
+
+/// Appends the session of `instance`, which just ended. Best-effort: a
+/// failed write only loses this session.
+pub(crate) async fn record_session(instance: &GameInstance, exit_code: Option<i32>, crashed: bool) {
+    let ended_at = SystemTime::now();
+    let record = LaunchRecord {
+        launched_at: instance.started_at,
+        duration: ended_at.duration_since(instance.started_at).unwrap_or_default(),
+        exit_code,
+        crashed,
+        username: instance.username.clone(),
+        version: instance.version.clone(),
+    };
+
+    let _guard = HISTORY_LOCK.lock().await;
+    let mut history = load(&instance.game_dir).await;
+
+    history.stats.launches += 1;
+    history.stats.total_playtime += record.duration;
+    history.stats.last_played = Some(ended_at);
+    if crashed {
+        history.stats.crash_count += 1;
+    }
+    history.sessions.push(record);
+    let overflow = history.sessions.len().saturating_sub(MAX_SESSIONS);
+    history.sessions.drain(..overflow);
+
+    let path = instance.game_dir.join(HISTORY_FILE);
+    let result = async {
+        let bytes = serde_json::to_vec(&history)?;
+        write_atomic(&path, &bytes).await?;
+        Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    }
+    .await;
+
+    if let Err(_e) = result {
+        lighty_core::trace_warn!(instance = %instance.instance_name, error = %_e, "Failed to save launch history");
+    }
+}
+
+/// History of the instance at `game_dirs`; empty when there is none. An
+/// unreadable file is moved aside to `<file>.corrupt` so the next save
+/// doesn't overwrite it.
+async fn load(game_dirs: &Path) -> HistoryFile {
+    let path = game_dirs.join(HISTORY_FILE);
+    let Ok(bytes) = tokio::fs::read(&path).await else {
+        return HistoryFile::default();
+    };
+
+    match serde_json::from_slice(&bytes) {
+        Ok(history) => history,
+        Err(_e) => {
+            let corrupt = game_dirs.join(format!("{}.corrupt", HISTORY_FILE));
+            lighty_core::trace_warn!(
+                path = %path.display(),
+                error = %_e,
+                "Unreadable launch history, moved to {}",
+                corrupt.display()
+            );
+            let _ = tokio::fs::rename(&path, &corrupt).await;
+            HistoryFile::default()
+        }
+    }
+}
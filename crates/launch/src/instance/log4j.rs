@@ -0,0 +1,270 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Structured game log records.
+//!
+//! Versions whose manifest has a logging configuration are started with
+//! Mojang's log4j XML layout, so stdout carries events such as:
+//!
+//! ```text
+//! <log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="INFO" thread="Render thread">
+//!   <log4j:Message><![CDATA[Setting user: Steve]]></log4j:Message>
+//! </log4j:Event>
+//! ```
+//!
+//! [`Log4jParser`] rebuilds them from the raw lines into [`LogRecord`]s,
+//! read through [`GameHandle::next_record`](super::GameHandle::next_record).
+//! The raw lines are still forwarded as [`ConsoleLine`](super::ConsoleLine)s.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const EVENT_START: &str = "<log4j:Event ";
+const EVENT_END: &str = "</log4j:Event>";
+const CDATA_START: &str = "<![CDATA[";
+const CDATA_END: &str = "]]>";
+
+/// Longest event buffered; a longer one is dropped (the raw lines remain).
+const MAX_EVENT_LEN: usize = 1024 * 1024;
+
+/// Severity of a [`LogRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    /// Parses a log4j level name; unknown levels are treated as `Info`
+    fn from_log4j(level: &str) -> Self {
+        match level {
+            "TRACE" => Self::Trace,
+            "DEBUG" => Self::Debug,
+            "WARN" => Self::Warn,
+            "ERROR" => Self::Error,
+            "FATAL" => Self::Fatal,
+            _ => Self::Info,
+        }
+    }
+}
+
+/// One log event of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// When the game logged it
+    pub timestamp: SystemTime,
+    pub level: LogLevel,
+    /// Thread name (e.g. `"Render thread"`)
+    pub thread: String,
+    /// Logger name (e.g. `"net.minecraft.client.Minecraft"`)
+    pub logger: String,
+    pub message: String,
+    /// Exception and stack trace, when one was logged
+    pub throwable: Option<String>,
+}
+
+/// Rebuilds [`LogRecord`]s from console lines, one line at a time.
+#[derive(Default)]
+pub(crate) struct Log4jParser {
+    /// Event being read, from its opening tag
+    buffer: Option<String>,
+}
+
+impl Log4jParser {
+    /// Feeds one line; returns the record it completes, if any.
+    pub(crate) fn push(&mut self, line: &str) -> Option<LogRecord> {
+        match &mut self.buffer {
+            Some(buffer) => {
+                buffer.push('\n');
+                buffer.push_str(line);
+            }
+            None => {
+                let start = line.find(EVENT_START)?;
+                self.buffer = Some(line[start..].to_string());
+            }
+        }
+
+        if line.contains(EVENT_END) {
+            return self.buffer.take().and_then(|event| parse_event(&event));
+        }
+        if self.buffer.as_ref().is_some_and(|buffer| buffer.len() > MAX_EVENT_LEN) {
+            lighty_core::trace_warn!("Oversized log4j event dropped");
+            self.buffer = None;
+        }
+        None
+    }
+}
+
+fn parse_event(event: &str) -> Option<LogRecord> {
+    let tag_end = event.find('>')?;
+    let (tag, body) = event.split_at(tag_end + 1);
+
+    let timestamp = attribute(tag, "timestamp")
+        .and_then(|millis| millis.parse().ok())
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis))
+        .unwrap_or_else(SystemTime::now);
+
+    Some(LogRecord {
+        timestamp,
+        level: LogLevel::from_log4j(&attribute(tag, "level").unwrap_or_default()),
+        thread: attribute(tag, "thread").unwrap_or_default(),
+        logger: attribute(tag, "logger").unwrap_or_default(),
+        message: element(body, "log4j:Message").unwrap_or_default(),
+        throwable: element(body, "log4j:Throwable"),
+    })
+}
+
+/// Value of the attribute `name` of an opening tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let key = format!(" {}=\"", name);
+    let start = tag.find(&key)? + key.len();
+    let len = tag[start..].find('"')?;
+    Some(unescape(&tag[start..start + len]))
+}
+
+/// Text of the first `name` element in `body`.
+fn element(body: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = body.find(&open)? + open.len();
+    let len = body[start..].find(&close)?;
+    Some(text_content(&body[start..start + len]))
+}
+
+/// Joins the CDATA sections of `raw` with its unescaped text.
+fn text_content(raw: &str) -> String {
+    let mut text = String::new();
+    let mut rest = raw;
+
+    while let Some(start) = rest.find(CDATA_START) {
+        text.push_str(&unescape(&rest[..start]));
+        let section = &rest[start + CDATA_START.len()..];
+        let end = section.find(CDATA_END).unwrap_or(section.len());
+        text.push_str(&section[..end]);
+        rest = section.get(end + CDATA_END.len()..).unwrap_or("");
+    }
+    text.push_str(&unescape(rest));
+    text
+}
+
+fn unescape(value: &str) -> String {
+    if !value.contains('&') {
+        return value.to_string();
+    }
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `output` line by line, collecting the records.
+    fn parse(output: &str) -> Vec<LogRecord> {
+        let mut parser = Log4jParser::default();
+        output.lines().filter_map(|line| parser.push(line)).collect()
+    }
+
+    #[test]
+    fn parses_client_output() {
+        let output = r#"[0.003s][warning][os,thread] Failed to start thread "Unknown thread"
+<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1718293331443" level="INFO" thread="Render thread">
+  <log4j:Message><![CDATA[Setting user: Steve]]></log4j:Message>
+</log4j:Event>
+<log4j:Event logger="com.mojang.blaze3d.platform.GLX" timestamp="1718293332010" level="WARN" thread="Render thread">
+  <log4j:Message><![CDATA[Failed to find OpenGL 3.2 core profile, using compatibility]]></log4j:Message>
+</log4j:Event>"#;
+
+        let records = parse(output);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0],
+            LogRecord {
+                timestamp: UNIX_EPOCH + Duration::from_millis(1718293331443),
+                level: LogLevel::Info,
+                thread: "Render thread".into(),
+                logger: "net.minecraft.client.Minecraft".into(),
+                message: "Setting user: Steve".into(),
+                throwable: None,
+            }
+        );
+        assert_eq!(records[1].level, LogLevel::Warn);
+        assert_eq!(records[1].logger, "com.mojang.blaze3d.platform.GLX");
+    }
+
+    #[test]
+    fn keeps_the_stack_trace() {
+        let output = "<log4j:Event logger=\"net.minecraft.server.packs.resources.ReloadableResourceManager\" timestamp=\"1718293340120\" level=\"ERROR\" thread=\"Worker-Main-3\">
+  <log4j:Message><![CDATA[Failed to load shader: minecraft:shaders/core/rendertype_solid.json]]></log4j:Message>
+  <log4j:Throwable><![CDATA[java.io.FileNotFoundException: minecraft:shaders/core/rendertype_solid.json
+\tat net.minecraft.server.packs.resources.ResourceProvider.getResourceOrThrow(ResourceProvider.java:26)
+\tat net.minecraft.client.renderer.ShaderInstance.<init>(ShaderInstance.java:103)
+]]></log4j:Throwable>
+</log4j:Event>";
+
+        let records = parse(output);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, LogLevel::Error);
+        assert_eq!(records[0].thread, "Worker-Main-3");
+        let throwable = records[0].throwable.as_deref().unwrap();
+        assert!(throwable.starts_with("java.io.FileNotFoundException: minecraft:shaders/core/rendertype_solid.json\n"));
+        assert!(throwable.contains("\tat net.minecraft.client.renderer.ShaderInstance.<init>(ShaderInstance.java:103)"));
+    }
+
+    #[test]
+    fn unescapes_attributes_and_text() {
+        let output = r#"<log4j:Event logger="Mod &quot;Fancy&quot; &amp; Co" timestamp="1718293331443" level="DEBUG" thread="Server &lt;1&gt;"><log4j:Message>a &lt; b &amp;&amp; c</log4j:Message></log4j:Event>"#;
+
+        let records = parse(output);
+        assert_eq!(records[0].logger, "Mod \"Fancy\" & Co");
+        assert_eq!(records[0].thread, "Server <1>");
+        assert_eq!(records[0].message, "a < b && c");
+        assert_eq!(records[0].level, LogLevel::Debug);
+    }
+
+    #[test]
+    fn joins_split_cdata_sections() {
+        // log4j splits a message containing `]]>` across two sections
+        let output = r#"<log4j:Event logger="chat" timestamp="1718293331443" level="INFO" thread="Render thread">
+  <log4j:Message><![CDATA[[CHAT] <Alex> look: ]]]]><![CDATA[>]]></log4j:Message>
+</log4j:Event>"#;
+
+        assert_eq!(parse(output)[0].message, "[CHAT] <Alex> look: ]]>");
+    }
+
+    #[test]
+    fn tolerates_missing_and_unknown_attributes() {
+        let before = SystemTime::now();
+        let records = parse(r#"<log4j:Event level="NOTICE"><log4j:Message><![CDATA[hi]]></log4j:Message></log4j:Event>"#);
+        assert_eq!(records[0].level, LogLevel::Info);
+        assert_eq!(records[0].thread, "");
+        assert!(records[0].timestamp >= before);
+    }
+
+    #[test]
+    fn plain_lines_are_not_records() {
+        assert!(parse("[12:00:00] [Render thread/INFO]: Setting user: Steve\nException in thread \"main\"").is_empty());
+    }
+
+    #[test]
+    fn oversized_event_is_dropped() {
+        let mut parser = Log4jParser::default();
+        assert!(parser.push(r#"<log4j:Event logger="spam" timestamp="1" level="INFO" thread="main">"#).is_none());
+        let line = "x".repeat(64 * 1024);
+        for _ in 0..(MAX_EVENT_LEN / line.len() + 1) {
+            assert!(parser.push(&line).is_none());
+        }
+        assert!(parser.push("</log4j:Event>").is_none());
+
+        // The next event parses normally
+        let record = parser.push(r#"<log4j:Event logger="ok" timestamp="1" level="INFO" thread="main"><log4j:Message>ok</log4j:Message></log4j:Event>"#);
+        assert_eq!(record.unwrap().message, "ok");
+    }
+}
@@ -1,37 +1,60 @@
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::RwLock;
-use std::time::SystemTime;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+#[cfg(feature = "events")]
+use lighty_event::ShutdownStage;
 
 use super::errors::{InstanceError, InstanceResult};
+use super::history;
+use super::registry::{self, InstanceRecord};
+
+/// Time a closed instance gets to exit on its own before it is killed.
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
 
 /// Internal representation of a running game instance.
 ///
-/// `version`/`username`/`game_dir`/`started_at` are stored for future
-/// audit/debug APIs (e.g. `pub fn started_at()` exposed via `InstanceControl`).
+/// Mirrored on disk by the [registry](super::registry) so a restarted
+/// launcher can reattach to it.
+/// The directories and Java binary are what the instance holds open; the
+/// [garbage collector](crate::installer::gc) leaves them alone.
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct GameInstance {
     /// Process ID
     pub pid: u32,
     /// Instance name
     pub instance_name: String,
     /// Version string (e.g., "1.20.1-fabric-0.15.0")
-    #[allow(dead_code)]
     pub version: String,
     /// Username used to launch
-    #[allow(dead_code)]
     pub username: String,
     /// Game directory path
-    #[allow(dead_code)]
     pub game_dir: PathBuf,
+    /// Working directory of the game process, where it writes crash reports
+    #[serde(default)]
+    pub runtime_dir: PathBuf,
+    /// `${library_directory}` the instance was launched with
+    pub libraries_dir: PathBuf,
+    /// `${assets_root}` the instance was launched with
+    pub assets_dir: PathBuf,
+    /// Java binary running the instance
+    pub java_path: PathBuf,
     /// Launch timestamp
-    #[allow(dead_code)]
     pub started_at: SystemTime,
 }
 
 /// Internal manager for tracking running game instances
 pub(crate) struct InstanceManager {
     instances: RwLock<HashMap<u32, GameInstance>>,
+    /// Woken whenever an instance is unregistered
+    unregistered: Notify,
+    /// Instances asked to stop, whose exit isn't a crash
+    stop_requested: Mutex<HashSet<u32>>,
 }
 
 /// Global instance manager
@@ -42,6 +65,8 @@ impl InstanceManager {
     pub fn new() -> Self {
         Self {
             instances: RwLock::new(HashMap::new()),
+            unregistered: Notify::new(),
+            stop_requested: Mutex::new(HashSet::new()),
         }
     }
 
@@ -64,68 +89,271 @@ impl InstanceManager {
             .collect()
     }
 
-    /// Register a new running instance
+    /// Copies of every running instance
+    pub fn snapshot(&self) -> Vec<GameInstance> {
+        let instances = self.instances.read().unwrap();
+        instances.values().cloned().collect()
+    }
+
+    /// Register a new running instance and persist its record
     pub async fn register_instance(&self, instance: GameInstance) {
-        let mut instances = self.instances.write().unwrap();
-        instances.insert(instance.pid, instance);
+        self.instances.write().unwrap().insert(instance.pid, instance.clone());
+
+        let _ = tokio::task::spawn_blocking(move || registry::save(&instance)).await;
     }
 
-    /// Unregister an instance by PID
+    /// Unregister an instance by PID and drop its record
     pub async fn unregister_instance(&self, pid: u32) {
-        let mut instances = self.instances.write().unwrap();
-        instances.remove(&pid);
+        self.instances.write().unwrap().remove(&pid);
+
+        let _ = tokio::task::spawn_blocking(move || registry::remove(pid)).await;
+        self.unregistered.notify_waiters();
+    }
+
+    /// Marks `pid` as stopped on purpose, so its exit isn't counted as a crash
+    pub fn request_stop(&self, pid: u32) {
+        self.stop_requested.lock().unwrap().insert(pid);
+    }
+
+    /// Appends the session of `pid`, which just exited, to its instance's
+    /// history. An `abnormal` exit counts as a crash unless a stop was
+    /// requested; the instance is returned when it does. Call before
+    /// unregistering.
+    pub async fn record_exit(&self, pid: u32, exit_code: Option<i32>, abnormal: bool) -> Option<GameInstance> {
+        let stop_requested = self.stop_requested.lock().unwrap().remove(&pid);
+        let instance = self.instances.read().unwrap().get(&pid).cloned()?;
+        let crashed = abnormal && !stop_requested;
+        history::record_session(&instance, exit_code, crashed).await;
+        crashed.then_some(instance)
+    }
+
+    /// Re-register instances from a previous launcher run that are still running
+    ///
+    /// Records whose process is gone (or whose PID now belongs to another
+    /// process) are deleted. Returns the PIDs reattached by this call.
+    pub async fn reattach(&self) -> Vec<u32> {
+        let records = tokio::task::spawn_blocking(|| {
+            registry::load()
+                .into_iter()
+                .filter(|record| {
+                    let alive = registry::is_alive(record);
+                    if !alive {
+                        registry::remove(record.instance.pid);
+                    }
+                    alive
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        let mut reattached = Vec::new();
+        for record in records {
+            let pid = record.instance.pid;
+            {
+                let mut instances = self.instances.write().unwrap();
+                if instances.contains_key(&pid) {
+                    continue;
+                }
+                instances.insert(pid, record.instance.clone());
+            }
+
+            lighty_core::trace_info!(pid = pid, instance = %record.instance.instance_name, "Reattached to running instance");
+            tokio::spawn(watch_reattached(record));
+            reattached.push(pid);
+        }
+        reattached
     }
 
     /// Close an instance by PID
     ///
-    /// Kills the process using the system's kill mechanism.
-    /// The instance will be unregistered automatically by the console handler.
-    pub async fn close_instance(&self, pid: u32) -> InstanceResult<()> {
-        let mut instances = self.instances.write().unwrap();
-        instances
-            .remove(&pid)
+    /// Escalates until the process is gone:
+    /// 1. asks the process group to stop (SIGTERM on Unix, `taskkill /T` on
+    ///    Windows) so the JVM runs its shutdown hooks and saves the world;
+    /// 2. after `grace_period`, force-kills the group (SIGKILL, `taskkill /T /F`).
+    ///
+    /// The instance stays registered until the console handler has seen the
+    /// process exit, so this returns once it is really gone.
+    pub async fn close_instance(&self, pid: u32, grace_period: Duration) -> InstanceResult<()> {
+        let _instance_name = self
+            .instances
+            .read()
+            .unwrap()
+            .get(&pid)
+            .map(|inst| inst.instance_name.clone())
             .ok_or(InstanceError::NotFound { pid })?;
+        self.request_stop(pid);
+
+        #[cfg(feature = "events")]
+        emit_shutdown_stage(pid, &_instance_name, ShutdownStage::Terminate, grace_period);
+        match terminate_process_group(pid) {
+            Ok(()) => lighty_core::trace_info!(pid = pid, "Instance asked to stop"),
+            Err(_e) => lighty_core::trace_warn!(pid = pid, error = %_e, "Failed to stop process"),
+        }
+        if self.wait_for_exit(pid, grace_period).await {
+            return Ok(());
+        }
 
-        drop(instances); // Release the lock
-
-        // Platform-specific kill:
-        // - Windows: shell out to `taskkill /F`, which terminates the process
-        //   tree without raising a console close event.
-        // - Unix: send SIGTERM so the JVM runs its shutdown hooks. SIGKILL is
-        //   deliberately avoided so the world/save state has a chance to flush.
-        #[cfg(target_os = "windows")]
-        {
-            use std::process::Command;
-            let output = Command::new("taskkill")
-                .args(&["/PID", &pid.to_string(), "/F"])
-                .output()?;
-
-            if !output.status.success() {
-                lighty_core::trace_warn!(pid = pid, "Failed to kill process");
-            } else {
-                lighty_core::trace_info!(pid = pid, "Instance killed");
+        #[cfg(feature = "events")]
+        emit_shutdown_stage(pid, &_instance_name, ShutdownStage::Kill, Duration::ZERO);
+        if let Err(e) = kill_process_group(pid) {
+            lighty_core::trace_warn!(pid = pid, error = %e, "Failed to kill process");
+            // Gone in between?
+            if self.wait_for_exit(pid, Duration::ZERO).await {
+                return Ok(());
             }
+            return Err(InstanceError::Io(e));
         }
+        lighty_core::trace_info!(pid = pid, "Instance killed");
 
-        #[cfg(not(target_os = "windows"))]
-        {
-            use nix::sys::signal::{kill, Signal};
-            use nix::unistd::Pid;
+        if self.wait_for_exit(pid, KILL_TIMEOUT).await {
+            Ok(())
+        } else {
+            Err(InstanceError::Io(std::io::Error::other(format!(
+                "process {} still running after being killed",
+                pid
+            ))))
+        }
+    }
 
-            match kill(Pid::from_raw(pid as i32), Signal::SIGTERM) {
-                Ok(_) => {
-                    lighty_core::trace_info!(pid = pid, "Instance killed");
-                }
-                Err(e) => {
-                    lighty_core::trace_warn!(pid = pid, error = %e, "Failed to kill process");
-                    return Err(InstanceError::Io(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Failed to kill process: {}", e),
-                    )));
-                }
+    /// Waits up to `timeout` for `pid` to be unregistered; `true` once it is.
+    async fn wait_for_exit(&self, pid: u32, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Arm the notification before checking, so an unregister in
+            // between isn't missed
+            let notified = self.unregistered.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if !self.instances.read().unwrap().contains_key(&pid) {
+                return true;
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return !self.instances.read().unwrap().contains_key(&pid);
             }
         }
+    }
+}
 
-        Ok(())
+/// Time given to the process to exit after a force kill.
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often a reattached instance is checked for exit.
+const REATTACHED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Re-register instances launched before a launcher restart
+///
+/// Call once after [`AppState`](lighty_core::AppState) is initialised. Every
+/// instance whose process is still running is registered again, so
+/// `get_pid()`, `close_instance()` and `delete_instance()` see it. Returns
+/// the reattached PIDs.
+///
+/// Reattached instances have no console output or [`GameHandle`](super::GameHandle):
+/// their pipes died with the previous launcher.
+pub async fn reattach_instances() -> Vec<u32> {
+    INSTANCE_MANAGER.reattach().await
+}
+
+/// Stands in for the console handler of a reattached instance: exit is
+/// detected by polling, then the instance is unregistered.
+async fn watch_reattached(record: InstanceRecord) {
+    loop {
+        tokio::time::sleep(REATTACHED_POLL_INTERVAL).await;
+        let probe = record.clone();
+        let alive = tokio::task::spawn_blocking(move || registry::is_alive(&probe))
+            .await
+            .unwrap_or(false);
+        if !alive {
+            break;
+        }
+    }
+
+    let pid = record.instance.pid;
+
+    // The exit code went to the previous launcher
+    #[cfg(feature = "events")]
+    {
+        use lighty_event::{Event, InstanceExitedEvent, EVENT_BUS};
+
+        EVENT_BUS.emit(Event::InstanceExited(InstanceExitedEvent {
+            pid,
+            instance_name: record.instance.instance_name.clone(),
+            exit_code: None,
+            timestamp: SystemTime::now(),
+        }));
+    }
+
+    lighty_core::trace_info!(pid = pid, instance = %record.instance.instance_name, "Reattached instance exited");
+    INSTANCE_MANAGER.record_exit(pid, None, false).await;
+    INSTANCE_MANAGER.unregister_instance(pid).await;
+}
+
+#[cfg(feature = "events")]
+fn emit_shutdown_stage(pid: u32, instance_name: &str, stage: ShutdownStage, grace_period: Duration) {
+    use lighty_event::{Event, InstanceShutdownEvent, EVENT_BUS};
+
+    EVENT_BUS.emit(Event::InstanceShutdown(InstanceShutdownEvent {
+        pid,
+        instance_name: instance_name.to_string(),
+        stage,
+        grace_period,
+        timestamp: SystemTime::now(),
+    }));
+}
+
+/// Asks the process group led by `pid` to exit.
+#[cfg(not(target_os = "windows"))]
+fn terminate_process_group(pid: u32) -> std::io::Result<()> {
+    signal_process_group(pid, nix::sys::signal::Signal::SIGTERM)
+}
+
+/// Kills the process group led by `pid`.
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn kill_process_group(pid: u32) -> std::io::Result<()> {
+    signal_process_group(pid, nix::sys::signal::Signal::SIGKILL)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn signal_process_group(pid: u32, signal: nix::sys::signal::Signal) -> std::io::Result<()> {
+    use nix::sys::signal::{kill, killpg};
+    use nix::unistd::Pid;
+
+    let pid = Pid::from_raw(pid as i32);
+    // Not a group leader (e.g. spawned by an older version): signal the process alone
+    killpg(pid, signal).or_else(|_| kill(pid, signal))?;
+    Ok(())
+}
+
+/// Asks the process tree rooted at `pid` to close.
+///
+/// `taskkill` without `/F` posts a close message instead of terminating,
+/// so the game gets to save.
+#[cfg(target_os = "windows")]
+fn terminate_process_group(pid: u32) -> std::io::Result<()> {
+    taskkill(pid, false)
+}
+
+/// Terminates the process tree rooted at `pid`.
+#[cfg(target_os = "windows")]
+pub(crate) fn kill_process_group(pid: u32) -> std::io::Result<()> {
+    taskkill(pid, true)
+}
+
+#[cfg(target_os = "windows")]
+fn taskkill(pid: u32, force: bool) -> std::io::Result<()> {
+    use std::process::Command;
+
+    let pid = pid.to_string();
+    let mut args = vec!["/PID", pid.as_str(), "/T"];
+    if force {
+        args.push("/F");
+    }
+    let output = Command::new("taskkill").args(&args).output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
     }
+    Ok(())
 }
@@ -1,10 +1,22 @@
 pub(crate) mod manager;
 pub(crate) mod console;
+pub mod crash;
+pub(crate) mod registry;
+pub mod handle;
+pub mod history;
+pub mod log4j;
+pub mod ready;
 pub mod utilities;
 pub mod errors;
 
 pub(crate) use manager::INSTANCE_MANAGER;
+pub use manager::{reattach_instances, DEFAULT_GRACE_PERIOD};
 pub(crate) use console::handle_console_streams;
 
+pub use handle::{ConsoleLine, ConsoleLines, ConsoleSource, GameHandle, LogRecords};
+pub use crash::{CrashKind, CrashReport};
+pub use log4j::{LogLevel, LogRecord};
+pub use ready::{Readiness, DEFAULT_READY_TIMEOUT};
+pub use history::{InstanceStats, LaunchRecord};
 pub use utilities::InstanceControl;
 pub use errors::{InstanceError, InstanceResult};
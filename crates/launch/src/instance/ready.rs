@@ -0,0 +1,262 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Portable "game ready" detection from console output.
+//!
+//! The game prints well-known lines while starting: the LWJGL backend
+//! once its window exists, then "Sound engine started" and the texture
+//! atlases right before the title screen shows. [`ReadyDetector`] matches
+//! them, with extra markers per loader, on every OS. The result is read
+//! through [`GameHandle::wait_ready`](super::GameHandle::wait_ready) and
+//! the `InstanceReady` event; [`Readiness::TimedOut`] is reported when no
+//! marker shows up in time.
+
+use std::time::{Duration, Instant};
+
+use lighty_loaders::types::Loader;
+
+/// Time a game gets to print a ready marker before [`Readiness::TimedOut`].
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Parts that must all appear in one line.
+type Marker = &'static [&'static str];
+
+/// Printed when the game window is created (1.13+, then 1.12 and older).
+const WINDOW_MARKERS: &[Marker] = &[&["Backend library: LWJGL"], &["LWJGL Version: "]];
+/// Forge/NeoForge early loading window.
+const FORGE_WINDOW_MARKERS: &[Marker] = &[&["ImmediateWindowProvider"]];
+
+/// Printed once resources are loaded, right before the title screen.
+const READY_MARKERS: &[Marker] = &[&["Sound engine started"], &["Created:", "-atlas"]];
+/// Legacy Forge (1.12 and older) once every mod is loaded.
+const FORGE_READY_MARKERS: &[Marker] = &[&["Forge Mod Loader has successfully loaded"]];
+
+/// Startup progress of a game process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Readiness {
+    /// No marker printed yet
+    Starting,
+    /// The game window was created; resources are still loading
+    WindowCreated,
+    /// A ready marker was printed
+    Ready {
+        /// Line that matched
+        marker: String,
+        /// Time since launch
+        after: Duration,
+    },
+    /// No ready marker within the timeout; the game may still be loading
+    /// and can turn [`Self::Ready`] later
+    TimedOut,
+    /// The process exited before getting ready
+    Exited,
+}
+
+impl Readiness {
+    /// Whether waiting is over: ready, timed out or exited
+    pub fn is_settled(&self) -> bool {
+        !matches!(self, Self::Starting | Self::WindowCreated)
+    }
+}
+
+/// Readiness settings of a [`LaunchBuilder`](crate::launch::LaunchBuilder).
+#[derive(Debug, Clone)]
+pub(crate) struct ReadyConfig {
+    pub(crate) timeout: Duration,
+    /// Extra ready markers, on top of the loader's
+    pub(crate) markers: Vec<String>,
+}
+
+impl Default for ReadyConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_READY_TIMEOUT,
+            markers: Vec::new(),
+        }
+    }
+}
+
+/// Matches console lines against the markers of one launch.
+pub(crate) struct ReadyDetector {
+    window: Vec<Vec<String>>,
+    ready: Vec<Vec<String>>,
+    pub(crate) timeout: Duration,
+    started: Instant,
+}
+
+impl ReadyDetector {
+    /// Markers of `loader`, plus those of `config`. Call right before
+    /// spawning the game: `Readiness::Ready::after` counts from here.
+    pub(crate) fn new(loader: &Loader, config: &ReadyConfig) -> Self {
+        // Server-driven modpacks can run any loader
+        let forge_like = matches!(loader, Loader::Forge | Loader::NeoForge | Loader::LightyUpdater);
+        let legacy_forge = matches!(loader, Loader::Forge | Loader::LightyUpdater);
+
+        let mut window = owned(WINDOW_MARKERS);
+        if forge_like {
+            window.extend(owned(FORGE_WINDOW_MARKERS));
+        }
+        let mut ready = owned(READY_MARKERS);
+        if legacy_forge {
+            ready.extend(owned(FORGE_READY_MARKERS));
+        }
+        ready.extend(config.markers.iter().map(|marker| vec![marker.clone()]));
+
+        Self {
+            window,
+            ready,
+            timeout: config.timeout,
+            started: Instant::now(),
+        }
+    }
+
+    /// State `line` moves `current` to, if any.
+    pub(crate) fn check(&self, line: &str, current: &Readiness) -> Option<Readiness> {
+        if matches!(current, Readiness::Ready { .. } | Readiness::Exited) {
+            return None;
+        }
+
+        if self.ready.iter().any(|marker| matches(marker, line)) {
+            return Some(Readiness::Ready {
+                marker: line.trim().to_string(),
+                after: self.started.elapsed(),
+            });
+        }
+        if *current == Readiness::Starting && self.window.iter().any(|marker| matches(marker, line)) {
+            return Some(Readiness::WindowCreated);
+        }
+        None
+    }
+}
+
+fn owned(markers: &[Marker]) -> Vec<Vec<String>> {
+    markers
+        .iter()
+        .map(|marker| marker.iter().map(|part| part.to_string()).collect())
+        .collect()
+}
+
+fn matches(marker: &[String], line: &str) -> bool {
+    marker.iter().all(|part| line.contains(part.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(loader: Loader) -> ReadyDetector {
+        ReadyDetector::new(&loader, &ReadyConfig::default())
+    }
+
+    /// Feeds `lines` from [`Readiness::Starting`], returning every state reached.
+    fn states(detector: &ReadyDetector, lines: &[&str]) -> Vec<Readiness> {
+        let mut current = Readiness::Starting;
+        let mut states = Vec::new();
+        for line in lines {
+            if let Some(next) = detector.check(line, &current) {
+                states.push(next.clone());
+                current = next;
+            }
+        }
+        states
+    }
+
+    fn ready_marker(state: &Readiness) -> &str {
+        match state {
+            Readiness::Ready { marker, .. } => marker,
+            other => panic!("not ready: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn modern_vanilla_startup() {
+        let lines = [
+            "[18:02:11] [main/INFO]: Setting user: Steve",
+            "[18:02:12] [Render thread/INFO]: Backend library: LWJGL version 3.3.3-snapshot",
+            "[18:02:13] [Render thread/INFO]: Reloading ResourceManager: vanilla",
+            "[18:02:15] [Render thread/INFO]: Sound engine started",
+            "[18:02:15] [Render thread/INFO]: Created: 1024x512x4 minecraft:textures/atlas/blocks.png-atlas",
+        ];
+
+        let states = states(&detector(Loader::Vanilla), &lines);
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[0], Readiness::WindowCreated);
+        assert_eq!(ready_marker(&states[1]), "[18:02:15] [Render thread/INFO]: Sound engine started");
+    }
+
+    #[test]
+    fn texture_atlas_is_a_ready_marker() {
+        let detector = detector(Loader::Fabric);
+        let line = "[18:02:15] [Render thread/INFO]: Created: 512x256x0 minecraft:textures/atlas/particles.png-atlas";
+        assert!(matches!(detector.check(line, &Readiness::WindowCreated), Some(Readiness::Ready { .. })));
+        // Both parts are needed
+        assert_eq!(detector.check("[18:02:14] [main/INFO]: Created: 12 recipes", &Readiness::WindowCreated), None);
+    }
+
+    #[test]
+    fn legacy_vanilla_startup() {
+        let lines = [
+            "[18:02:12] [Client thread/INFO]: LWJGL Version: 2.9.4",
+            "[18:02:14] [Sound Library Loader/INFO]: Sound engine started",
+        ];
+        let states = states(&detector(Loader::Vanilla), &lines);
+        assert_eq!(states[0], Readiness::WindowCreated);
+        assert_eq!(ready_marker(&states[1]), lines[1]);
+    }
+
+    #[test]
+    fn log4j_xml_lines_match() {
+        let line = "  <log4j:Message><![CDATA[Sound engine started]]></log4j:Message>";
+        let state = detector(Loader::Vanilla).check(line, &Readiness::Starting).unwrap();
+        assert_eq!(ready_marker(&state), "<log4j:Message><![CDATA[Sound engine started]]></log4j:Message>");
+    }
+
+    #[test]
+    fn forge_markers_depend_on_the_loader() {
+        let early_window =
+            "[18:02:10] [main/INFO] [ne.ne.fm.lo.ImmediateWindowHandler/]: Loading ImmediateWindowProvider fmlearlywindow";
+        assert_eq!(detector(Loader::NeoForge).check(early_window, &Readiness::Starting), Some(Readiness::WindowCreated));
+        assert_eq!(detector(Loader::Vanilla).check(early_window, &Readiness::Starting), None);
+
+        let fml_loaded = "[18:02:40] [Client thread/INFO] [FML]: Forge Mod Loader has successfully loaded 42 mods";
+        assert!(detector(Loader::Forge).check(fml_loaded, &Readiness::WindowCreated).is_some());
+        assert!(detector(Loader::LightyUpdater).check(fml_loaded, &Readiness::WindowCreated).is_some());
+        assert_eq!(detector(Loader::NeoForge).check(fml_loaded, &Readiness::WindowCreated), None);
+    }
+
+    #[test]
+    fn custom_markers_are_ready_markers() {
+        let config = ReadyConfig {
+            markers: vec!["[MyModpack] Loaded".to_string()],
+            ..ReadyConfig::default()
+        };
+        let detector = ReadyDetector::new(&Loader::Fabric, &config);
+        let state = detector.check("[18:02:20] [Render thread/INFO]: [MyModpack] Loaded 230 mods", &Readiness::Starting);
+        assert!(matches!(state, Some(Readiness::Ready { .. })));
+    }
+
+    #[test]
+    fn settled_states_stay_put() {
+        let detector = detector(Loader::Vanilla);
+        let window = "[18:02:12] [Render thread/INFO]: Backend library: LWJGL version 3.3.3";
+        let ready = "[18:02:15] [Render thread/INFO]: Sound engine started";
+
+        assert_eq!(detector.check(window, &Readiness::WindowCreated), None);
+        assert_eq!(detector.check(ready, &Readiness::Exited), None);
+        let ready_state = detector.check(ready, &Readiness::Starting).unwrap();
+        assert_eq!(detector.check(ready, &ready_state), None);
+
+        // A slow game can still turn ready after the timeout
+        assert!(matches!(detector.check(ready, &Readiness::TimedOut), Some(Readiness::Ready { .. })));
+        assert_eq!(detector.check(window, &Readiness::TimedOut), None);
+    }
+
+    #[test]
+    fn only_final_states_are_settled() {
+        assert!(!Readiness::Starting.is_settled());
+        assert!(!Readiness::WindowCreated.is_settled());
+        assert!(Readiness::TimedOut.is_settled());
+        assert!(Readiness::Exited.is_settled());
+        assert!(Readiness::Ready { marker: String::new(), after: Duration::ZERO }.is_settled());
+    }
+}
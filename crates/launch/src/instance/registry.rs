@@ -0,0 +1,152 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! On-disk records of running instances.
+//!
+//! Each registered instance is mirrored to `<data_dir>/.running/<pid>.json`
+//! and the file is removed when it unregisters. After a launcher restart,
+//! [`InstanceManager::reattach`](super::manager::InstanceManager::reattach)
+//! reads the records back and keeps those whose process is still the game:
+//! the OS-reported process start time has to match the recorded one, so a
+//! PID reused by an unrelated process is never mistaken for the game.
+
+use std::path::PathBuf;
+
+use lighty_core::persist::write_atomic_sync;
+use lighty_core::AppState;
+use serde::{Deserialize, Serialize};
+
+use super::manager::GameInstance;
+
+/// A persisted running instance.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct InstanceRecord {
+    /// [`process_start_marker`] of the process when it was registered
+    pub process_start: String,
+    #[serde(flatten)]
+    pub instance: GameInstance,
+}
+
+/// `None` when [`AppState`] hasn't been initialised: instances are then
+/// tracked in memory only.
+fn records_dir() -> Option<PathBuf> {
+    let paths = AppState::try_paths().ok()?;
+    Some(paths.data_dir.join(".running"))
+}
+
+/// Persists `instance`. Blocking; best-effort, a missing record only costs
+/// the reattach after a restart.
+pub(crate) fn save(instance: &GameInstance) {
+    let (Some(dir), Some(process_start)) = (records_dir(), process_start_marker(instance.pid)) else {
+        return;
+    };
+    let record = InstanceRecord {
+        process_start,
+        instance: instance.clone(),
+    };
+
+    let path = dir.join(format!("{}.json", instance.pid));
+    let result = (|| {
+        let bytes = serde_json::to_vec(&record)?;
+        std::fs::create_dir_all(&dir)?;
+        write_atomic_sync(&path, &bytes)?;
+        Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    })();
+
+    if let Err(_e) = result {
+        lighty_core::trace_warn!(pid = instance.pid, error = %_e, "Failed to persist instance record");
+    }
+}
+
+/// Removes the record of `pid`. Blocking.
+pub(crate) fn remove(pid: u32) {
+    if let Some(dir) = records_dir() {
+        let _ = std::fs::remove_file(dir.join(format!("{}.json", pid)));
+    }
+}
+
+/// Every readable record. Blocking; unreadable files are deleted.
+pub(crate) fn load() -> Vec<InstanceRecord> {
+    let Some(dir) = records_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut records = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        match std::fs::read(&path).ok().and_then(|bytes| serde_json::from_slice(&bytes).ok()) {
+            Some(record) => records.push(record),
+            None => {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+    records
+}
+
+/// Whether `record` still describes a live process.
+pub(crate) fn is_alive(record: &InstanceRecord) -> bool {
+    process_start_marker(record.instance.pid).is_some_and(|marker| marker == record.process_start)
+}
+
+/// Opaque token identifying the process currently holding `pid`, derived
+/// from its start time. `None` when no live process has that PID.
+#[cfg(target_os = "linux")]
+pub(crate) fn process_start_marker(pid: u32) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // `comm` (field 2) may contain spaces; the fields after it don't
+    let fields: Vec<&str> = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().collect();
+    // fields[0] is the state (field 3), fields[19] the start time in ticks since boot (field 22)
+    if fields.first() == Some(&"Z") {
+        return None;
+    }
+    fields.get(19).map(|ticks| ticks.to_string())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(crate) fn process_start_marker(pid: u32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let lstart = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !lstart.is_empty()).then_some(lstart)
+}
+
+#[cfg(windows)]
+pub(crate) fn process_start_marker(pid: u32) -> Option<String> {
+    use windows::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // Exit code reported while a process is running
+    const STILL_ACTIVE: u32 = 259;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        let mut exit_code = 0u32;
+        let (mut created, mut exited, mut kernel, mut user) =
+            (FILETIME::default(), FILETIME::default(), FILETIME::default(), FILETIME::default());
+        let running = GetExitCodeProcess(handle, &mut exit_code).is_ok() && exit_code == STILL_ACTIVE;
+        let times = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user);
+        let _ = CloseHandle(handle);
+
+        if !running || times.is_err() {
+            return None;
+        }
+        Some((((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64).to_string())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn process_start_marker(_pid: u32) -> Option<String> {
+    None
+}
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use lighty_loaders::types::{InstanceSize, VersionInfo};
 use lighty_loaders::types::version_metadata::Version;
 
 use super::errors::{InstanceError, InstanceResult};
+use super::history::{self, InstanceStats, LaunchRecord};
 use super::INSTANCE_MANAGER;
+use crate::launch::{load_quick_play_log, QuickPlayLogEntry};
 
 /// Extension trait providing instance management utilities
 ///
@@ -103,8 +107,10 @@ pub trait InstanceControl: VersionInfo {
 
     /// Close a specific instance by PID
     ///
-    /// Attempts a graceful shutdown with a 5-second timeout.
-    /// If the instance doesn't respond, it will be force-killed.
+    /// Attempts a graceful shutdown with a 5-second timeout
+    /// ([`DEFAULT_GRACE_PERIOD`](super::DEFAULT_GRACE_PERIOD)).
+    /// If the instance doesn't respond, its process group is force-killed.
+    /// Returns once the process has exited and been unregistered.
     ///
     /// # Arguments
     ///
@@ -124,7 +130,78 @@ pub trait InstanceControl: VersionInfo {
     /// }
     /// ```
     async fn close_instance(&self, pid: u32) -> InstanceResult<()> {
-        INSTANCE_MANAGER.close_instance(pid).await
+        INSTANCE_MANAGER.close_instance(pid, super::DEFAULT_GRACE_PERIOD).await
+    }
+
+    /// Close a specific instance by PID with a custom grace period
+    ///
+    /// Same as [`close_instance`](Self::close_instance), but the process
+    /// gets `grace_period` to exit before it is force-killed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// // Modded instances can take a while to save
+    /// instance.close_instance_with_grace(pid, Duration::from_secs(30)).await?;
+    /// ```
+    async fn close_instance_with_grace(&self, pid: u32, grace_period: Duration) -> InstanceResult<()> {
+        INSTANCE_MANAGER.close_instance(pid, grace_period).await
+    }
+
+    /// Finished sessions of this instance, oldest first
+    ///
+    /// Each [`LaunchRecord`] holds the launch time, duration, exit code,
+    /// account and version. The last 500 sessions are kept; a running
+    /// session is added once it exits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for session in instance.launch_history().await.iter().rev().take(5) {
+    ///     println!("{} played {} min", session.username, session.duration.as_secs() / 60);
+    /// }
+    /// ```
+    async fn launch_history(&self) -> Vec<LaunchRecord> {
+        history::load_history(self.game_dirs()).await
+    }
+
+    /// Playtime totals of this instance
+    ///
+    /// Totals cover every session ever recorded, including those dropped
+    /// from [`launch_history`](Self::launch_history). A session counts as a
+    /// crash when the game exits abnormally without being closed through
+    /// the launcher.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let stats = instance.instance_stats().await;
+    /// println!("{} h played", stats.total_playtime.as_secs() / 3600);
+    /// if let Some(last) = stats.last_played {
+    ///     println!("Last played {:?} ago", last.elapsed().unwrap_or_default());
+    /// }
+    /// ```
+    async fn instance_stats(&self) -> InstanceStats {
+        history::load_stats(self.game_dirs()).await
+    }
+
+    /// Worlds, servers and realms joined through Quick Play
+    ///
+    /// Written by the game (1.20+) when launched with
+    /// [`LaunchBuilder::join_server`](crate::launch::LaunchBuilder::join_server),
+    /// [`join_world`](crate::launch::LaunchBuilder::join_world) or
+    /// [`join_realm`](crate::launch::LaunchBuilder::join_realm). Empty
+    /// until the game has written it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// for entry in instance.quick_play_log().await {
+    ///     println!("{:?} {} ({})", entry.kind, entry.name, entry.id);
+    /// }
+    /// ```
+    async fn quick_play_log(&self) -> Vec<QuickPlayLogEntry> {
+        load_quick_play_log(self.game_dirs()).await
     }
 
     /// Delete the instance completely from disk
@@ -134,7 +211,9 @@ pub trait InstanceControl: VersionInfo {
     /// # Safety
     ///
     /// The instance must not be running. If any instances are running,
-    /// this method will return an error without deleting anything.
+    /// this method will return an error without deleting anything. Games
+    /// launched before a launcher restart count too (see
+    /// [`reattach_instances`](super::reattach_instances)).
     ///
     /// # Errors
     ///
@@ -155,7 +234,9 @@ pub trait InstanceControl: VersionInfo {
     /// println!("Instance deleted");
     /// ```
     async fn delete_instance(&self) -> InstanceResult<()> {
-        // Check that no instances are running
+        // Pick up games started before a launcher restart, then check that
+        // no instances are running
+        INSTANCE_MANAGER.reattach().await;
         let running_pids = self.get_pids();
         if !running_pids.is_empty() {
             return Err(InstanceError::StillRunning {
@@ -4,12 +4,21 @@
 //! Launch builder for configuring game arguments and JVM options
 
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::Duration;
 use lighty_auth::UserProfile;
+use lighty_core::cancel::{with_cancellation, CancellationToken};
 use lighty_java::JavaDistribution;
 use crate::errors::InstallerResult;
 use lighty_loaders::types::{VersionInfo, Loader, LoaderExtensions};
-use crate::arguments::Arguments;
+use crate::arguments::{Arguments, ArgumentOptions, LaunchFeature, KEY_RESOLUTION_HEIGHT, KEY_RESOLUTION_WIDTH};
 use crate::installer::Installer;
+use crate::installer::verifier::{Verifier, VerifyReport};
+use crate::instance::GameHandle;
+use crate::instance::ready::ReadyConfig;
+use crate::launch::LaunchPlan;
+use super::plan::ProcessEnvironment;
+use super::quick_play::QuickPlay;
 
 #[cfg(feature = "events")]
 use lighty_event::EventBus;
@@ -21,15 +30,22 @@ pub struct LaunchBuilder<'a, T> {
     pub(crate) version: &'a mut T,
     pub(crate) profile: &'a UserProfile,
     pub(crate) java_distribution: JavaDistribution,
-    pub(crate) jvm_overrides: HashMap<String, String>,
-    pub(crate) jvm_removals: HashSet<String>,
-    pub(crate) arg_overrides: HashMap<String, String>,
-    pub(crate) arg_removals: HashSet<String>,
-    pub(crate) raw_args: Vec<String>,
+    pub(crate) options: LaunchOptions,
+    pub(crate) cancellation: Option<CancellationToken>,
     #[cfg(feature = "events")]
     pub(crate) event_bus: Option<&'a EventBus>,
 }
 
+/// Argument, process and readiness options set on a [`LaunchBuilder`],
+/// passed as a whole to the launch pipeline.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LaunchOptions {
+    pub(crate) arguments: ArgumentOptions,
+    pub(crate) quick_play: Option<QuickPlay>,
+    pub(crate) environment: ProcessEnvironment,
+    pub(crate) ready: ReadyConfig,
+}
+
 impl<'a, T> LaunchBuilder<'a, T>
 where
     T: VersionInfo<LoaderType = Loader> + LoaderExtensions + Arguments + Installer,
@@ -44,16 +60,36 @@ where
             version,
             profile,
             java_distribution,
-            jvm_overrides: HashMap::new(),
-            jvm_removals: HashSet::new(),
-            arg_overrides: HashMap::new(),
-            arg_removals: HashSet::new(),
-            raw_args: Vec::new(),
+            options: LaunchOptions::default(),
+            cancellation: None,
             #[cfg(feature = "events")]
             event_bus: None,
         }
     }
 
+    /// Make the install cancellable and pausable through `token`
+    ///
+    /// Cancelling stops [`Self::run`], [`Self::prepare`] or [`Self::verify`]
+    /// at the next safe point with [`InstallerError::Cancelled`](crate::errors::InstallerError::Cancelled)
+    /// (see [`InstallerError::is_cancelled`](crate::errors::InstallerError::is_cancelled)).
+    /// Pausing holds every download until the token is resumed. Once the
+    /// game is spawned, the token has no effect.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let token = CancellationToken::new();
+    /// let control = token.clone(); // cancel(), pause(), resume() from the UI
+    ///
+    /// version.launch(&profile, JavaDistribution::Zulu)
+    ///     .with_cancellation(token)
+    ///     .run()
+    ///     .await?;
+    /// ```
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
     /// Set an event bus to receive download progress events
     ///
     /// # Example
@@ -83,11 +119,14 @@ where
     ///     .run()
     ///     .await
     /// ```
-    pub fn with_jvm_options(self) -> JvmOptionsBuilder<'a, T> {
+    pub fn with_jvm_options(mut self) -> JvmOptionsBuilder<'a, T> {
+        // Start from what is already set, so `done()` keeps it
+        let overrides = std::mem::take(&mut self.options.arguments.jvm_overrides);
+        let removals = std::mem::take(&mut self.options.arguments.jvm_removals);
         JvmOptionsBuilder {
             parent: self,
-            overrides: HashMap::new(),
-            removals: HashSet::new(),
+            overrides,
+            removals,
         }
     }
 
@@ -97,41 +136,274 @@ where
     /// ```no_run
     /// version.launch(&profile, JavaDistribution::Zulu)
     ///     .with_arguments()
-    ///         .set(KEY_WIDTH, "1920")
-    ///         .set(KEY_HEIGHT, "1080")
+    ///         .set(KEY_LAUNCHER_NAME, "MyLauncher")
+    ///         .set("fullscreen", "")
     ///         .done()
     ///     .run()
     ///     .await
     /// ```
-    pub fn with_arguments(self) -> ArgumentsBuilder<'a, T> {
+    pub fn with_arguments(mut self) -> ArgumentsBuilder<'a, T> {
+        // Start from what is already set (e.g. by `with_resolution`), so
+        // `done()` keeps it
+        let overrides = std::mem::take(&mut self.options.arguments.arg_overrides);
+        let removals = std::mem::take(&mut self.options.arguments.arg_removals);
+        let raw_args = std::mem::take(&mut self.options.arguments.raw_args);
         ArgumentsBuilder {
             parent: self,
-            overrides: HashMap::new(),
-            removals: HashSet::new(),
-            raw_args: Vec::new(),
+            overrides,
+            removals,
+            raw_args,
         }
     }
 
+    /// Enable a launch feature tested by the manifest's argument rules
+    ///
+    /// # Example
+    /// ```ignore
+    /// version.launch(&profile, JavaDistribution::Zulu)
+    ///     .with_feature(LaunchFeature::Demo) // adds `--demo`
+    ///     .run()
+    ///     .await?;
+    /// ```
+    pub fn with_feature(mut self, feature: LaunchFeature) -> Self {
+        self.options.arguments.features.insert(feature);
+        self
+    }
+
+    /// Open the game window at `width` x `height`
+    ///
+    /// Enables [`LaunchFeature::CustomResolution`], which adds
+    /// `--width` / `--height` on versions whose manifest has them.
+    pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
+        let overrides = &mut self.options.arguments.arg_overrides;
+        overrides.insert(KEY_RESOLUTION_WIDTH.into(), width.to_string());
+        overrides.insert(KEY_RESOLUTION_HEIGHT.into(), height.to_string());
+        self.with_feature(LaunchFeature::CustomResolution)
+    }
+
+    /// Join `address` (`"host"` or `"host:port"`) as soon as the game starts
+    ///
+    /// Uses Quick Play (`--quickPlayMultiplayer`) on 1.20+ and
+    /// `--server` / `--port` on older versions.
+    ///
+    /// # Example
+    /// ```ignore
+    /// version.launch(&profile, JavaDistribution::Zulu)
+    ///     .join_server("play.example.net:25565")
+    ///     .run()
+    ///     .await?;
+    /// ```
+    pub fn join_server(mut self, address: impl Into<String>) -> Self {
+        self.options.quick_play = Some(QuickPlay::Server(address.into()));
+        self
+    }
+
+    /// Open the singleplayer world in folder `name` as soon as the game starts
+    ///
+    /// Needs Quick Play (1.20+); older versions start on the title screen.
+    pub fn join_world(mut self, name: impl Into<String>) -> Self {
+        self.options.quick_play = Some(QuickPlay::World(name.into()));
+        self
+    }
+
+    /// Join the realm `id` as soon as the game starts
+    ///
+    /// Needs Quick Play (1.20+); older versions start on the title screen.
+    pub fn join_realm(mut self, id: impl ToString) -> Self {
+        self.options.quick_play = Some(QuickPlay::Realm(id.to_string()));
+        self
+    }
+
+    /// Run Java through a wrapper command
+    ///
+    /// The first item is the program, the rest its arguments; the Java
+    /// binary and its argv follow. Calling this again wraps further out.
+    ///
+    /// # Example
+    /// ```ignore
+    /// version.launch(&profile, JavaDistribution::Zulu)
+    ///     .with_wrapper(["mangohud"])
+    ///     .with_wrapper(["gamemoderun"]) // gamemoderun mangohud java ...
+    ///     .run()
+    ///     .await?;
+    /// ```
+    pub fn with_wrapper<I, S>(mut self, command: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut wrapper: Vec<String> = command.into_iter().map(Into::into).collect();
+        wrapper.append(&mut self.options.environment.wrapper);
+        self.options.environment.wrapper = wrapper;
+        self
+    }
+
+    /// Set an environment variable for the game process
+    ///
+    /// Also keeps a variable from the default [`SCRUBBED_ENV`](crate::launch::SCRUBBED_ENV) removals.
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        self.options.environment.removals.remove(&key);
+        self.options.environment.overrides.insert(key, value.into());
+        self
+    }
+
+    /// Unset an inherited environment variable for the game process
+    ///
+    /// [`SCRUBBED_ENV`](crate::launch::SCRUBBED_ENV) (`JAVA_TOOL_OPTIONS`, `_JAVA_OPTIONS`,
+    /// `JDK_JAVA_OPTIONS`) is always unset unless set with [`Self::with_env`].
+    pub fn remove_env(mut self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        self.options.environment.overrides.remove(&key);
+        self.options.environment.removals.insert(key);
+        self
+    }
+
+    /// Give the game `timeout` to print a "game ready" marker
+    ///
+    /// Past it, [`GameHandle::wait_ready`] returns
+    /// [`Readiness::TimedOut`](crate::instance::Readiness::TimedOut).
+    /// Defaults to [`DEFAULT_READY_TIMEOUT`](crate::instance::DEFAULT_READY_TIMEOUT).
+    pub fn with_ready_timeout(mut self, timeout: Duration) -> Self {
+        self.options.ready.timeout = timeout;
+        self
+    }
+
+    /// Also treat a console line containing `marker` as "game ready"
+    ///
+    /// Useful for modpacks that print their own line once loaded.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let handle = version.launch(&profile, JavaDistribution::Zulu)
+    ///     .with_ready_marker("[MyPack] Loading complete")
+    ///     .with_ready_timeout(Duration::from_secs(300))
+    ///     .run()
+    ///     .await?;
+    /// handle.wait_ready().await; // hide the splash screen
+    /// ```
+    pub fn with_ready_marker(mut self, marker: impl Into<String>) -> Self {
+        self.options.ready.markers.push(marker.into());
+        self
+    }
+
     /// Execute the launch
     ///
+    /// Returns once the game is spawned. The [`GameHandle`] gives access to
+    /// its PID, console output and exit status; dropping it leaves the game
+    /// running.
+    ///
     /// # Example
     /// ```no_run
-    /// version.launch(&profile, JavaDistribution::Zulu).run().await?;
+    /// let handle = version.launch(&profile, JavaDistribution::Zulu).run().await?;
+    /// let status = handle.wait().await?;
     /// ```
-    pub async fn run(self) -> InstallerResult<()> {
-        crate::launch::execute_launch(
+    pub async fn run(self) -> InstallerResult<GameHandle> {
+        let launch = crate::launch::execute_launch(
             self.version,
             self.profile,
             self.java_distribution,
-            &self.jvm_overrides,
-            &self.jvm_removals,
-            &self.arg_overrides,
-            &self.arg_removals,
-            &self.raw_args,
+            &self.options,
             #[cfg(feature = "events")]
             self.event_bus,
-        )
-        .await
+        );
+        cancellable(self.cancellation, launch).await
+    }
+
+    /// Resolve the launch command without starting the game
+    ///
+    /// Runs the same steps as [`Self::prepare`], then builds the Java path,
+    /// argv, working directory, environment and classpath that
+    /// [`Self::run`] would spawn. The plan holds the real access token;
+    /// use [`LaunchPlan::redacted`] before logging or sharing it.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let plan = version.launch(&profile, JavaDistribution::Zulu).plan().await?;
+    /// println!("{}", serde_json::to_string_pretty(&plan.redacted())?);
+    /// ```
+    pub async fn plan(self) -> InstallerResult<LaunchPlan> {
+        let verifier = Verifier::for_instance(&*self.version).await;
+        let plan = async {
+            let prepared = crate::launch::prepare_instance(
+                &mut *self.version,
+                &self.java_distribution,
+                &self.options.arguments.arg_overrides,
+                &verifier,
+                #[cfg(feature = "events")]
+                self.event_bus,
+            )
+            .await?;
+
+            Ok(crate::launch::build_plan(
+                &*self.version,
+                prepared.version(),
+                self.profile,
+                prepared.java_path.clone(),
+                &self.options,
+            ))
+        };
+        cancellable(self.cancellation, plan).await
+    }
+
+    /// Install everything the instance needs without starting it
+    ///
+    /// Runs the same steps as [`Self::run`] (metadata, Java runtime, mods,
+    /// libraries, natives, client, assets and the Forge/NeoForge
+    /// processors) but doesn't spawn the game. A later `run()` finds
+    /// everything in place.
+    ///
+    /// # Example
+    /// ```ignore
+    /// version.launch(&profile, JavaDistribution::Zulu).prepare().await?;
+    /// ```
+    pub async fn prepare(self) -> InstallerResult<()> {
+        let verifier = Verifier::for_instance(&*self.version).await;
+        let prepare = crate::launch::prepare_instance(
+            self.version,
+            &self.java_distribution,
+            &self.options.arguments.arg_overrides,
+            &verifier,
+            #[cfg(feature = "events")]
+            self.event_bus,
+        );
+        cancellable(self.cancellation, prepare).await?;
+        Ok(())
+    }
+
+    /// Re-verify every installed file and repair the instance
+    ///
+    /// Like [`Self::prepare`], but every file is hashed again, ignoring the
+    /// verify index. Missing and corrupted files are downloaded again (or
+    /// linked from the shared store) and listed in the returned report.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let report = version.launch(&profile, JavaDistribution::Zulu).verify().await?;
+    /// for path in &report.corrupted {
+    ///     println!("Replaced corrupted file {}", path.display());
+    /// }
+    /// ```
+    pub async fn verify(self) -> InstallerResult<VerifyReport> {
+        let verifier = Verifier::for_repair(&*self.version).await;
+        let prepare = crate::launch::prepare_instance(
+            self.version,
+            &self.java_distribution,
+            &self.options.arguments.arg_overrides,
+            &verifier,
+            #[cfg(feature = "events")]
+            self.event_bus,
+        );
+        cancellable(self.cancellation, prepare).await?;
+        Ok(verifier.report())
+    }
+}
+
+/// Runs `fut` under `token`, if one was given.
+async fn cancellable<F: Future>(token: Option<CancellationToken>, fut: F) -> F::Output {
+    match token {
+        Some(token) => with_cancellation(token, fut).await,
+        None => fut.await,
     }
 }
 
@@ -183,8 +455,8 @@ where
     /// Finish configuring JVM options and return to the launch builder
     pub fn done(self) -> LaunchBuilder<'a, T> {
         let mut parent = self.parent;
-        parent.jvm_overrides = self.overrides;
-        parent.jvm_removals = self.removals;
+        parent.options.arguments.jvm_overrides = self.overrides;
+        parent.options.arguments.jvm_removals = self.removals;
         parent
     }
 }
@@ -234,6 +506,8 @@ where
             "game_directory", "assets_root", "natives_directory", "library_directory",
             "assets_index_name", "launcher_name", "launcher_version",
             "classpath", "classpath_separator",
+            "resolution_width", "resolution_height",
+            "quickPlayPath", "quickPlaySingleplayer", "quickPlayMultiplayer", "quickPlayRealms",
         ];
 
         // Known placeholders are recorded as substitutions
@@ -271,9 +545,9 @@ where
     /// Finish configuring arguments and return to the launch builder
     pub fn done(self) -> LaunchBuilder<'a, T> {
         let mut parent = self.parent;
-        parent.arg_overrides = self.overrides;
-        parent.arg_removals = self.removals;
-        parent.raw_args = self.raw_args;
+        parent.options.arguments.arg_overrides = self.overrides;
+        parent.options.arguments.arg_removals = self.removals;
+        parent.options.arguments.raw_args = self.raw_args;
         parent
     }
 }
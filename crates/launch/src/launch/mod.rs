@@ -4,9 +4,16 @@
 mod runner;
 mod builder;
 mod config;
+mod plan;
+mod quick_play;
+mod script;
 #[cfg(feature = "events")]
 mod window;
 
 pub use runner::*;
 pub use builder::LaunchBuilder;
 pub use config::LaunchConfig;
+pub use plan::{LaunchPlan, REDACTED, SCRUBBED_ENV};
+pub use quick_play::{QuickPlay, QuickPlayKind, QuickPlayLogEntry};
+pub(crate) use quick_play::load_log as load_quick_play_log;
+pub use script::{ScriptKind, ScriptToken, ACCESS_TOKEN_VAR};
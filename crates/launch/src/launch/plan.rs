@@ -0,0 +1,217 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Resolved launch command, as returned by
+//! [`LaunchBuilder::plan`](super::LaunchBuilder::plan).
+//!
+//! The launch pipeline builds the same [`LaunchPlan`] right before
+//! spawning the game, so a plan is exactly what `run()` would execute.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+use lighty_auth::UserProfile;
+use lighty_java::runtime::ProcessOptions;
+use lighty_loaders::types::version_metadata::Version;
+use lighty_loaders::types::VersionInfo;
+use serde::{Deserialize, Serialize};
+
+use super::builder::LaunchOptions;
+use crate::arguments::{Arguments, KEY_AUTH_ACCESS_TOKEN, KEY_AUTH_XUID, KEY_CLIENT_ID, KEY_GAME_DIRECTORY};
+
+/// Replacement for secrets in a [redacted](LaunchPlan::redacted) plan.
+pub const REDACTED: &str = "<redacted>";
+
+/// Variables unset by default: each one injects JVM options behind the
+/// launcher's back. Set one with
+/// [`LaunchBuilder::with_env`](super::LaunchBuilder::with_env) to keep it.
+pub const SCRUBBED_ENV: &[&str] = &["JAVA_TOOL_OPTIONS", "_JAVA_OPTIONS", "JDK_JAVA_OPTIONS"];
+
+/// Game arguments whose value is a credential.
+const SECRET_FLAGS: &[&str] = &["--accessToken", "--session", "--xuid", "--clientId"];
+
+/// Everything needed to start the game: binary, argv, working directory
+/// and environment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchPlan {
+    /// Command the Java binary is run through, program first
+    #[serde(default)]
+    pub wrapper: Vec<String>,
+    /// Java binary
+    pub java_path: PathBuf,
+    /// Full argv after the Java binary: JVM arguments, main class, game
+    /// arguments and raw arguments, with overrides and removals applied
+    pub arguments: Vec<String>,
+    /// Working directory of the game process
+    pub working_dir: PathBuf,
+    /// Variables set on top of the launcher's own environment
+    pub env: BTreeMap<String, String>,
+    /// Inherited variables unset before spawning
+    #[serde(default)]
+    pub env_remove: BTreeSet<String>,
+    /// Main class
+    pub main_class: String,
+    /// Classpath entries, in order
+    pub classpath: Vec<PathBuf>,
+    /// Credential values to scrub in [`Self::redacted`]
+    #[serde(skip)]
+    secrets: Vec<String>,
+    /// Access token as passed in the argv, for [`Self::to_script`]
+    #[serde(skip)]
+    pub(super) access_token: Option<String>,
+}
+
+impl LaunchPlan {
+    /// Copy with the access token, XUID and client ID replaced by
+    /// [`REDACTED`], safe to log or attach to a bug report.
+    pub fn redacted(&self) -> Self {
+        let mut plan = self.clone();
+
+        let mut redact_next = false;
+        for arg in &mut plan.arguments {
+            if redact_next || self.secrets.iter().any(|secret| arg.contains(secret.as_str())) {
+                *arg = REDACTED.to_string();
+            }
+            redact_next = SECRET_FLAGS.contains(&arg.as_str());
+        }
+        for value in plan.env.values_mut() {
+            if self.secrets.iter().any(|secret| value.contains(secret.as_str())) {
+                *value = REDACTED.to_string();
+            }
+        }
+
+        plan.secrets.clear();
+        plan.access_token = plan.access_token.map(|_| REDACTED.to_string());
+        plan
+    }
+
+    /// Command line as a single string, for display, wrapper included.
+    /// Arguments containing whitespace are double-quoted.
+    pub fn command_line(&self) -> String {
+        self.wrapper
+            .iter()
+            .cloned()
+            .chain(std::iter::once(self.java_path.display().to_string()))
+            .chain(self.arguments.iter().cloned())
+            .map(|arg| {
+                if arg.contains(char::is_whitespace) {
+                    format!("\"{}\"", arg)
+                } else {
+                    arg
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Builds the plan `execute_game` spawns, `options` environment included.
+pub(crate) fn build_plan<T>(
+    builder: &T,
+    version: &Version,
+    profile: &UserProfile,
+    java_path: PathBuf,
+    options: &LaunchOptions,
+) -> LaunchPlan
+where
+    T: VersionInfo + Arguments,
+{
+    // Quick Play becomes features and placeholders, or legacy raw args
+    let mut resolved = options.arguments.clone();
+    if let Some(quick_play) = &options.quick_play {
+        quick_play.apply(version, builder.game_dirs(), &mut resolved);
+    }
+    let arg_overrides = &resolved.arg_overrides;
+
+    // Build the full argv (JVM args + main class + game args)
+    let arguments = builder.build_arguments(version, Some(profile), &resolved);
+
+    // Determine the effective runtime directory.
+    // If an explicit `game_directory` override exists, launch from it.
+    let working_dir = match arg_overrides.get(KEY_GAME_DIRECTORY) {
+        Some(dir) => PathBuf::from(dir),
+        None => builder.game_dirs().join("runtime"),
+    };
+
+    let classpath = arguments
+        .iter()
+        .position(|arg| arg == "-cp")
+        .and_then(|idx| arguments.get(idx + 1))
+        .map(|cp| std::env::split_paths(cp).collect())
+        .unwrap_or_default();
+
+    // Overrides win over the profile, as in the argv
+    let access_token = arg_overrides
+        .get(KEY_AUTH_ACCESS_TOKEN)
+        .cloned()
+        .or_else(|| profile.access_token.clone());
+    let secrets = [
+        (KEY_AUTH_ACCESS_TOKEN, profile.access_token.clone()),
+        (KEY_AUTH_XUID, profile.xuid.clone()),
+        (KEY_CLIENT_ID, Some(lighty_core::AppState::client_id().to_string())),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| arg_overrides.get(key).cloned().or(value))
+    .filter(|value| looks_like_secret(value))
+    .collect();
+
+    let mut plan = LaunchPlan {
+        java_path,
+        arguments,
+        working_dir,
+        env: BTreeMap::new(),
+        env_remove: BTreeSet::new(),
+        wrapper: Vec::new(),
+        main_class: version.main_class.main_class.clone(),
+        classpath,
+        secrets,
+        access_token,
+    };
+    plan.apply_environment(&options.environment);
+    plan
+}
+
+/// Shortest value treated as a credential.
+const MIN_SECRET_LEN: usize = 8;
+
+/// Whether `value` can be a real credential: shorter values are
+/// placeholders such as the offline token `"0"`.
+pub(super) fn looks_like_secret(value: &str) -> bool {
+    value.len() >= MIN_SECRET_LEN
+}
+
+/// Wrapper and environment changes set on a
+/// [`LaunchBuilder`](super::LaunchBuilder).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProcessEnvironment {
+    pub(crate) wrapper: Vec<String>,
+    pub(crate) overrides: HashMap<String, String>,
+    pub(crate) removals: HashSet<String>,
+}
+
+impl LaunchPlan {
+    /// Applies `environment` on top of the default [`SCRUBBED_ENV`] removals.
+    pub(crate) fn apply_environment(&mut self, environment: &ProcessEnvironment) {
+        self.wrapper = environment.wrapper.clone();
+        self.env_remove = SCRUBBED_ENV
+            .iter()
+            .map(|key| key.to_string())
+            .chain(environment.removals.iter().cloned())
+            .filter(|key| !environment.overrides.contains_key(key))
+            .collect();
+        self.env = environment
+            .overrides
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+    }
+
+    /// Spawn options for [`JavaRuntime::execute_with`](lighty_java::runtime::JavaRuntime::execute_with).
+    pub(crate) fn process_options(&self) -> ProcessOptions {
+        ProcessOptions {
+            wrapper: self.wrapper.clone(),
+            env: self.env.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+            env_remove: self.env_remove.iter().cloned().collect(),
+        }
+    }
+}
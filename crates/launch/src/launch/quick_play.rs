@@ -0,0 +1,336 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Quick Play: launch straight into a server, world or realm.
+//!
+//! Set on the builder with
+//! [`LaunchBuilder::join_server`](super::LaunchBuilder::join_server),
+//! [`join_world`](super::LaunchBuilder::join_world) or
+//! [`join_realm`](super::LaunchBuilder::join_realm). Versions whose
+//! manifest has the `quickPlay*` arguments (1.20+) get them, together
+//! with a `--quickPlayPath` log read back by
+//! [`InstanceControl::quick_play_log`](crate::instance::InstanceControl::quick_play_log).
+//! Older versions join servers through `--server` / `--port` and cannot
+//! open a world or realm directly.
+
+use std::path::Path;
+
+use lighty_loaders::types::version_metadata::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::arguments::{
+    supports_feature, ArgumentOptions, LaunchFeature, KEY_QUICK_PLAY_MULTIPLAYER, KEY_QUICK_PLAY_PATH,
+    KEY_QUICK_PLAY_REALMS, KEY_QUICK_PLAY_SINGLEPLAYER,
+};
+
+/// Quick Play log, stored under the instance's game directory.
+const QUICK_PLAY_LOG_FILE: &str = ".lighty-quickplay.json";
+
+/// Port assumed when a server address has none.
+const DEFAULT_SERVER_PORT: u16 = 25565;
+
+/// Where the game goes right after starting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickPlay {
+    /// Server address, optionally with a port (`"play.example.net:25565"`)
+    Server(String),
+    /// Singleplayer world, by folder name
+    World(String),
+    /// Realm ID
+    Realm(String),
+}
+
+/// Kind of a [`QuickPlayLogEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuickPlayKind {
+    Singleplayer,
+    Multiplayer,
+    Realms,
+}
+
+/// One world, server or realm joined, as logged by the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickPlayLogEntry {
+    pub kind: QuickPlayKind,
+    /// World folder, server address or realm ID
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// When it was joined (ISO-8601)
+    pub last_played: Option<String>,
+    /// Game mode (e.g. `"survival"`)
+    pub game_mode: Option<String>,
+}
+
+impl QuickPlay {
+    /// Adds the features and placeholder values joining this target to
+    /// `arguments` when `version` supports them, or legacy arguments when
+    /// it does not.
+    pub(crate) fn apply(&self, version: &Version, game_dirs: &Path, arguments: &mut ArgumentOptions) {
+        let (feature, key, target) = match self {
+            QuickPlay::Server(address) => (LaunchFeature::QuickPlayMultiplayer, KEY_QUICK_PLAY_MULTIPLAYER, address),
+            QuickPlay::World(name) => (LaunchFeature::QuickPlaySingleplayer, KEY_QUICK_PLAY_SINGLEPLAYER, name),
+            QuickPlay::Realm(id) => (LaunchFeature::QuickPlayRealms, KEY_QUICK_PLAY_REALMS, id),
+        };
+
+        if supports_feature(version, feature) {
+            arguments.features.insert(feature);
+            arguments.arg_overrides.insert(key.into(), target.clone());
+
+            if supports_feature(version, LaunchFeature::QuickPlaySupport) {
+                arguments.features.insert(LaunchFeature::QuickPlaySupport);
+                arguments
+                    .arg_overrides
+                    .entry(KEY_QUICK_PLAY_PATH.into())
+                    .or_insert_with(|| game_dirs.join(QUICK_PLAY_LOG_FILE).display().to_string());
+            }
+            return;
+        }
+
+        match self {
+            QuickPlay::Server(address) => {
+                let (host, port) = split_server_address(address);
+                arguments.raw_args.extend([
+                    "--server".to_string(),
+                    host.to_string(),
+                    "--port".to_string(),
+                    port.to_string(),
+                ]);
+            }
+            QuickPlay::World(_) | QuickPlay::Realm(_) => {
+                lighty_core::trace_warn!(
+                    destination = %target,
+                    "Quick Play into a world or realm needs Minecraft 1.20+, starting on the title screen"
+                );
+            }
+        }
+    }
+}
+
+/// Splits `host[:port]` (or `[ipv6]:port`), defaulting to port 25565.
+fn split_server_address(address: &str) -> (&str, u16) {
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, after)) = rest.split_once(']') {
+            let port = after.strip_prefix(':').and_then(|port| port.parse().ok());
+            return (host, port.unwrap_or(DEFAULT_SERVER_PORT));
+        }
+    }
+
+    match address.rsplit_once(':') {
+        // A bare IPv6 address has several colons and no port
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, port),
+            Err(_) => (address, DEFAULT_SERVER_PORT),
+        },
+        _ => (address, DEFAULT_SERVER_PORT),
+    }
+}
+
+/// Raw log entry, as written by the game.
+#[derive(Deserialize)]
+struct RawLogEntry {
+    #[serde(rename = "quickPlayWorld")]
+    world: RawLogWorld,
+    #[serde(rename = "lastPlayedTime", default)]
+    last_played: Option<String>,
+    #[serde(rename = "gamemode", default)]
+    game_mode: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawLogWorld {
+    #[serde(rename = "type")]
+    kind: QuickPlayKind,
+    id: String,
+    #[serde(default)]
+    name: String,
+}
+
+/// Quick Play log of the instance at `game_dirs`; empty when the game
+/// never wrote one.
+pub(crate) async fn load_log(game_dirs: &Path) -> Vec<QuickPlayLogEntry> {
+    let Ok(bytes) = tokio::fs::read(game_dirs.join(QUICK_PLAY_LOG_FILE)).await else {
+        return Vec::new();
+    };
+
+    // A list of entries; a lone entry is accepted too
+    let raw: Vec<RawLogEntry> = match serde_json::from_slice(&bytes) {
+        Ok(entries) => entries,
+        Err(_) => match serde_json::from_slice(&bytes) {
+            Ok(entry) => vec![entry],
+            Err(_e) => {
+                lighty_core::trace_warn!(error = %_e, "Unreadable Quick Play log");
+                return Vec::new();
+            }
+        },
+    };
+
+    raw.into_iter()
+        .map(|entry| QuickPlayLogEntry {
+            kind: entry.world.kind,
+            id: entry.world.id,
+            name: entry.world.name,
+            last_played: entry.last_played,
+            game_mode: entry.game_mode,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arguments::Arguments;
+    use lighty_core::app_state::LauncherPaths;
+    use lighty_core::AppState;
+    use lighty_version::VersionBuilder;
+
+    /// Manifest with the given rule-gated game arguments.
+    fn version(conditional_game: serde_json::Value) -> Version {
+        serde_json::from_value(serde_json::json!({
+            "main_class": { "main_class": "net.minecraft.client.main.Main" },
+            "java_version": { "major_version": 21 },
+            "arguments": { "game": [], "jvm": null, "conditional_game": conditional_game },
+            "libraries": [],
+            "mods": null,
+            "natives": null,
+            "client": null,
+            "assets_index": null,
+            "assets": null
+        }))
+        .unwrap()
+    }
+
+    /// Quick Play arguments of the 1.21 manifest.
+    fn modern() -> Version {
+        version(serde_json::json!([
+            {
+                "rules": [{ "action": "allow", "features": { "has_quick_plays_support": true } }],
+                "value": ["--quickPlayPath", "${quickPlayPath}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_singleplayer": true } }],
+                "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_multiplayer": true } }],
+                "value": ["--quickPlayMultiplayer", "${quickPlayMultiplayer}"]
+            },
+            {
+                "rules": [{ "action": "allow", "features": { "is_quick_play_realms": true } }],
+                "value": ["--quickPlayRealms", "${quickPlayRealms}"]
+            }
+        ]))
+    }
+
+    /// 1.16: only the demo and resolution rules.
+    fn legacy() -> Version {
+        version(serde_json::json!([
+            { "rules": [{ "action": "allow", "features": { "is_demo_user": true } }], "value": ["--demo"] }
+        ]))
+    }
+
+    fn game_args(quick_play: &QuickPlay, version: &Version) -> Vec<String> {
+        let _ = AppState::init_with_paths(LauncherPaths::under_root("LightyTest", std::env::temp_dir()));
+        let instance = VersionBuilder::new("quick-play-test", (), "", "1.21");
+
+        let mut options = ArgumentOptions::default();
+        quick_play.apply(version, Path::new("/games/quick-play-test"), &mut options);
+
+        let args = instance.build_arguments(version, None, &options);
+        let main = args.iter().position(|arg| arg == "net.minecraft.client.main.Main").unwrap();
+        args[main + 1..].to_vec()
+    }
+
+    #[test]
+    fn modern_versions_use_quick_play_arguments() {
+        let log = Path::new("/games/quick-play-test").join(QUICK_PLAY_LOG_FILE).display().to_string();
+
+        assert_eq!(
+            game_args(&QuickPlay::Server("play.example.net:25566".into()), &modern()),
+            ["--quickPlayPath", &log, "--quickPlayMultiplayer", "play.example.net:25566"]
+        );
+        assert_eq!(
+            game_args(&QuickPlay::World("New World".into()), &modern()),
+            ["--quickPlayPath", &log, "--quickPlaySingleplayer", "New World"]
+        );
+        assert_eq!(
+            game_args(&QuickPlay::Realm("1234567".into()), &modern()),
+            ["--quickPlayPath", &log, "--quickPlayRealms", "1234567"]
+        );
+    }
+
+    #[test]
+    fn quick_play_path_override_is_kept() {
+        let mut options = ArgumentOptions::default();
+        options.arg_overrides.insert(KEY_QUICK_PLAY_PATH.into(), "/tmp/custom.json".into());
+        QuickPlay::World("New World".into()).apply(&modern(), Path::new("/games/x"), &mut options);
+        assert_eq!(options.arg_overrides[KEY_QUICK_PLAY_PATH], "/tmp/custom.json");
+    }
+
+    #[test]
+    fn legacy_versions_join_servers_with_server_and_port() {
+        assert_eq!(
+            game_args(&QuickPlay::Server("play.example.net".into()), &legacy()),
+            ["--server", "play.example.net", "--port", "25565"]
+        );
+        assert_eq!(
+            game_args(&QuickPlay::Server("[2001:db8::1]:25570".into()), &legacy()),
+            ["--server", "2001:db8::1", "--port", "25570"]
+        );
+    }
+
+    #[test]
+    fn legacy_versions_ignore_worlds_and_realms() {
+        assert!(game_args(&QuickPlay::World("New World".into()), &legacy()).is_empty());
+        assert!(game_args(&QuickPlay::Realm("1234567".into()), &legacy()).is_empty());
+    }
+
+    #[test]
+    fn server_addresses_are_split() {
+        assert_eq!(split_server_address("play.example.net"), ("play.example.net", 25565));
+        assert_eq!(split_server_address("play.example.net:25566"), ("play.example.net", 25566));
+        assert_eq!(split_server_address("play.example.net:port"), ("play.example.net:port", 25565));
+        assert_eq!(split_server_address("192.168.1.20:25570"), ("192.168.1.20", 25570));
+        assert_eq!(split_server_address("[2001:db8::1]:25570"), ("2001:db8::1", 25570));
+        assert_eq!(split_server_address("[2001:db8::1]"), ("2001:db8::1", 25565));
+        assert_eq!(split_server_address("2001:db8::1"), ("2001:db8::1", 25565));
+    }
+
+    #[tokio::test]
+    async fn log_written_by_the_game_is_read() {
+        let dir = std::env::temp_dir().join(format!("lighty-quick-play-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        assert!(load_log(&dir).await.is_empty());
+
+        tokio::fs::write(
+            dir.join(QUICK_PLAY_LOG_FILE),
+            r#"[
+                {"quickPlayWorld":{"type":"singleplayer","id":"New World","name":"New World"},"lastPlayedTime":"2024-06-13T18:02:11Z","gamemode":"survival"},
+                {"quickPlayWorld":{"type":"multiplayer","id":"play.example.net:25565","name":"Example"},"lastPlayedTime":"2024-06-13T18:10:45Z","gamemode":"adventure"}
+            ]"#,
+        )
+        .await
+        .unwrap();
+        let log = load_log(&dir).await;
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].kind, QuickPlayKind::Singleplayer);
+        assert_eq!(log[0].id, "New World");
+        assert_eq!(log[1].kind, QuickPlayKind::Multiplayer);
+        assert_eq!(log[1].game_mode.as_deref(), Some("adventure"));
+
+        // A lone entry
+        tokio::fs::write(
+            dir.join(QUICK_PLAY_LOG_FILE),
+            r#"{"quickPlayWorld":{"type":"realms","id":"1234567","name":"Realm"}}"#,
+        )
+        .await
+        .unwrap();
+        let log = load_log(&dir).await;
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].kind, QuickPlayKind::Realms);
+        assert_eq!(log[0].last_played, None);
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -1,4 +1,3 @@
-use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -9,24 +8,32 @@ use lighty_event::EventBus;
 use lighty_java::jre_downloader::{find_java_binary, jre_download};
 use lighty_java::runtime::JavaRuntime;
 use lighty_java::JavaDistribution;
-#[cfg(not(feature = "events"))]
 use lighty_java::JreError;
 use lighty_loaders::types::version_metadata::{Version, VersionMetaData};
 use lighty_loaders::types::{Loader, LoaderExtensions, VersionInfo};
 
-use crate::arguments::{Arguments, KEY_GAME_DIRECTORY};
+use crate::arguments::Arguments;
 use crate::errors::{InstallerError, InstallerResult};
 use crate::installer::Installer;
+use crate::instance::GameHandle;
+use crate::instance::ready::ReadyDetector;
 
-use lighty_core::hosts::{build_fallback_urls, HTTP_CLIENT as CLIENT};
-use lighty_core::verify_file_sha1;
+use lighty_core::scheduler::{with_priority, DownloadPriority};
+use lighty_core::ExpectedHash;
+use lighty_core::cancel::checkpoint;
+use lighty_core::offline::ensure_online;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
 #[cfg(any(feature = "neoforge", feature = "forge"))]
 use crate::installer::ressources::libraries::{collect_library_tasks, download_libraries};
+use crate::installer::installer::install_with;
+use crate::installer::verifier::Verifier;
+use crate::installer::downloader::{download_file, DownloadTask};
 
-use super::builder::LaunchBuilder;
+use super::builder::{LaunchBuilder, LaunchOptions};
+use super::plan::LaunchPlan;
+pub(crate) use super::plan::build_plan;
 
 #[cfg(feature = "forge")]
 use crate::installer::processors::forge_install::run_forge_install_processors;
@@ -69,9 +76,7 @@ pub trait Launch {
     ///     .with_jvm_options()
     ///         .set("Xmx", "4G")
     ///         .done()
-    ///     .with_arguments()
-    ///         .set(KEY_WIDTH, "1920")
-    ///         .done()
+    ///     .with_resolution(1920, 1080)
     ///     .await?;
     /// ```
     fn launch<'a>(
@@ -102,13 +107,68 @@ pub(crate) async fn execute_launch<T>(
     version: &mut T,
     profile: &UserProfile,
     java_distribution: JavaDistribution,
-    jvm_overrides: &std::collections::HashMap<String, String>,
-    jvm_removals: &std::collections::HashSet<String>,
+    options: &LaunchOptions,
+    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
+) -> InstallerResult<GameHandle>
+where
+    T: VersionInfo<LoaderType = Loader> + LoaderExtensions + Arguments + Installer,
+{
+    let verifier = Verifier::for_instance(version).await;
+    let prepared = prepare_instance(
+        version,
+        &java_distribution,
+        &options.arguments.arg_overrides,
+        &verifier,
+        #[cfg(feature = "events")]
+        event_bus,
+    )
+    .await?;
+
+    let plan = build_plan(version, prepared.version(), profile, prepared.java_path.clone(), options);
+
+    // Launch the game
+    let ready = ReadyDetector::new(version.loader(), &options.ready);
+    execute_game(
+        version,
+        plan,
+        profile,
+        ready,
+        #[cfg(feature = "events")]
+        event_bus,
+    )
+    .await
+}
+
+/// Everything [`prepare_instance`] resolved, ready to be spawned.
+pub(crate) struct Prepared {
+    metadata: Arc<VersionMetaData>,
+    /// `metadata`'s version with user-attached mods merged in, if any.
+    merged: Option<Version>,
+    pub(crate) java_path: PathBuf,
+}
+
+impl Prepared {
+    pub(crate) fn version(&self) -> &Version {
+        match (&self.merged, self.metadata.as_ref()) {
+            (Some(merged), _) => merged,
+            (None, VersionMetaData::Version(version)) => version,
+            (None, _) => unreachable!("checked by prepare_instance"),
+        }
+    }
+}
+
+/// Every launch step short of spawning the game: metadata, JRE, mods,
+/// install and the Forge/NeoForge post-install processors.
+///
+/// Installed files are checked through `verifier`, so a repair verifier
+/// collects what it found along the way.
+pub(crate) async fn prepare_instance<T>(
+    version: &mut T,
+    java_distribution: &JavaDistribution,
     arg_overrides: &std::collections::HashMap<String, String>,
-    arg_removals: &std::collections::HashSet<String>,
-    raw_args: &[String],
+    verifier: &Verifier,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
-) -> InstallerResult<()>
+) -> InstallerResult<Prepared>
 where
     T: VersionInfo<LoaderType = Loader> + LoaderExtensions + Arguments + Installer,
 {
@@ -121,16 +181,18 @@ where
     .await?;
 
     let version_data = extract_version(&metadata)?;
+    checkpoint().await?;
 
     // 2. Make sure Java is installed
     let java_path = ensure_java_installed(
         version,
         version_data,
-        &java_distribution,
+        java_distribution,
         #[cfg(feature = "events")]
         event_bus,
     )
     .await?;
+    checkpoint().await?;
 
     // Reconcile arg_overrides[KEY_GAME_DIRECTORY] back onto the
     // builder so install + args read the same value via
@@ -156,53 +218,54 @@ where
     // source features are off (the builder methods are gated too,
     // so `mod_requests()` is always empty in that case).
     #[cfg(any(feature = "modrinth", feature = "curseforge"))]
-    let _merged_owned;
-    #[cfg(any(feature = "modrinth", feature = "curseforge"))]
-    let version_data: &Version = {
+    let merged: Option<Version> = {
         let user_mods = crate::installer::ressources::mod_resolver::resolve_user_mods(
             version.mod_requests(),
             version.minecraft_version(),
             version.loader(),
+            version.game_dirs(),
             #[cfg(feature = "events")]
             event_bus,
         )
         .await?;
         if user_mods.is_empty() {
-            version_data
+            None
         } else {
             let mut merged = version_data.clone();
             match &mut merged.mods {
                 Some(existing) => existing.extend(user_mods),
                 slot => *slot = Some(user_mods),
             }
-            _merged_owned = merged;
-            &_merged_owned
+            Some(merged)
         }
     };
+    #[cfg(not(any(feature = "modrinth", feature = "curseforge")))]
+    let merged: Option<Version> = None;
+    let version_data = merged.as_ref().unwrap_or(version_data);
+    checkpoint().await?;
 
-    // 2. Make sure Java is installed
-    let java_path = ensure_java_installed(
+    // 3. Install Minecraft dependencies (libraries, natives, client, assets)
+    // Before install, ensure the asset index exists on disk (with fallbacks).
+    ensure_asset_index_exists(
         version,
         version_data,
-        &java_distribution,
+        verifier,
         #[cfg(feature = "events")]
         event_bus,
     )
     .await?;
-
-    // 3. Install Minecraft dependencies (libraries, natives, client, assets)
-    // Before install, ensure the asset index exists on disk (with fallbacks).
-    ensure_asset_index_exists(version, version_data).await?;
     time_it!(
         "Install delay",
-        version
-            .install(
-                version_data,
-                #[cfg(feature = "events")]
-                event_bus,
-            )
-            .await?
+        install_with(
+            version,
+            version_data,
+            verifier,
+            #[cfg(feature = "events")]
+            event_bus,
+        )
+        .await?
     );
+    checkpoint().await?;
 
     // 3b. Forge-family install_profile libraries + processors.
     //
@@ -219,7 +282,8 @@ where
     if matches!(version.loader(), Loader::NeoForge) {
         let install_profile = NEOFORGE.get_raw(version).await?;
         let profile_libs = neoforge_install_profile_libraries(install_profile.as_ref());
-        let profile_tasks = collect_library_tasks(version, &profile_libs).await;
+        let profile_tasks = collect_library_tasks(version, &profile_libs, verifier).await;
+        verifier.save().await;
         download_libraries(
             profile_tasks,
             #[cfg(feature = "events")]
@@ -239,7 +303,8 @@ where
             } => {
                 // Download processor-only libraries, then run processors.
                 let profile_libs = forge_install_profile_libraries_modern(install_profile);
-                let profile_tasks = collect_library_tasks(version, &profile_libs).await;
+                let profile_tasks = collect_library_tasks(version, &profile_libs, verifier).await;
+                verifier.save().await;
                 download_libraries(
                     profile_tasks,
                     #[cfg(feature = "events")]
@@ -257,21 +322,11 @@ where
         }
     }
 
-    // Launch the game
-    execute_game(
-        version,
-        version_data,
-        profile,
+    Ok(Prepared {
+        metadata,
+        merged,
         java_path,
-        arg_overrides,
-        arg_removals,
-        jvm_overrides,
-        jvm_removals,
-        raw_args,
-        #[cfg(feature = "events")]
-        event_bus,
-    )
-    .await
+    })
 }
 
 /// Fetches the loader's full metadata document.
@@ -382,6 +437,8 @@ where
         }
     }
 
+    ensure_online(|| format!("{} Java {} runtime", java_distribution.get_name(), java_version))?;
+
     // Download JRE (either not found or invalid existing install)
     #[cfg(feature = "events")]
     let path = jre_download(
@@ -392,7 +449,10 @@ where
             lighty_core::trace_debug!("[Java] Download progress: {}/{}", current, total);
         },
         event_bus,
-    ).await.map_err(|e| InstallerError::DownloadFailed(format!("JRE download failed: {}", e)))?;
+    ).await.map_err(|e| match e {
+        JreError::Cancelled(cancelled) => cancelled.into(),
+        e => InstallerError::DownloadFailed(format!("JRE download failed: {}", e)),
+    })?;
 
     #[cfg(not(feature = "events"))]
     let path = jre_download(
@@ -402,7 +462,10 @@ where
         |current, total| {
             lighty_core::trace_debug!("[Java] Download progress: {}/{}", current, total);
         },
-    ).await.map_err(|e : JreError | InstallerError::DownloadFailed(format!("JRE download failed: {}", e)))?;
+    ).await.map_err(|e: JreError| match e {
+        JreError::Cancelled(cancelled) => cancelled.into(),
+        e => InstallerError::DownloadFailed(format!("JRE download failed: {}", e)),
+    })?;
 
     lighty_core::trace_info!("[Java] Java {} installed successfully", java_version);
     Ok(path)
@@ -452,18 +515,15 @@ async fn validate_java_binary(path: &std::path::Path) -> bool {
 }
 
 /// Spawns the game process and wires up event/console handlers.
+///
+/// The returned handle is fed by the console task, which owns the child.
 async fn execute_game<T>(
     builder: &T,
-    version: &Version,
+    plan: LaunchPlan,
     profile: &UserProfile,
-    java_path: PathBuf,
-    arg_overrides: &HashMap<String, String>,
-    arg_removals: &HashSet<String>,
-    jvm_overrides: &HashMap<String, String>,
-    jvm_removals: &HashSet<String>,
-    raw_args: &[String],
+    ready: ReadyDetector,
     #[cfg(feature = "events")] event_bus: Option<&EventBus>,
-) -> InstallerResult<()>
+) -> InstallerResult<GameHandle>
 where
     T: VersionInfo + Arguments,
 {
@@ -471,41 +531,22 @@ where
     use crate::instance::{handle_console_streams, INSTANCE_MANAGER};
 
     let username = profile.username.as_str();
-
-    // Build the full argv (JVM args + main class + game args)
-    let arguments = builder.build_arguments(
-        version,
-        Some(profile),
-        arg_overrides,
-        arg_removals,
-        jvm_overrides,
-        jvm_removals,
-        raw_args,
-    );
-
-    // Determine the effective runtime directory.
-    // If an explicit `game_directory` override exists, launch from it.
-    let mut runtime_dir = if let Some(dir) = arg_overrides.get(KEY_GAME_DIRECTORY) {
-        let path = PathBuf::from(dir);
-        lighty_core::trace_info!("[Launch] Using overridden game_directory as runtime_dir: {:?}", path);
-        path
-    } else {
-        let path = builder.game_dirs().join("runtime");
-        lighty_core::trace_info!("[Launch] Using default runtime_dir from game_dirs(): {:?}", path);
-        path
-    };
+    let options = plan.process_options();
+    let runtime_dir = plan.working_dir;
+    lighty_core::trace_info!("[Launch] Using runtime_dir: {:?}", runtime_dir);
 
     if !runtime_dir.exists() {
-        if let Err(e) = std::fs::create_dir_all(&runtime_dir) {
-            lighty_core::trace_warn!("[Launch] Failed to create runtime_dir {:?}: {}", runtime_dir, e);
+        if let Err(_e) = std::fs::create_dir_all(&runtime_dir) {
+            lighty_core::trace_warn!("[Launch] Failed to create runtime_dir {:?}: {}", runtime_dir, _e);
         }
     }
 
     // Wrap the Java binary path in a runtime helper
-    let java_runtime = JavaRuntime::new(java_path);
+    let java_path = plan.java_path;
+    let java_runtime = JavaRuntime::new(java_path.clone());
     lighty_core::trace_info!("[Launch] Executing game in runtime_dir {:?}...", runtime_dir);
 
-    match java_runtime.execute(arguments, &runtime_dir).await {
+    match java_runtime.execute_with(plan.arguments, &runtime_dir, &options).await {
         Ok(child) => {
             let pid = child.id().ok_or(InstallerError::NoPid)?;
 
@@ -525,10 +566,15 @@ where
                 // (données persistantes), même si le runtime effectif peut
                 // pointer vers un répertoire éphémère.
                 game_dir: builder.game_dirs().to_path_buf(),
+                runtime_dir: runtime_dir.clone(),
+                libraries_dir: builder.libraries_dir(),
+                assets_dir: builder.assets_dir(),
+                java_path,
                 started_at: std::time::SystemTime::now(),
             };
 
             INSTANCE_MANAGER.register_instance(instance).await;
+            let (handle, link) = GameHandle::new(pid, builder.name().to_string());
 
             // Emit InstanceLaunched event
             #[cfg(feature = "events")]
@@ -560,6 +606,7 @@ where
                     instance_name,
                     version,
                     bus_clone,
+                    handle.readiness_watch(),
                 ));
             }
 
@@ -569,11 +616,13 @@ where
                 pid,
                 builder.name().to_string(),
                 child,
+                link,
+                ready,
                 #[cfg(feature = "events")]
                 event_bus.cloned(),
             ));
 
-            Ok(())
+            Ok(handle)
         }
         Err(e) => {
             lighty_core::trace_error!("[Launch] Failed to launch game: {}", e);
@@ -594,69 +643,52 @@ fn extract_version(metadata: &VersionMetaData) -> InstallerResult<&Version> {
 }
 
 /// Ensure the asset index JSON exists on disk and is valid. Downloads it if missing or invalid.
+///
+/// The index goes through [`download_file`] like every other install file,
+/// so it is written to a temporary file, locked, and linked from the shared
+/// store when one is configured.
 async fn ensure_asset_index_exists<T>(
     builder: &T,
     version: &Version,
+    verifier: &Verifier,
+    #[cfg(feature = "events")] event_bus: Option<&EventBus>,
 ) -> InstallerResult<()>
 where
     T: VersionInfo,
 {
-    if let Some(asset_index) = &version.assets_index {
-        let indexes_dir = builder.game_dirs().join("assets").join("indexes");
-        // Ensure directory exists
-        lighty_core::mkdir!(indexes_dir);
-
-        let index_file_path = builder
-            .game_dirs()
-            .join("assets")
-            .join("indexes")
-            .join(format!("{}.json", asset_index.id));
-
-        // If exists and valid, nothing to do
-        if index_file_path.exists() {
-            match verify_file_sha1(&index_file_path, &asset_index.sha1).await {
-                Ok(true) => return Ok(()),
-                _ => {
-                    let _ = tokio::fs::remove_file(&index_file_path).await;
-                    lighty_core::trace_warn!(
-                        "[Assets] Asset index {} missing or invalid on disk, will re-download",
-                        asset_index.id
-                    );
-                }
-            }
-        }
-
-        // Try download from fallback URLs
-        for candidate in build_fallback_urls(&asset_index.url) {
-            let resp = CLIENT.get(&candidate).send().await?;
-            if !resp.status().is_success() {
-                continue;
-            }
-
-            let bytes = resp.bytes().await?;
-            tokio::fs::write(&index_file_path, &bytes).await?;
+    let Some(asset_index) = &version.assets_index else {
+        return Ok(());
+    };
 
-            match verify_file_sha1(&index_file_path, &asset_index.sha1).await {
-                Ok(true) => {
-                    lighty_core::trace_info!(
-                        "[Assets] Asset index {} downloaded and verified",
-                        asset_index.id
-                    );
-                    return Ok(());
-                }
-                _ => {
-                    let _ = tokio::fs::remove_file(&index_file_path).await;
-                    continue;
-                }
-            }
-        }
+    let task = DownloadTask::new(
+        asset_index.url.clone(),
+        builder
+            .assets_dir()
+            .join("indexes")
+            .join(format!("{}.json", asset_index.id)),
+        Some(ExpectedHash::sha1(asset_index.sha1.clone())),
+        Some(asset_index.size),
+    );
+    // If exists and valid, nothing to do
+    let Some(task) = verifier.retain_missing(vec![task]).await.pop() else {
+        return Ok(());
+    };
 
-        return Err(InstallerError::DownloadFailed(format!(
-            "Failed to download or verify asset index {}",
-            asset_index.id
-        )));
-    }
+    ensure_online(|| format!("asset index {}", asset_index.id))?;
 
+    with_priority(
+        DownloadPriority::High,
+        download_file(
+            &task,
+            #[cfg(feature = "events")]
+            event_bus,
+        ),
+    )
+    .await?;
+    lighty_core::trace_info!(
+        "[Assets] Asset index {} downloaded and verified",
+        asset_index.id
+    );
     Ok(())
 }
 
@@ -0,0 +1,408 @@
+// Copyright (c) 2025 Hamadi
+// Licensed under the MIT License
+
+//! Standalone launch scripts generated from a [`LaunchPlan`].
+//!
+//! The script starts the prepared instance with no launcher in the loop:
+//! the Java binary, working directory and natives directory are variables
+//! at the top, followed by the quoted argv. Paths are those of the machine
+//! the plan was resolved on; edit the variables when moving the instance
+//! elsewhere. Either kind can be generated on any OS.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::plan::{looks_like_secret, LaunchPlan};
+
+/// Environment variable a [`ScriptToken::Placeholder`] script reads the
+/// access token from.
+pub const ACCESS_TOKEN_VAR: &str = "ACCESS_TOKEN";
+
+/// Variable holding the natives directory.
+const NATIVES_DIR_VAR: &str = "NATIVES_DIR";
+
+/// Game arguments whose value carries the access token.
+const TOKEN_FLAGS: &[&str] = &["--accessToken", "--session"];
+
+/// Script flavour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    /// POSIX `sh` script (`launch.sh`)
+    Shell,
+    /// Windows batch file (`launch.bat`)
+    Batch,
+}
+
+impl ScriptKind {
+    /// Conventional file name
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Shell => "launch.sh",
+            Self::Batch => "launch.bat",
+        }
+    }
+
+    fn classpath_separator(self) -> &'static str {
+        match self {
+            Self::Shell => ":",
+            Self::Batch => ";",
+        }
+    }
+}
+
+/// How the access token ends up in the script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptToken {
+    /// Written into the script; keep the file private
+    Embed,
+    /// Read from the [`ACCESS_TOKEN_VAR`] environment variable at launch
+    Placeholder,
+}
+
+/// Piece of a script argument.
+enum Part<'a> {
+    Text(&'a str),
+    Var(&'static str),
+}
+
+impl LaunchPlan {
+    /// Renders the plan as a standalone launch script.
+    ///
+    /// The classpath is re-joined with the separator of `kind`, so a batch
+    /// file generated on Linux still splits it correctly. Offline profiles
+    /// have no real token, so [`ScriptToken::Placeholder`] leaves it as is.
+    pub fn to_script(&self, kind: ScriptKind, token: ScriptToken) -> String {
+        let natives_dir = self
+            .arguments
+            .iter()
+            .find_map(|arg| arg.strip_prefix("-Djava.library.path="))
+            .filter(|dir| !dir.is_empty());
+        let access_token = match token {
+            ScriptToken::Placeholder => self.access_token.as_deref().filter(|token| looks_like_secret(token)),
+            ScriptToken::Embed => None,
+        };
+
+        let classpath = self
+            .classpath
+            .iter()
+            .map(|entry| entry.display().to_string())
+            .collect::<Vec<_>>()
+            .join(kind.classpath_separator());
+
+        let mut arguments = Vec::with_capacity(self.arguments.len());
+        let mut previous: Option<&str> = None;
+        for arg in &self.arguments {
+            let arg = if previous == Some("-cp") && !self.classpath.is_empty() {
+                classpath.as_str()
+            } else {
+                arg.as_str()
+            };
+
+            let secret = access_token.filter(|_| previous.is_some_and(|flag| TOKEN_FLAGS.contains(&flag)));
+            let mut parts = vec![Part::Text(arg)];
+            if let Some(secret) = secret {
+                parts = substitute(parts, secret, ACCESS_TOKEN_VAR);
+            }
+            if let Some(dir) = natives_dir {
+                parts = substitute(parts, dir, NATIVES_DIR_VAR);
+            }
+            arguments.push(parts);
+            previous = Some(arg);
+        }
+
+        match kind {
+            ScriptKind::Shell => self.shell_script(natives_dir, access_token.is_some(), &arguments),
+            ScriptKind::Batch => self.batch_script(natives_dir, access_token.is_some(), &arguments),
+        }
+    }
+
+    /// Writes [`Self::to_script`] to `dir` under [`ScriptKind::file_name`]
+    /// and returns its path. Shell scripts are made executable on Unix.
+    pub async fn write_script(&self, dir: &Path, kind: ScriptKind, token: ScriptToken) -> io::Result<PathBuf> {
+        let path = dir.join(kind.file_name());
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(&path, self.to_script(kind, token)).await?;
+
+        #[cfg(unix)]
+        if kind == ScriptKind::Shell {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).await?;
+        }
+
+        lighty_core::trace_info!(path = %path.display(), "Launch script written");
+        Ok(path)
+    }
+
+    fn shell_script(&self, natives_dir: Option<&str>, token_placeholder: bool, arguments: &[Vec<Part>]) -> String {
+        let mut script = String::from("#!/bin/sh\n");
+        script.push_str(&format!("# Generated by {} - {}\n\n", lighty_core::AppState::name(), self.main_class));
+
+        script.push_str(&format!("JAVA={}\n", shell_quote(&self.java_path.display().to_string())));
+        script.push_str(&format!("WORKING_DIR={}\n", shell_quote(&self.working_dir.display().to_string())));
+        if let Some(dir) = natives_dir {
+            script.push_str(&format!("{}={}\n", NATIVES_DIR_VAR, shell_quote(dir)));
+        }
+        if token_placeholder {
+            script.push_str(&format!(
+                "{var}=\"${{{var}:?set {var} to the account access token}}\"\n",
+                var = ACCESS_TOKEN_VAR
+            ));
+        }
+        script.push('\n');
+
+        for key in &self.env_remove {
+            script.push_str(&format!("unset {}\n", key));
+        }
+        for (key, value) in &self.env {
+            script.push_str(&format!("export {}={}\n", key, shell_quote(value)));
+        }
+
+        script.push_str("cd \"$WORKING_DIR\" || exit 1\n");
+        script.push_str("exec");
+        for arg in &self.wrapper {
+            script.push(' ');
+            script.push_str(&shell_quote(arg));
+        }
+        script.push_str(" \"$JAVA\"");
+        for parts in arguments {
+            script.push_str(" \\\n    ");
+            if parts.is_empty() {
+                script.push_str("''");
+            }
+            for part in parts {
+                match part {
+                    Part::Text(text) => script.push_str(&shell_quote(text)),
+                    Part::Var(var) => script.push_str(&format!("\"${}\"", var)),
+                }
+            }
+        }
+        script.push('\n');
+        script
+    }
+
+    fn batch_script(&self, natives_dir: Option<&str>, token_placeholder: bool, arguments: &[Vec<Part>]) -> String {
+        let mut lines = vec![
+            "@echo off".to_string(),
+            format!("rem Generated by {} - {}", lighty_core::AppState::name(), self.main_class),
+            String::new(),
+            "setlocal".to_string(),
+            format!("set \"JAVA={}\"", batch_escape(&self.java_path.display().to_string())),
+            format!("set \"WORKING_DIR={}\"", batch_escape(&self.working_dir.display().to_string())),
+        ];
+        if let Some(dir) = natives_dir {
+            lines.push(format!("set \"{}={}\"", NATIVES_DIR_VAR, batch_escape(dir)));
+        }
+        if token_placeholder {
+            lines.push(format!(
+                "if not defined {var} (echo set {var} to the account access token & exit /b 1)",
+                var = ACCESS_TOKEN_VAR
+            ));
+        }
+        lines.push(String::new());
+
+        for key in &self.env_remove {
+            lines.push(format!("set \"{}=\"", key));
+        }
+        for (key, value) in &self.env {
+            lines.push(format!("set \"{}={}\"", key, batch_escape(value)));
+        }
+
+        lines.push("cd /d \"%WORKING_DIR%\" || exit /b 1".to_string());
+        let mut command = String::new();
+        for arg in &self.wrapper {
+            command.push_str(&format!("\"{}\" ", batch_escape(arg)));
+        }
+        command.push_str("\"%JAVA%\"");
+        for parts in arguments {
+            command.push_str(" ^\r\n    \"");
+            for part in parts {
+                match part {
+                    Part::Text(text) => command.push_str(&batch_escape(&text.replace('"', "\\\""))),
+                    Part::Var(var) => command.push_str(&format!("%{}%", var)),
+                }
+            }
+            command.push('"');
+        }
+        lines.push(command);
+
+        let mut script = lines.join("\r\n");
+        script.push_str("\r\n");
+        script
+    }
+}
+
+/// Splits every text part on `needle`, putting `var` in between.
+fn substitute<'a>(parts: Vec<Part<'a>>, needle: &str, var: &'static str) -> Vec<Part<'a>> {
+    let mut result = Vec::with_capacity(parts.len());
+    for part in parts {
+        match part {
+            Part::Text(text) => {
+                let mut pieces = text.split(needle);
+                if let Some(first) = pieces.next() {
+                    result.push(Part::Text(first));
+                }
+                for piece in pieces {
+                    result.push(Part::Var(var));
+                    result.push(Part::Text(piece));
+                }
+            }
+            var => result.push(var),
+        }
+    }
+    result.retain(|part| !matches!(part, Part::Text("")));
+    result
+}
+
+/// Single-quotes `value` for `sh`.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Escapes `%` for a batch file.
+fn batch_escape(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighty_core::app_state::LauncherPaths;
+    use lighty_core::AppState;
+
+    /// Arguments a shell or batch file could mangle.
+    const TRICKY: &[&str] = &[
+        "plain",
+        "with space",
+        "100%",
+        "%APPDATA%",
+        "say \"hi\"",
+        "$HOME",
+        "${PATH}",
+        "`id`",
+        "wow!",
+        "it's",
+        "back\\slash",
+        "",
+    ];
+
+    fn plan(java_path: &Path, working_dir: &Path, arguments: Vec<String>) -> LaunchPlan {
+        // Scripts name the launcher in their header
+        let _ = AppState::init_with_paths(LauncherPaths::under_root("LightyTest", std::env::temp_dir()));
+        serde_json::from_value(serde_json::json!({
+            "java_path": java_path,
+            "arguments": arguments,
+            "working_dir": working_dir,
+            "env": {},
+            "main_class": "net.minecraft.client.main.Main",
+            "classpath": [],
+        }))
+        .unwrap()
+    }
+
+    /// Fresh directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("lighty-script-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_quote_round_trips() {
+        for value in TRICKY {
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("printf %s {}", shell_quote(value)))
+                .output()
+                .unwrap();
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), *value);
+        }
+    }
+
+    /// Runs the shell script of a plan whose "Java" prints its argv, one
+    /// NUL-terminated argument each.
+    #[cfg(unix)]
+    fn run_shell_script(name: &str, arguments: Vec<String>, access_token: Option<&str>, env: &[(&str, &str)]) -> (String, Vec<String>) {
+        let dir = temp_dir(name);
+        let echo = dir.join("echo-args.sh");
+        std::fs::write(&echo, "#!/bin/sh\nfor arg in \"$@\"; do printf '%s\\0' \"$arg\"; done\n").unwrap();
+
+        // Run through `sh`: executing a file just written races with forks
+        // of other test threads (ETXTBSY)
+        let mut plan = plan(Path::new("/bin/sh"), &dir, arguments);
+        plan.arguments.insert(0, echo.display().to_string());
+        plan.access_token = access_token.map(str::to_string);
+
+        let script = plan.to_script(ScriptKind::Shell, ScriptToken::Placeholder);
+        let path = dir.join(ScriptKind::Shell.file_name());
+        std::fs::write(&path, &script).unwrap();
+
+        let output = std::process::Command::new("sh").arg(&path).envs(env.iter().copied()).output().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let args = stdout.split_terminator('\0').map(str::to_string).collect();
+        (script, args)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_script_passes_arguments_verbatim() {
+        let arguments: Vec<String> = TRICKY.iter().map(|arg| arg.to_string()).collect();
+        let (_, args) = run_shell_script("verbatim", arguments.clone(), None, &[]);
+        assert_eq!(args, arguments);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_script_reads_natives_dir_and_token_from_variables() {
+        let natives = "/tmp/natives dir/$1%";
+        let token = "eyJhbGciOi.secret$token";
+        let arguments = vec![
+            format!("-Djava.library.path={}", natives),
+            format!("-Dorg.lwjgl.librarypath={}", natives),
+            "--accessToken".to_string(),
+            token.to_string(),
+        ];
+
+        let (script, args) = run_shell_script("variables", arguments.clone(), Some(token), &[(ACCESS_TOKEN_VAR, token)]);
+        assert_eq!(args, arguments);
+        assert!(!script.contains(token));
+        assert!(script.contains("\"$NATIVES_DIR\""));
+    }
+
+    #[test]
+    fn placeholder_keeps_short_tokens() {
+        let mut plan = plan(Path::new("java"), Path::new("."), vec!["--accessToken".into(), "0".into()]);
+        plan.access_token = Some("0".into());
+
+        let script = plan.to_script(ScriptKind::Shell, ScriptToken::Placeholder);
+        assert!(script.contains("'--accessToken' \\\n    '0'"));
+        assert!(!script.contains(ACCESS_TOKEN_VAR));
+    }
+
+    #[test]
+    fn batch_script_escapes_arguments() {
+        let arguments = TRICKY.iter().map(|arg| arg.to_string()).collect();
+        let plan = plan(Path::new("C:\\Program Files\\Java\\bin\\java.exe"), Path::new("C:\\Games\\100%"), arguments);
+        let script = plan.to_script(ScriptKind::Batch, ScriptToken::Embed);
+
+        assert!(script.contains("set \"JAVA=C:\\Program Files\\Java\\bin\\java.exe\"\r\n"));
+        assert!(script.contains("set \"WORKING_DIR=C:\\Games\\100%%\"\r\n"));
+        for expected in [
+            "\"plain\"",
+            "\"with space\"",
+            "\"100%%\"",
+            "\"%%APPDATA%%\"",
+            "\"say \\\"hi\\\"\"",
+            "\"$HOME\"",
+            "\"wow!\"",
+            "\"it's\"",
+            " ^\r\n    \"\"",
+        ] {
+            assert!(script.contains(expected), "{} not in {}", expected, script);
+        }
+    }
+}
@@ -5,23 +5,30 @@
 //!
 //! Used by the launch pipeline to emit the [`InstanceWindowAppeared`]
 //! event once the freshly spawned Minecraft process has a visible window.
+//! Outside Windows, the window is detected from the game's console output
+//! (see [`ready`](crate::instance::ready)).
 //!
 //! [`InstanceWindowAppeared`]: lighty_event::Event::InstanceWindowAppeared
 
 #![cfg(feature = "events")]
 
 use lighty_event::{Event, EventBus, InstanceWindowAppearedEvent};
+use tokio::sync::watch;
+
+use crate::instance::Readiness;
 
 /// Watches for the game window to appear and emits `InstanceWindowAppeared`.
 ///
-/// On Windows: polls every 100ms for up to 30s using `EnumWindows`.
-/// On other platforms: emits unconditionally after a 5s delay (heuristic,
-/// since there is no portable per-PID window enumeration).
+/// On Windows: polls every 100ms for up to 30s using `EnumWindows`, until
+/// the process exits.
+/// On other platforms: waits for the console's LWJGL window marker (or a
+/// ready marker), since there is no portable per-PID window enumeration.
 pub(crate) async fn detect_window_appearance(
     pid: u32,
     instance_name: String,
     version: String,
     event_bus: EventBus,
+    readiness: watch::Receiver<Readiness>,
 ) {
     #[cfg(windows)]
     {
@@ -32,6 +39,9 @@ pub(crate) async fn detect_window_appearance(
         let check_interval = Duration::from_millis(100);
 
         for _ in 0..max_attempts {
+            if *readiness.borrow() == Readiness::Exited {
+                return;
+            }
             if has_visible_window(pid) {
                 lighty_core::trace_info!("[Launch] Window appeared for PID: {}", pid);
 
@@ -52,13 +62,17 @@ pub(crate) async fn detect_window_appearance(
 
     #[cfg(not(windows))]
     {
-        // Non-Windows platforms: emit unconditionally after a fixed delay (best-effort)
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        let mut readiness = readiness;
+        let state = match readiness.wait_for(|state| *state != Readiness::Starting).await {
+            Ok(state) => state.clone(),
+            Err(_) => return,
+        };
+        if !matches!(state, Readiness::WindowCreated | Readiness::Ready { .. }) {
+            lighty_core::trace_warn!("[Launch] No window marker in the console for PID: {}", pid);
+            return;
+        }
 
-        lighty_core::trace_info!(
-            "[Launch] Assuming window appeared for PID: {} (non-Windows platform)",
-            pid
-        );
+        lighty_core::trace_info!("[Launch] Window appeared for PID: {}", pid);
 
         event_bus.emit(Event::InstanceWindowAppeared(InstanceWindowAppearedEvent {
             pid,
@@ -19,6 +19,6 @@ pub mod installer;
 pub mod instance;
 
 // Re-export commonly used items
-pub use launch::{LaunchBuilder, LaunchConfig};
+pub use launch::{LaunchBuilder, LaunchConfig, LaunchPlan, ScriptKind, ScriptToken};
 pub use installer::Installer;
-pub use instance::{InstanceControl, InstanceError, InstanceResult};
+pub use instance::{GameHandle, InstanceControl, InstanceError, InstanceResult};
@@ -5,10 +5,15 @@ use crate::loaders::vanilla::{vanilla::VanillaQuery};
 use once_cell::sync::Lazy;
 use super::fabric_metadata::FabricMetaData;
 use async_trait::async_trait;
-use lighty_core::hosts::{HTTP_CLIENT as CLIENT, build_fallback_urls};
+use serde::Serialize;
+use lighty_core::hosts::{http_client, build_fallback_urls};
+use lighty_core::mirrors::MirrorPolicy;
+use crate::utils::disk_cache::record_response;
+use lighty_core::offline::{ensure_online, is_offline};
 use lighty_core::hosts::prism_meta_url;
 use futures::future::join_all;
 use std::collections::HashMap;
+use std::time::Instant;
 use serde::de::DeserializeOwned;
 
 /// FabricMC metadata server (returns the `profile/json` manifest).
@@ -22,7 +27,8 @@ pub type Result<T> = std::result::Result<T, QueryError>;
 pub static FABRIC: Lazy<ManifestRepository<FabricQuery>> = Lazy::new(|| ManifestRepository::new());
 
 /// Sub-queries supported by the Fabric loader.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FabricQuery {
     /// Merged library list (Fabric + Vanilla).
     Libraries,
@@ -95,6 +101,7 @@ impl Query for FabricQuery {
             client: vanilla_builder.client,
             assets_index: vanilla_builder.assets_index,
             assets: vanilla_builder.assets,
+            logging: vanilla_builder.logging,
         })
     }
 }
@@ -124,7 +131,8 @@ fn merge_arguments(vanilla: Arguments, fabric: Arguments) -> Arguments {
             (None, Some(f)) => Some(f),
             (None, None) => None,
         },
-
+        conditional_game: vanilla.conditional_game.into_iter().chain(fabric.conditional_game).collect(),
+        conditional_jvm: vanilla.conditional_jvm.into_iter().chain(fabric.conditional_jvm).collect(),
     }
 }
 
@@ -200,6 +208,7 @@ async fn extract_libraries(full_data: &FabricMetaData) -> Result<Vec<Library>> {
                 path: Some(path),
                 sha1,
                 size,
+                hashes: Vec::new(),
             }
         }
     });
@@ -233,20 +242,28 @@ fn maven_artifact_to_path_and_url(maven_name: &str, base_url: &str) -> (String,
 }
 
 async fn fetch_json_with_fallback<T: DeserializeOwned>(url: &str) -> Result<T> {
+    ensure_online(|| url.to_string())?;
     let mut last_error = None;
 
     for candidate in build_fallback_urls(url) {
-        match CLIENT.get(&candidate).send().await {
+        let started = Instant::now();
+        match http_client().get(&candidate).send().await {
             Ok(response) => match response.error_for_status() {
-                Ok(response) => match response.json::<T>().await {
-                    Ok(value) => return Ok(value),
-                    Err(e) => {
-                        last_error = Some(format!(
-                            "JSON parse error for {}: {}",
-                            candidate, e
-                        ));
+                Ok(response) => {
+                    record_response(&candidate, &response);
+                    match response.json::<T>().await {
+                        Ok(value) => {
+                            MirrorPolicy::global().record_success(&candidate, started.elapsed());
+                            return Ok(value);
+                        }
+                        Err(e) => {
+                            last_error = Some(format!(
+                                "JSON parse error for {}: {}",
+                                candidate, e
+                            ));
+                        }
                     }
-                },
+                }
                 Err(e) => {
                     last_error = Some(format!("HTTP error for {}: {}", candidate, e));
                 }
@@ -255,6 +272,7 @@ async fn fetch_json_with_fallback<T: DeserializeOwned>(url: &str) -> Result<T> {
                 last_error = Some(format!("Request error for {}: {}", candidate, e));
             }
         }
+        MirrorPolicy::global().record_failure(&candidate);
     }
 
     Err(QueryError::Conversion {
@@ -264,10 +282,13 @@ async fn fetch_json_with_fallback<T: DeserializeOwned>(url: &str) -> Result<T> {
 
 /// Récupère le SHA1 d'un artifact Maven depuis le fichier .sha1
 async fn fetch_maven_sha1(jar_url: &str) -> Option<String> {
+    if is_offline() {
+        return None;
+    }
     for candidate in build_fallback_urls(jar_url) {
         let sha1_url = format!("{}.sha1", candidate);
 
-        if let Ok(response) = CLIENT.get(&sha1_url).send().await {
+        if let Ok(response) = http_client().get(&sha1_url).send().await {
             if response.status().is_success() {
                 if let Ok(text) = response.text().await {
                     if let Some(sha1) = text.trim().split_whitespace().next() {
@@ -285,8 +306,11 @@ async fn fetch_maven_sha1(jar_url: &str) -> Option<String> {
 
 /// Récupère la taille d'un fichier sans le télécharger (HEAD request)
 async fn fetch_file_size(url: &str) -> Option<u64> {
+    if is_offline() {
+        return None;
+    }
     for candidate in build_fallback_urls(url) {
-        if let Ok(response) = CLIENT.head(&candidate).send().await {
+        if let Ok(response) = http_client().head(&candidate).send().await {
             if let Some(value) = response.headers().get("content-length") {
                 if let Ok(text) = value.to_str() {
                     if let Ok(size) = text.parse() {
@@ -303,6 +327,7 @@ fn extract_arguments(full_data: &FabricMetaData) -> Arguments {
     Arguments {
         game: full_data.arguments.game.clone(),
         jvm: Some(full_data.arguments.jvm.clone()),
+        ..Default::default()
     }
 }
 
@@ -19,12 +19,14 @@
 //! distinct from NeoForge's `net/neoforged/` so the two never collide.
 
 use async_trait::async_trait;
+use serde::Serialize;
 use once_cell::sync::Lazy;
 use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
 use zip::ZipArchive;
 
 use lighty_core::download::download_file_untracked;
 use lighty_core::mkdir;
+use lighty_core::offline::ensure_online;
 
 use crate::loaders::vanilla::vanilla::VanillaQuery;
 use crate::types::version_metadata::{Arguments, Library, MainClass, Version, VersionMetaData};
@@ -50,7 +52,8 @@ pub static FORGE: Lazy<ManifestRepository<ForgeQuery>> =
     Lazy::new(|| ManifestRepository::new());
 
 /// Sub-queries supported by the Forge loader.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ForgeQuery {
     /// Library list from the installer.
     /// - Modern: processor-only libraries from `install_profile.json`
@@ -188,6 +191,7 @@ async fn fetch_modern_install_data<V: VersionInfo>(version: &V) -> Result<ForgeR
 
     if needs_download {
         lighty_core::trace_info!(path = ?installer_path, loader = "forge", "Downloading installer");
+        ensure_online(|| format!("Forge installer {}", installer_path.display()))?;
         download_file_untracked(&installer_url, &installer_path)
             .await
             .map_err(|e| QueryError::Conversion {
@@ -251,6 +255,7 @@ async fn modern_version_builder<V: VersionInfo>(
         client: vanilla_builder.client,
         assets_index: vanilla_builder.assets_index,
         assets: vanilla_builder.assets,
+        logging: vanilla_builder.logging,
     })
 }
 
@@ -262,6 +267,8 @@ fn replace_game_keep_jvm(vanilla: Arguments, forge: Arguments) -> Arguments {
     Arguments {
         game: forge.game,
         jvm: vanilla.jvm,
+        conditional_game: Vec::new(),
+        conditional_jvm: vanilla.conditional_jvm,
     }
 }
 
@@ -290,6 +297,8 @@ fn merge_arguments(vanilla: Arguments, forge: Arguments) -> Arguments {
             (None, Some(f)) => Some(f),
             (None, None) => None,
         },
+        conditional_game: vanilla.conditional_game.into_iter().chain(forge.conditional_game).collect(),
+        conditional_jvm: vanilla.conditional_jvm.into_iter().chain(forge.conditional_jvm).collect(),
     }
 }
 
@@ -345,6 +354,7 @@ fn extract_arguments(version_meta: &ForgeVersionManifest) -> Arguments {
         return Arguments {
             game: args.game.clone(),
             jvm: Some(args.jvm.clone()),
+            ..Default::default()
         };
     }
     // Back-ported modern installers (e.g. Forge 14.23.5.2860 for MC
@@ -355,7 +365,7 @@ fn extract_arguments(version_meta: &ForgeVersionManifest) -> Arguments {
         .as_deref()
         .map(|s| s.split_whitespace().map(String::from).collect())
         .unwrap_or_default();
-    Arguments { game, jvm: None }
+    Arguments { game, ..Default::default() }
 }
 
 /// Returns the install_profile.json libraries (modern era, ≥ 1.13) as
@@ -375,6 +385,7 @@ pub fn extract_install_profile_libraries_modern(full_data: &ForgeInstallProfile)
             path: Some(lib.downloads.artifact.path.clone()),
             sha1: Some(lib.downloads.artifact.sha1.clone()),
             size: Some(lib.downloads.artifact.size),
+            hashes: Vec::new(),
         })
         .collect()
 }
@@ -389,6 +400,7 @@ fn extract_libraries_from_version_meta(version_meta: &ForgeVersionManifest) -> V
             path: Some(lib.downloads.artifact.path.clone()),
             sha1: Some(lib.downloads.artifact.sha1.clone()),
             size: Some(lib.downloads.artifact.size),
+            hashes: Vec::new(),
         })
         .collect()
 }
@@ -34,6 +34,7 @@ use zip::ZipArchive;
 
 use lighty_core::download::download_file_untracked;
 use lighty_core::mkdir;
+use lighty_core::offline::ensure_online;
 
 use lighty_core::system::OS;
 
@@ -194,6 +195,7 @@ pub async fn ensure_installer_cached<V: VersionInfo>(version: &V) -> Result<Path
             path = ?installer_path,
             "Downloading legacy installer"
         );
+        ensure_online(|| format!("Forge installer {}", installer_path.display()))?;
         download_file_untracked(&installer_url, &installer_path)
             .await
             .map_err(|e| QueryError::Conversion {
@@ -399,6 +401,7 @@ async fn legacy_library_to_pivot(
             path: Some(path),
             sha1: None,
             size: None,
+            hashes: Vec::new(),
         });
     }
 
@@ -410,6 +413,7 @@ async fn legacy_library_to_pivot(
         path: Some(path),
         sha1: None,
         size: None,
+        hashes: Vec::new(),
     })
 }
 
@@ -436,6 +440,7 @@ fn parse_legacy_arguments(s: &str, extra_jvm: Vec<String>) -> Arguments {
     Arguments {
         game: s.split_whitespace().map(String::from).collect(),
         jvm: if extra_jvm.is_empty() { None } else { Some(extra_jvm) },
+        ..Default::default()
     }
 }
 
@@ -512,11 +517,13 @@ pub async fn legacy_version_builder<V: VersionInfo>(
         client: vanilla_builder.client,
         assets_index: vanilla_builder.assets_index,
         assets: vanilla_builder.assets,
+        logging: vanilla_builder.logging,
     })
 }
 
 /// Extracts the universal JAR from the cached legacy installer to its
-/// target Maven path under `{game_dir}/libraries/`.
+/// target Maven path under the library directory
+/// ([`VersionInfo::libraries_dir`]).
 ///
 /// Must run after [`fetch_legacy_install_profile`] has cached the
 /// installer JAR on disk, and before the game is launched (the
@@ -534,7 +541,7 @@ pub async fn extract_universal_jar<V: VersionInfo>(
         });
     }
 
-    let target_path = version.game_dirs().join("libraries").join(
+    let target_path = version.libraries_dir().join(
         maven_relative_path(&profile.install.path).ok_or_else(|| QueryError::Conversion {
             message: format!("Invalid Maven coord in install.path: {}", profile.install.path),
         })?,
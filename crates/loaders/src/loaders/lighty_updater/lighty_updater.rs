@@ -6,7 +6,10 @@ use crate::utils::{error::QueryError, query::Query, manifest::ManifestRepository
 use once_cell::sync::Lazy;
 use super::lighty_metadata::{LightyMetadata, ServersResponse};
 use async_trait::async_trait;
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use serde::Serialize;
+use lighty_core::hosts::http_client;
+use lighty_core::offline::ensure_online;
+use crate::utils::disk_cache::record_response;
 
 pub type Result<T> = std::result::Result<T, QueryError>;
 
@@ -58,7 +61,8 @@ impl VersionInfo for VersionOverride {
 pub static LIGHTY_UPDATER: Lazy<ManifestRepository<LightyQuery>> = Lazy::new(|| ManifestRepository::new());
 
 /// Sub-queries supported by the LightyUpdater loader.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LightyQuery {
     /// Library overrides supplied by the LightyUpdater server.
     Libraries,
@@ -93,10 +97,12 @@ impl Query for LightyQuery {
         let server_info_url = format!("{}/", version.loader_version());
         lighty_core::trace_debug!("📡 [LightyUpdater] Fetching ServerInfo from: {}", server_info_url);
 
-        let response = CLIENT.get(&server_info_url).send().await;
+        ensure_online(|| server_info_url.clone())?;
+        let response = http_client().get(&server_info_url).send().await;
         lighty_core::trace_debug!("📡 [LightyUpdater] HTTP Response: {:?}", response.as_ref().map(|r| r.status()));
 
         let response = response?;
+        record_response(&server_info_url, &response);
         let text = response.text().await?;
         lighty_core::trace_debug!("📄 [LightyUpdater] Raw JSON response: {}", text);
 
@@ -127,10 +133,12 @@ impl Query for LightyQuery {
         let metadata_url = server_info.url();
         lighty_core::trace_debug!("[LightyUpdater] Fetching LightyMetadata from: {}", metadata_url);
 
-        let meta_response = CLIENT.get(metadata_url).send().await;
+        let meta_response = http_client().get(metadata_url).send().await;
         lighty_core::trace_debug!("[LightyUpdater] Metadata HTTP Response: {:?}", meta_response.as_ref().map(|r| r.status()));
 
-        let mut manifest: LightyMetadata = meta_response?.json().await?;
+        let meta_response = meta_response?;
+        record_response(metadata_url, &meta_response);
+        let mut manifest: LightyMetadata = meta_response.json().await?;
 
         // 3. Store server_info in the metadata to avoid a second fetch later
         manifest.server_info = Some(server_info);
@@ -290,6 +298,7 @@ fn extract_arguments(full_data: &LightyMetadata) -> Arguments {
         } else {
             Some(args.jvm.clone())
         },
+        ..Default::default()
     }
 }
 
@@ -302,6 +311,7 @@ fn extract_libraries(full_data: &LightyMetadata) -> Vec<Library> {
         path: lib.path.clone(),
         sha1: lib.sha1.clone(),
         size: lib.size,
+        hashes: Vec::new(),
     }).collect()
 }
 
@@ -314,6 +324,7 @@ fn extract_mods(full_data: &LightyMetadata) -> Vec<Mods> {
         path: Some(mod_.path.clone()),
         sha1: Some(mod_.sha1.clone()),
         size: Some(mod_.size),
+        hashes: Vec::new(),
     }).collect()
 }
 
@@ -326,6 +337,7 @@ fn extract_natives(full_data: &LightyMetadata) -> Vec<Native> {
         path: Some(native.path.clone()),
         sha1: Some(native.sha1.clone()),
         size: Some(native.size),
+        hashes: Vec::new(),
     }).collect()
 }
 
@@ -338,6 +350,7 @@ fn extract_client(full_data: &LightyMetadata) -> Client {
         path: Some(client.path.clone()),
         sha1: Some(client.sha1.clone()),
         size: Some(client.size),
+        hashes: Vec::new(),
     }
 }
 
@@ -383,6 +396,8 @@ fn merge_arguments(loader_args: Arguments, lighty_args: Arguments) -> Arguments
             (None, Some(lighty_jvm)) => Some(lighty_jvm),
             (None, None) => None,
         },
+        conditional_game: loader_args.conditional_game.into_iter().chain(lighty_args.conditional_game).collect(),
+        conditional_jvm: loader_args.conditional_jvm.into_iter().chain(lighty_args.conditional_jvm).collect(),
     }
 }
 
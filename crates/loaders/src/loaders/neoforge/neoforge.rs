@@ -1,10 +1,12 @@
 use async_trait::async_trait;
+use serde::Serialize;
 use once_cell::sync::Lazy;
 use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
 use zip::ZipArchive;
 
 use lighty_core::download::download_file_untracked;
 use lighty_core::mkdir;
+use lighty_core::offline::ensure_online;
 
 use crate::loaders::vanilla::vanilla::VanillaQuery;
 use crate::types::version_metadata::{Arguments, Library, MainClass, Version, VersionMetaData};
@@ -27,7 +29,8 @@ pub type Result<T> = std::result::Result<T, QueryError>;
 pub static NEOFORGE: Lazy<ManifestRepository<NeoForgeQuery>> = Lazy::new(|| ManifestRepository::new());
 
 /// Sub-queries supported by the NeoForge loader.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NeoForgeQuery {
     /// Library list from `install_profile.json` (processor-side libraries).
     Libraries,
@@ -82,6 +85,7 @@ impl Query for NeoForgeQuery {
 
         if needs_download {
             lighty_core::trace_info!(path = ?installer_path, loader = "neoforge", "Downloading installer");
+            ensure_online(|| format!("NeoForge installer {}", installer_path.display()))?;
             download_file_untracked(&installer_url, &installer_path)
                 .await
                 .map_err(|e| QueryError::Conversion {
@@ -150,6 +154,7 @@ impl Query for NeoForgeQuery {
             client: vanilla_builder.client,
             assets_index: vanilla_builder.assets_index,
             assets: vanilla_builder.assets,
+            logging: vanilla_builder.logging,
         })
     }
 }
@@ -179,6 +184,8 @@ fn merge_arguments(vanilla: Arguments, neoforge: Arguments) -> Arguments {
             (None, Some(n)) => Some(n),
             (None, None) => None,
         },
+        conditional_game: vanilla.conditional_game.into_iter().chain(neoforge.conditional_game).collect(),
+        conditional_jvm: vanilla.conditional_jvm.into_iter().chain(neoforge.conditional_jvm).collect(),
     }
 }
 
@@ -237,9 +244,10 @@ fn extract_arguments(version_meta: &ForgeVersionManifest) -> Arguments {
         return Arguments {
             game: args.game.clone(),
             jvm: Some(args.jvm.clone()),
+            ..Default::default()
         };
     }
-    Arguments { game: Vec::new(), jvm: None }
+    Arguments::default()
 }
 
 /// Returns the install_profile.json libraries as the launcher's pivot
@@ -259,6 +267,7 @@ pub fn extract_install_profile_libraries(full_data: &ForgeInstallProfile) -> Vec
             path: Some(lib.downloads.artifact.path.clone()),
             sha1: Some(lib.downloads.artifact.sha1.clone()),
             size: Some(lib.downloads.artifact.size),
+            hashes: Vec::new(),
         })
         .collect()
 }
@@ -273,6 +282,7 @@ fn extract_libraries_from_version_meta(version_meta: &ForgeVersionManifest) -> V
             path: Some(lib.downloads.artifact.path.clone()),
             sha1: Some(lib.downloads.artifact.sha1.clone()),
             size: Some(lib.downloads.artifact.size),
+            hashes: Vec::new(),
         })
         .collect()
 }
@@ -3,7 +3,7 @@ use crate::minecraft::version::loaders::utils::assets::Assets;
 use crate::minecraft::version::loaders::utils::librairies::Libraries;
 use crate::minecraft::version::loaders::utils::natives::Natives;
 use crate::types::VersionInfo;
-use lighty_core::hosts::HTTP_CLIENT;
+use lighty_core::hosts::http_client;
 use tokio::{fs as async_fs, fs};
 use tokio::io::AsyncWriteExt;
 use lighty_core::mkdir;
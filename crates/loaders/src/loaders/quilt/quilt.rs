@@ -1,9 +1,11 @@
 use once_cell::sync::Lazy;
 use async_trait::async_trait;
+use serde::Serialize;
 use futures::future::join_all;
 use std::collections::HashMap;
 
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use lighty_core::hosts::http_client;
+use lighty_core::offline::ensure_online;
 
 use super::quilt_metadata::QuiltMetaData;
 use crate::types::VersionInfo;
@@ -11,6 +13,7 @@ use crate::types::VersionInfo;
 use crate::loaders::vanilla::vanilla::VanillaQuery;
 use crate::utils::
 {query::Query, error::QueryError, manifest::ManifestRepository};
+use crate::utils::disk_cache::record_response;
 use crate::utils::maven::{fetch_file_size, fetch_maven_sha1};
 use crate::types::version_metadata::
 {Library, VersionMetaData, Arguments, MainClass, Version};
@@ -25,7 +28,8 @@ pub static QUILT: Lazy<ManifestRepository<QuiltQuery>> = Lazy::new(|| ManifestRe
 pub type Result<T> = std::result::Result<T, QueryError>;
 
 /// Sub-queries supported by the Quilt loader.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum QuiltQuery {
     /// Main class to launch.
     MainClass,
@@ -55,7 +59,10 @@ impl Query for QuiltQuery {
             version.loader_version()
         );
         lighty_core::trace_debug!(url = %manifest_url, loader = "quilt", "Fetching manifest");
-        let manifest: QuiltMetaData = CLIENT.get(manifest_url).send().await?.json().await?;
+        ensure_online(|| manifest_url.clone())?;
+        let response = http_client().get(&manifest_url).send().await?;
+        record_response(&manifest_url, &response);
+        let manifest: QuiltMetaData = response.json().await?;
         Ok(manifest)
     }
 
@@ -91,6 +98,7 @@ impl Query for QuiltQuery {
             client: vanilla_builder.client,
             assets_index: vanilla_builder.assets_index,
             assets: vanilla_builder.assets,
+            logging: vanilla_builder.logging,
         })
     }
 }
@@ -119,6 +127,8 @@ fn merge_arguments(vanilla: Arguments, quilt: Arguments) -> Arguments {
             (None, Some(q)) => Some(q),
             (None, None) => None,
         },
+        conditional_game: vanilla.conditional_game.into_iter().chain(quilt.conditional_game).collect(),
+        conditional_jvm: vanilla.conditional_jvm.into_iter().chain(quilt.conditional_jvm).collect(),
     }
 }
 
@@ -179,6 +189,7 @@ async fn extract_libraries(full_data: &QuiltMetaData) -> Result<Vec<Library>> {
                 path: Some(path),
                 sha1,
                 size,
+                hashes: Vec::new(),
             }
         }
     });
@@ -215,5 +226,6 @@ fn extract_arguments(full_data: &QuiltMetaData) -> Arguments {
     Arguments {
         game: full_data.arguments.game.clone(),
         jvm: None,
+        ..Default::default()
     }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use serde::Serialize;
 use once_cell::sync::Lazy;
 use lighty_core::{mkdir, verify_file_sha1};
 use lighty_core::system::{ARCHITECTURE, OS};
@@ -8,10 +9,14 @@ use crate::utils::query::Query;
 use super::vanilla_metadata::{PistonMetaManifest, VanillaAssetFile,VanillaMetaData,Rule};
 use crate::types::version_metadata::
 {VersionMetaData,JavaVersion, Library, MainClass,Native,Client,AssetIndex,Asset, Arguments,
- Version, AssetsFile
+ Version, AssetsFile, ConditionalArgument, LoggingConfig
 };
 use crate::types::VersionInfo;
-use lighty_core::hosts::{HTTP_CLIENT as CLIENT, build_fallback_urls};
+use lighty_core::hosts::{http_client, build_fallback_urls};
+use lighty_core::mirrors::MirrorPolicy;
+use crate::utils::disk_cache::record_response;
+use lighty_core::offline::ensure_online;
+use std::time::Instant;
 use lighty_core::hosts::prism_meta_url;
 use serde::de::DeserializeOwned;
 
@@ -29,7 +34,8 @@ const MINECRAFT_RESOURCES: &str = "https://resources.download.minecraft.net";
 pub static VANILLA: Lazy<ManifestRepository<VanillaQuery>> = Lazy::new(|| ManifestRepository::new());
 
 /// Sub-queries supported by the Vanilla loader.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum VanillaQuery {
     /// Required Java major version (e.g. `8`, `17`, `21`).
     JavaVersion,
@@ -127,6 +133,7 @@ impl Query for VanillaQuery {
             client: extract_client(version, full_data).ok(),
             assets_index: Some(extract_assets_index(full_data)),
             assets: Some(extract_assets(version, full_data).await?),
+            logging: extract_logging(full_data),
         })
     }
 }
@@ -143,6 +150,7 @@ fn extract_libraries(full_data: &VanillaMetaData) -> Vec<Library> {
                 path: Some(a.path.clone()),
                 sha1: Some(a.sha1.clone()),
                 size: Some(a.size),
+                hashes: Vec::new(),
             })
         })
         .collect()
@@ -206,6 +214,7 @@ fn extract_natives(full_data: &VanillaMetaData) -> Result<Vec<Native>> {
                                 path: Some(a.path.clone()),
                                 sha1: Some(a.sha1.clone()),
                                 size: Some(a.size),
+                                hashes: Vec::new(),
                             });
                         }
                     }
@@ -233,6 +242,7 @@ fn extract_natives(full_data: &VanillaMetaData) -> Result<Vec<Native>> {
                                     path: Some(artifact.path.clone()),
                                     sha1: Some(artifact.sha1.clone()),
                                     size: Some(artifact.size),
+                                    hashes: Vec::new(),
                                 });
                             }
                         }
@@ -298,7 +308,7 @@ async fn extract_assets<V: VersionInfo>(version: &V, full_data: &VanillaMetaData
     let asset_index = &full_data.asset_index;
 
     // Create assets/indexes directory
-    let indexes_dir = version.game_dirs().join("assets").join("indexes");
+    let indexes_dir = version.assets_dir().join("indexes");
     mkdir!(indexes_dir);
 
     // Index file path (e.g. assets/indexes/1.7.10.json or 26.json)
@@ -323,9 +333,10 @@ async fn extract_assets<V: VersionInfo>(version: &V, full_data: &VanillaMetaData
 
     // Download if needed
     if needs_download {
+        ensure_online(|| format!("asset index {}", asset_index.id))?;
         lighty_core::trace_info!("[Assets] Downloading index {} from {}", asset_index.id, asset_index.url);
 
-        let response = CLIENT.get(&asset_index.url).send().await?;
+        let response = http_client().get(&asset_index.url).send().await?;
 
         if !response.status().is_success() {
             return Err(QueryError::Conversion {
@@ -386,12 +397,30 @@ fn extract_client<V: VersionInfo>(version: &V, full_data: &VanillaMetaData) -> R
             path: Some(format!("{}.jar", version.name())),
             sha1: Some(client.sha1.clone()),
             size: Some(client.size),
+            hashes: Vec::new(),
         })
         .ok_or_else(|| QueryError::MissingField {
             field: CLIENT_NAME.into(),
         })
 }
 
+/// --------- Logging ----------
+fn extract_logging(full_data: &VanillaMetaData) -> Option<LoggingConfig> {
+    let client = full_data.logging.as_ref()?.client.as_ref()?;
+    // Only the XML layout is understood by the console parser
+    if client.r#type != "log4j2-xml" {
+        return None;
+    }
+
+    Some(LoggingConfig {
+        argument: client.argument.clone(),
+        id: client.file.id.clone(),
+        url: client.file.url.clone(),
+        sha1: client.file.sha1.clone(),
+        size: client.file.size,
+    })
+}
+
 /// --------- Arguments ----------
 fn extract_arguments(full_data: &VanillaMetaData) -> Arguments {
     if let Some(args) = &full_data.arguments {
@@ -406,35 +435,61 @@ fn extract_arguments(full_data: &VanillaMetaData) -> Arguments {
                     .filter_map(|a| a.as_str().map(String::from))
                     .collect(),
             ),
+            conditional_game: args.game.iter().filter_map(extract_conditional_argument).collect(),
+            conditional_jvm: args.jvm.iter().filter_map(extract_conditional_argument).collect(),
         }
     } else if let Some(legacy) = &full_data.minecraft_arguments {
         Arguments {
             game: legacy.split_whitespace().map(String::from).collect(),
             jvm: None,
+            ..Default::default()
         }
     } else {
-        Arguments {
-            game: vec![],
-            jvm: None,
-        }
+        Arguments::default()
     }
 }
 
+/// Parses a `{"rules": [...], "value": "..." | [...]}` manifest argument.
+/// Plain strings return `None`; they are taken as-is by [`extract_arguments`].
+fn extract_conditional_argument(argument: &serde_json::Value) -> Option<ConditionalArgument> {
+    let object = argument.as_object()?;
+    let rules = serde_json::from_value(object.get("rules")?.clone())
+        .map_err(|_e| lighty_core::trace_warn!(error = %_e, "Skipping argument with malformed rules"))
+        .ok()?;
+    let value = match object.get("value")? {
+        serde_json::Value::String(value) => vec![value.clone()],
+        serde_json::Value::Array(values) => values
+            .iter()
+            .filter_map(|v| v.as_str().map(String::from))
+            .collect(),
+        _ => return None,
+    };
+    Some(ConditionalArgument { rules, value })
+}
+
 async fn fetch_json_with_fallback<T: DeserializeOwned>(url: &str) -> Result<T> {
+    ensure_online(|| url.to_string())?;
     let mut last_error = None;
 
     for candidate in build_fallback_urls(url) {
-        match CLIENT.get(&candidate).send().await {
+        let started = Instant::now();
+        match http_client().get(&candidate).send().await {
             Ok(response) => match response.error_for_status() {
-                Ok(response) => match response.json::<T>().await {
-                    Ok(value) => return Ok(value),
-                    Err(e) => {
-                        last_error = Some(format!(
-                            "JSON parse error for {}: {}",
-                            candidate, e
-                        ));
+                Ok(response) => {
+                    record_response(&candidate, &response);
+                    match response.json::<T>().await {
+                        Ok(value) => {
+                            MirrorPolicy::global().record_success(&candidate, started.elapsed());
+                            return Ok(value);
+                        }
+                        Err(e) => {
+                            last_error = Some(format!(
+                                "JSON parse error for {}: {}",
+                                candidate, e
+                            ));
+                        }
                     }
-                },
+                }
                 Err(e) => {
                     last_error = Some(format!("HTTP error for {}: {}", candidate, e));
                 }
@@ -443,6 +498,7 @@ async fn fetch_json_with_fallback<T: DeserializeOwned>(url: &str) -> Result<T> {
                 last_error = Some(format!("Request error for {}: {}", candidate, e));
             }
         }
+        MirrorPolicy::global().record_failure(&candidate);
     }
 
     Err(QueryError::Conversion {
@@ -14,7 +14,7 @@ use std::sync::RwLock;
 
 use once_cell::sync::Lazy;
 
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use lighty_core::hosts::http_client;
 
 use crate::types::version_metadata::Mods;
 use crate::types::Loader;
@@ -106,7 +106,7 @@ async fn fetch_pinned_file(
     api_key: &str,
 ) -> Result<CurseForgeFile, QueryError> {
     let url = format!("{}/mods/{}/files/{}", BASE_URL, mod_id, file_id);
-    let response = CLIENT.get(&url).header("x-api-key", api_key).send().await?;
+    let response = http_client().get(&url).header("x-api-key", api_key).send().await?;
     if response.status() == reqwest::StatusCode::NOT_FOUND {
         return Err(QueryError::ModNotFound {
             provider: PROVIDER,
@@ -135,7 +135,7 @@ async fn fetch_latest_compatible(
         mod_loader_code
     );
 
-    let response = CLIENT.get(&url).header("x-api-key", api_key).send().await?;
+    let response = http_client().get(&url).header("x-api-key", api_key).send().await?;
     if response.status() == reqwest::StatusCode::NOT_FOUND {
         return Err(QueryError::ModNotFound {
             provider: PROVIDER,
@@ -179,6 +179,7 @@ fn into_pivot(file: &CurseForgeFile) -> Result<Mods, QueryError> {
         path: Some(file.file_name.clone()),
         sha1,
         size: (file.file_length > 0).then_some(file.file_length),
+        hashes: Vec::new(),
     })
 }
 
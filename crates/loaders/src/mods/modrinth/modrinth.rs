@@ -9,7 +9,8 @@
 //!
 //! Wire-format structs live in [`super::modrinth_metadata`].
 
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use lighty_core::hash::ExpectedHash;
+use lighty_core::hosts::http_client;
 
 use crate::types::version_metadata::Mods;
 use crate::types::Loader;
@@ -79,7 +80,7 @@ fn loader_tag(loader: &Loader) -> Result<&'static str, QueryError> {
 
 async fn fetch_pinned_version(version_id: &str) -> Result<ModrinthVersion, QueryError> {
     let url = format!("{}/version/{}", BASE_URL, version_id);
-    let response = CLIENT.get(&url).header("User-Agent", USER_AGENT).send().await?;
+    let response = http_client().get(&url).header("User-Agent", USER_AGENT).send().await?;
     if response.status() == reqwest::StatusCode::NOT_FOUND {
         return Err(QueryError::ModNotFound {
             provider: PROVIDER,
@@ -104,7 +105,7 @@ async fn fetch_latest_compatible(
         BASE_URL, slug, loaders_param, game_versions_param
     );
 
-    let response = CLIENT.get(&url).header("User-Agent", USER_AGENT).send().await?;
+    let response = http_client().get(&url).header("User-Agent", USER_AGENT).send().await?;
     if response.status() == reqwest::StatusCode::NOT_FOUND {
         return Err(QueryError::ModNotFound {
             provider: PROVIDER,
@@ -138,6 +139,7 @@ fn into_pivot(slug: &str, version: &ModrinthVersion) -> Result<Mods, QueryError>
         path: Some(file.filename.clone()),
         sha1: Some(file.hashes.sha1.clone()),
         size: Some(file.size),
+        hashes: file.hashes.sha512.iter().map(ExpectedHash::sha512).collect(),
     })
 }
 
@@ -38,6 +38,8 @@ pub struct ModrinthFile {
 #[derive(Debug, Deserialize)]
 pub struct ModrinthHashes {
     pub sha1: String,
+    #[serde(default)]
+    pub sha512: Option<String>,
 }
 
 /// One declared dependency.
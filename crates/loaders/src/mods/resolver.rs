@@ -13,6 +13,7 @@ use std::collections::{HashSet, VecDeque};
 use crate::types::version_metadata::Mods;
 use crate::types::Loader;
 use crate::utils::error::QueryError;
+use lighty_core::offline::ensure_online;
 
 use super::request::{ModKey, ModRequest};
 
@@ -45,6 +46,8 @@ pub async fn resolve(
             continue;
         }
 
+        ensure_online(|| format!("mod {}:{}", key.source.as_str(), key.id))?;
+
         if let Some(cb) = callbacks {
             (cb.on_fetch)(key.source.as_str(), &key.id);
         }
@@ -1,5 +1,7 @@
 use std::path::{Path, PathBuf};
 
+use lighty_core::store::shared_store;
+
 use crate::mods::request::ModRequest;
 
 /// Generic view of an installable instance.
@@ -72,6 +74,30 @@ pub trait VersionInfo: Clone + Send + Sync {
     /// don't track a runtime override keep the default no-op.
     fn set_runtime_dir(&mut self, _path: PathBuf) {}
 
+    /// Maven libraries directory — the value passed as
+    /// `${library_directory}`.
+    ///
+    /// Default: the [shared store](lighty_core::store)'s `libraries/`
+    /// when one is enabled, `game_dirs/libraries` otherwise.
+    fn libraries_dir(&self) -> PathBuf {
+        match shared_store() {
+            Some(store) => store.libraries_dir(),
+            None => self.game_dirs().join("libraries"),
+        }
+    }
+
+    /// Assets root (`indexes/`, `objects/`) — the value passed as
+    /// `${assets_root}`.
+    ///
+    /// Default: the [shared store](lighty_core::store)'s `assets/`
+    /// when one is enabled, `game_dirs/assets` otherwise.
+    fn assets_dir(&self) -> PathBuf {
+        match shared_store() {
+            Some(store) => store.assets_dir(),
+            None => self.game_dirs().join("assets"),
+        }
+    }
+
     /// Returns whether the game directory exists on disk.
     fn game_dir_exists(&self) -> bool {
         self.game_dirs().exists()
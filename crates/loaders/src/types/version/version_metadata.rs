@@ -1,9 +1,10 @@
 use std::collections::HashMap;
+use lighty_core::hash::ExpectedHash;
 use serde::{Deserialize, Serialize};
 
 
 /// Universal pivot type shared by every loader (Vanilla, Fabric, Forge, ...).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum VersionMetaData {
     JavaVersion(JavaVersion),
     MainClass(MainClass),
@@ -18,7 +19,7 @@ pub enum VersionMetaData {
 }
 
 /// Full set of metadata required to install and launch a Minecraft version.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Version {
     pub main_class: MainClass,
     pub java_version: JavaVersion,
@@ -29,25 +30,70 @@ pub struct Version {
     pub client: Option<Client>,
     pub assets_index: Option<AssetIndex>,
     pub assets: Option<AssetsFile>,
+    /// Log4j configuration of the client, when the manifest has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingConfig>,
 }
 
 /// Game main class name (e.g. `net.minecraft.client.main.Main`).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MainClass {
     pub main_class: String,
 }
 
 /// Required Java major version (e.g. `8`, `17`, `21`).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JavaVersion {
     pub major_version: u8,
 }
 
 /// Game and JVM argument lists, post-placeholder substitution.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Arguments {
     pub game: Vec<String>,
     pub jvm: Option<Vec<String>>,
+    /// Rule-gated game arguments, evaluated at launch and appended after `game`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditional_game: Vec<ConditionalArgument>,
+    /// Rule-gated JVM arguments, evaluated at launch and placed before `jvm`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditional_jvm: Vec<ConditionalArgument>,
+}
+
+/// Manifest argument only used when its `rules` allow it
+/// (e.g. `--width ${resolution_width}` behind `has_custom_resolution`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalArgument {
+    pub rules: Vec<ArgumentRule>,
+    /// One or more argv entries, kept together
+    pub value: Vec<String>,
+}
+
+/// One entry of a Mojang `rules` array. The last matching rule decides;
+/// with none matching, the argument is left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentRule {
+    /// `"allow"` or `"disallow"`
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<OsRule>,
+    /// Launch features that must be on (`true`) or off (`false`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub features: HashMap<String, bool>,
+}
+
+/// OS condition of an [`ArgumentRule`]; every field set must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsRule {
+    /// `"windows"`, `"linux"` or `"osx"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Regex matched against the OS version (e.g. `"^10\\."`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// `"x86"` (32-bit), `"x64"`, `"arm"` or `"aarch64"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
 }
 
 /// Runtime classpath library entry.
@@ -58,6 +104,9 @@ pub struct Library {
     pub path: Option<String>,
     pub sha1: Option<String>,
     pub size: Option<u64>,
+    /// See [`Digests::hashes`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<ExpectedHash>,
 }
 
 /// Mod JAR entry (used by `LightyUpdater` instances).
@@ -68,6 +117,9 @@ pub struct Mods {
     pub path: Option<String>,
     pub sha1: Option<String>,
     pub size: Option<u64>,
+    /// See [`Digests::hashes`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<ExpectedHash>,
 }
 
 /// Native library entry (per-OS shared object inside a Maven JAR).
@@ -78,6 +130,9 @@ pub struct Native {
     pub path: Option<String>,
     pub sha1: Option<String>,
     pub size: Option<u64>,
+    /// See [`Digests::hashes`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<ExpectedHash>,
 }
 
 /// Game client JAR entry.
@@ -88,6 +143,56 @@ pub struct Client {
     pub path: Option<String>,
     pub sha1: Option<String>,
     pub size: Option<u64>,
+    /// See [`Digests::hashes`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<ExpectedHash>,
+}
+
+/// Digests a downloadable entry ([`Library`], [`Mods`], [`Native`],
+/// [`Client`]) is checked against.
+pub trait Digests {
+    /// Mojang-style SHA-1, when the source has one.
+    fn sha1(&self) -> Option<&str>;
+
+    /// Additional digests published by the source (e.g. Modrinth's SHA-512).
+    fn hashes(&self) -> &[ExpectedHash];
+
+    /// Strongest hash available for this entry, `sha1` included.
+    fn strongest_hash(&self) -> Option<ExpectedHash> {
+        let sha1 = self.sha1().map(ExpectedHash::sha1);
+        ExpectedHash::strongest(self.hashes().iter().chain(sha1.as_ref())).cloned()
+    }
+}
+
+macro_rules! impl_digests {
+    ($($entry:ty),*) => {
+        $(
+            impl Digests for $entry {
+                fn sha1(&self) -> Option<&str> {
+                    self.sha1.as_deref()
+                }
+
+                fn hashes(&self) -> &[ExpectedHash] {
+                    &self.hashes
+                }
+            }
+        )*
+    };
+}
+
+impl_digests!(Library, Mods, Native, Client);
+
+/// Log4j configuration file the client is started with (`logging.client`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// JVM argument, `${path}` standing for the downloaded file
+    /// (e.g. `-Dlog4j.configurationFile=${path}`)
+    pub argument: String,
+    /// File name (e.g. `client-1.12.xml`)
+    pub id: String,
+    pub url: String,
+    pub sha1: String,
+    pub size: u64,
 }
 
 /// Asset-index descriptor (used to fetch the actual asset list).
@@ -0,0 +1,195 @@
+//! On-disk layer behind [`ManifestRepository`](super::manifest::ManifestRepository).
+//!
+//! Every extracted query result is stored as JSON under
+//! `<cache_dir>/metadata/<loader>/<sha1(key)>.json`, together with the
+//! HTTP validators (`ETag` / `Last-Modified`) of the manifests it was built
+//! from. Fetchers report those validators through [`record_response`] while
+//! running inside [`record`], so no fetcher needs to know about the cache.
+//!
+//! Entries are served as-is while younger than the query TTL, revalidated
+//! with conditional requests afterwards, and kept as a stale fallback when
+//! the network is unavailable.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::future::join_all;
+use lighty_core::hosts::http_client;
+use lighty_core::persist::write_atomic;
+use lighty_core::{calculate_sha1_bytes, AppState};
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+tokio::task_local! {
+    static RECORDED: RefCell<Vec<Validator>>;
+}
+
+/// HTTP validators of one manifest URL.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validator {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validator {
+    fn is_usable(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+/// Records the validators of a successful manifest response, if a
+/// [`record`] scope is active. No-op otherwise.
+pub fn record_response(url: &str, response: &reqwest::Response) {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let validator = Validator {
+        url: url.to_string(),
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    let _ = RECORDED.try_with(|recorded| push_unique(&mut recorded.borrow_mut(), validator));
+}
+
+/// Runs `fut`, collecting every validator reported by [`record_response`]
+/// along the way. Validators also bubble up to an enclosing scope, so a
+/// loader built on top of vanilla inherits vanilla's manifests.
+pub async fn record<F: Future>(fut: F) -> (F::Output, Vec<Validator>) {
+    let (output, recorded) = RECORDED
+        .scope(RefCell::new(Vec::new()), async {
+            let output = fut.await;
+            (output, RECORDED.with(|recorded| recorded.take()))
+        })
+        .await;
+
+    let _ = RECORDED.try_with(|outer| {
+        let mut outer = outer.borrow_mut();
+        for validator in &recorded {
+            push_unique(&mut outer, validator.clone());
+        }
+    });
+
+    (output, recorded)
+}
+
+/// Adds `validator`, replacing any older record of the same URL.
+pub(crate) fn push_unique(validators: &mut Vec<Validator>, validator: Validator) {
+    validators.retain(|v| v.url != validator.url);
+    validators.push(validator);
+}
+
+/// Asks the origin whether every manifest behind an entry is unchanged.
+///
+/// `true` only if each URL answers `304 Not Modified`. Entries without
+/// usable validators can't be revalidated and count as changed.
+pub async fn revalidate(validators: &[Validator]) -> bool {
+    if validators.is_empty() || !validators.iter().all(Validator::is_usable) {
+        return false;
+    }
+
+    let checks = validators.iter().map(|validator| async move {
+        let mut request = http_client().get(&validator.url);
+        if let Some(etag) = &validator.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validator.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        matches!(request.send().await, Ok(response) if response.status() == StatusCode::NOT_MODIFIED)
+    });
+
+    join_all(checks).await.into_iter().all(|unchanged| unchanged)
+}
+
+/// A cached query result as stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiskEntry<T> {
+    /// Human-readable key: the JSON form of the [`QueryKey`](super::query::QueryKey).
+    pub key: String,
+    /// Unix time of the last fetch or successful revalidation.
+    pub stored_at: u64,
+    pub validators: Vec<Validator>,
+    pub data: T,
+}
+
+impl<T> DiskEntry<T> {
+    /// Whether the entry is younger than `ttl`.
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        now_secs().saturating_sub(self.stored_at) < ttl.as_secs()
+    }
+}
+
+/// Per-loader cache directory under `AppState::cache_dir()`.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Opens the cache for `loader`. `None` when [`AppState`] hasn't been
+    /// initialised: the repository then behaves as a memory-only cache.
+    pub fn open(loader: &str) -> Option<Self> {
+        let paths = AppState::try_paths().ok()?;
+        Some(Self { dir: paths.cache_dir.join("metadata").join(loader) })
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", calculate_sha1_bytes(key.as_bytes())))
+    }
+
+    /// Reads the entry for `key`. Unreadable or outdated-schema entries
+    /// are treated as missing.
+    pub async fn load<T: DeserializeOwned>(&self, key: &str) -> Option<DiskEntry<T>> {
+        let bytes = tokio::fs::read(self.entry_path(key)).await.ok()?;
+        let entry: DiskEntry<T> = serde_json::from_slice(&bytes).ok()?;
+        (entry.key == key).then_some(entry)
+    }
+
+    /// Writes the entry for `key`, stamped with the current time.
+    /// Best-effort: failures only cost a refetch on the next start.
+    pub async fn store<T: Serialize>(&self, key: &str, data: &T, validators: &[Validator]) {
+        let entry = DiskEntry {
+            key: key.to_string(),
+            stored_at: now_secs(),
+            validators: validators.to_vec(),
+            data,
+        };
+
+        let path = self.entry_path(key);
+        let result = async {
+            let bytes = serde_json::to_vec(&entry)?;
+            tokio::fs::create_dir_all(&self.dir).await?;
+            write_atomic(&path, &bytes).await?;
+            Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+        }
+        .await;
+
+        if let Err(_e) = result {
+            lighty_core::trace_warn!("[Cache] Failed to persist metadata {}: {}", key, _e);
+        }
+    }
+
+    /// Deletes every entry of this loader.
+    pub async fn purge(&self) -> std::io::Result<()> {
+        match tokio::fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -12,6 +12,12 @@ pub enum QueryError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error), 
 
+    #[error(transparent)]
+    OfflineUnavailable(#[from] lighty_core::OfflineUnavailable),
+
+    #[error(transparent)]
+    Cancelled(#[from] lighty_core::Cancelled),
+
     #[error("Version '{version}' not found in manifest")]
     VersionNotFound { version: String },
 
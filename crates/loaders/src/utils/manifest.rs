@@ -1,11 +1,29 @@
 use crate::utils::error::QueryError;
 use crate::utils::query::{Query, QueryKey};
 use crate::utils::cache::Cache;
+use crate::utils::disk_cache::{self, DiskCache, Validator};
 use crate::types::VersionInfo;
+use lighty_core::offline::is_offline;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub type Result<T> = std::result::Result<T, QueryError>;
 
+/// Raw manifest plus the validators of the responses it was built from.
+struct RawEntry<R> {
+    raw: Arc<R>,
+    validators: Arc<Vec<Validator>>,
+}
+
+impl<R> Clone for RawEntry<R> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: Arc::clone(&self.raw),
+            validators: Arc::clone(&self.validators),
+        }
+    }
+}
+
 /// Caches `Query` results so each remote manifest is fetched at most once
 /// per TTL window.
 ///
@@ -14,12 +32,22 @@ pub type Result<T> = std::result::Result<T, QueryError>;
 ///   [`Query::fetch_full_data`] returns) keyed by instance name.
 /// - `query_cache` keeps the extracted sub-query results keyed by
 ///   `(instance, sub-query)`.
+/// - Below both, a [`DiskCache`] under `AppState::cache_dir()` persists
+///   extracted results across restarts, keyed by the same [`QueryKey`].
+///   Fresh entries (younger than the query TTL) are served directly,
+///   older ones are revalidated with `ETag` / `Last-Modified`, and a
+///   stale entry is still served when refetching fails.
 ///
 /// Concurrent calls for the same key share a single fetch via the inner
 /// [`Cache::get_or_try_insert_with`] mechanism.
+///
+/// In [offline mode](lighty_core::offline) disk entries are served
+/// whatever their age; a miss falls through to the fetchers, which fail
+/// with [`QueryError::OfflineUnavailable`] unless everything they need is
+/// already on disk.
 pub struct ManifestRepository<F: Query> {
     query_cache: Arc<Cache<QueryKey<F::Query>, Arc<F::Data>>>,
-    raw_version_cache: Arc<Cache<String, Arc<<F as Query>::Raw>>>,
+    raw_version_cache: Arc<Cache<String, RawEntry<<F as Query>::Raw>>>,
     _marker: std::marker::PhantomData<F>,
 }
 
@@ -49,16 +77,10 @@ impl<F: Query> ManifestRepository<F> {
             .query_cache
             .get_or_try_insert_with(key.clone(), ttl, || {
                 let version = version.clone();
-                let query = query.clone();
                 let repo = self.clone();
 
                 async move {
-                    // Get cached version data - propagate errors
-                    let full_data = repo.get_cached_version_data(&version).await?;
-
-                    // Extract data - propagate errors
-                    let data = F::extract(&version, &query, &full_data).await?;
-
+                    let data = repo.load_or_fetch(&version, &key, ttl).await?;
                     Ok::<Arc<F::Data>, QueryError>(Arc::new(data))
                 }
             })
@@ -68,10 +90,83 @@ impl<F: Query> ManifestRepository<F> {
     }
 
     pub async fn get_raw<V: VersionInfo>(&self, version: &V) -> Result<Arc<<F as Query>::Raw>> {
-        self.get_cached_version_data(version).await
+        Ok(self.get_cached_version_data(version).await?.raw)
+    }
+
+    /// Serves `key` from disk when possible, otherwise fetches, extracts
+    /// and persists it.
+    async fn load_or_fetch<V: VersionInfo>(
+        &self,
+        version: &V,
+        key: &QueryKey<F::Query>,
+        ttl: Duration,
+    ) -> Result<F::Data> {
+        let disk = DiskCache::open(F::name());
+        // e.g. `{"version":"vanilla-1.21.1","query":"libraries"}`
+        let disk_key = serde_json::to_string(key).map_err(|e| QueryError::Conversion {
+            message: format!("Failed to serialize cache key: {}", e),
+        })?;
+
+        let cached = match &disk {
+            Some(disk) => disk.load::<F::Data>(&disk_key).await,
+            None => None,
+        };
+
+        if let (Some(disk), Some(entry)) = (&disk, &cached) {
+            if is_offline() || entry.is_fresh(ttl) {
+                lighty_core::trace_debug!(key = %disk_key, "Metadata served from disk cache");
+                return Ok(entry.data.clone());
+            }
+            if disk_cache::revalidate(&entry.validators).await {
+                lighty_core::trace_debug!(key = %disk_key, "Metadata revalidated (304)");
+                disk.store(&disk_key, &entry.data, &entry.validators).await;
+                return Ok(entry.data.clone());
+            }
+        }
+
+        match self.fetch_and_extract(version, &key.query).await {
+            Ok((data, validators)) => {
+                if let Some(disk) = &disk {
+                    disk.store(&disk_key, &data, &validators).await;
+                }
+                Ok(data)
+            }
+            Err(e) => match cached {
+                Some(entry) => {
+                    lighty_core::trace_warn!(
+                        "[Cache] Refreshing {} failed ({}); serving stale metadata",
+                        disk_key,
+                        e
+                    );
+                    Ok(entry.data)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Extracts `query` from the (memory-cached) raw manifest, returning
+    /// the validators of every manifest involved.
+    async fn fetch_and_extract<V: VersionInfo>(
+        &self,
+        version: &V,
+        query: &F::Query,
+    ) -> Result<(F::Data, Vec<Validator>)> {
+        let (result, mut validators) = disk_cache::record(async {
+            let entry = self.get_cached_version_data(version).await?;
+            let data = F::extract(version, query, &entry.raw).await?;
+            Ok::<_, QueryError>((data, entry.validators))
+        })
+        .await;
+
+        let (data, raw_validators) = result?;
+        for validator in raw_validators.iter() {
+            disk_cache::push_unique(&mut validators, validator.clone());
+        }
+        Ok((data, validators))
     }
-    
-    async fn get_cached_version_data<V: VersionInfo>(&self, version: &V) -> Result<Arc<<F as Query>::Raw>> {
+
+    async fn get_cached_version_data<V: VersionInfo>(&self, version: &V) -> Result<RawEntry<<F as Query>::Raw>> {
         let ttl = F::cache_ttl();
         let key = version.full_identifier();
 
@@ -80,7 +175,11 @@ impl<F: Query> ManifestRepository<F> {
             .get_or_try_insert_with(key.clone(), ttl, || {
                 let version = version.clone();
                 async move {
-                    F::fetch_full_data(&version).await.map(Arc::new)
+                    let (raw, validators) = disk_cache::record(F::fetch_full_data(&version)).await;
+                    raw.map(|raw| RawEntry {
+                        raw: Arc::new(raw),
+                        validators: Arc::new(validators),
+                    })
                 }
             })
             .await?;
@@ -88,11 +187,21 @@ impl<F: Query> ManifestRepository<F> {
         Ok(data)
     }
 
+    /// Clears the in-memory caches. The on-disk layer is kept; see
+    /// [`Self::purge_disk_cache`].
     pub async fn clear_cache(&self) {
         self.query_cache.clear().await;
         self.raw_version_cache.clear().await;
     }
 
+    /// Deletes this loader's on-disk metadata entries.
+    pub async fn purge_disk_cache(&self) -> std::io::Result<()> {
+        match DiskCache::open(F::name()) {
+            Some(disk) => disk.purge().await,
+            None => Ok(()),
+        }
+    }
+
     pub async fn cache_len(&self) -> (usize, usize) {
         let queries = self.query_cache.len().await;
         let raw = self.raw_version_cache.len().await;
@@ -112,4 +221,4 @@ impl<F: Query> Clone for ManifestRepository<F> {
             _marker: std::marker::PhantomData,
         }
     }
-}
\ No newline at end of file
+}
@@ -7,7 +7,8 @@
 //! (Fabric, Quilt, Forge, NeoForge). Centralized here so each loader
 //! doesn't reimplement the same two HTTP probes.
 
-use lighty_core::hosts::HTTP_CLIENT as CLIENT;
+use lighty_core::hosts::http_client;
+use lighty_core::offline::is_offline;
 
 /// Fetches the expected SHA1 of a Maven artifact from its `.sha1` sidecar.
 ///
@@ -20,9 +21,12 @@ use lighty_core::hosts::HTTP_CLIENT as CLIENT;
 /// the Forge-family CDNs (Cloudflare in front of JFrog) strip custom
 /// checksum headers; the sidecar is the only authoritative source.
 pub async fn fetch_maven_sha1(jar_url: &str) -> Option<String> {
+    if is_offline() {
+        return None;
+    }
     let sha1_url = format!("{}.sha1", jar_url);
 
-    match CLIENT.get(&sha1_url).send().await {
+    match http_client().get(&sha1_url).send().await {
         Ok(response) if response.status().is_success() => {
             response.text().await.ok().and_then(|text| {
                 let sha1 = text.trim().split_whitespace().next()?.to_string();
@@ -38,7 +42,10 @@ pub async fn fetch_maven_sha1(jar_url: &str) -> Option<String> {
 /// Reads the `Content-Length` response header. Returns `None` when the
 /// server doesn't provide the header or the request fails.
 pub async fn fetch_file_size(url: &str) -> Option<u64> {
-    CLIENT
+    if is_offline() {
+        return None;
+    }
+    http_client()
         .head(url)
         .send()
         .await
@@ -72,9 +79,12 @@ pub async fn fetch_maven_metadata(url: &str) -> (Option<String>, Option<u64>) {
 /// `bases` must already have a trailing `/`. Returns the full URL on
 /// success, `None` if every base 404s or returns an empty body.
 pub async fn probe_maven_bases(bases: &[&str], relative_path: &str) -> Option<String> {
+    if is_offline() {
+        return None;
+    }
     for base in bases {
         let url = format!("{}{}", base, relative_path);
-        if let Ok(resp) = CLIENT.head(&url).send().await {
+        if let Ok(resp) = http_client().head(&url).send().await {
             if resp.status().is_success() {
                 // Treat zero-byte responses as not-found: some CDNs answer
                 // 200 with an empty body when the artifact is missing.
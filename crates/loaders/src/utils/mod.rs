@@ -3,12 +3,14 @@
 //! [`query::Query`] is the trait each loader implements,
 //! [`manifest::ManifestRepository`] is the cached generic repository that
 //! wraps it, [`cache::Cache`] is the TTL-keyed async cache with
-//! thundering-herd protection, and [`error::QueryError`] is the unified
-//! error type returned by every loader operation.
+//! thundering-herd protection, [`disk_cache`] persists query results
+//! across restarts, and [`error::QueryError`] is the unified error type
+//! returned by every loader operation.
 
 pub mod manifest;
 pub mod error;
 pub mod cache;
+pub mod disk_cache;
 pub mod query;
 pub mod maven;
 #[cfg(any(feature = "neoforge", feature = "forge"))]
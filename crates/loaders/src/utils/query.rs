@@ -4,6 +4,8 @@ use std::time::Duration;
 use crate::utils::error::QueryError;
 use async_trait::async_trait;
 use std::hash::Hash;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 pub type Result<T> = std::result::Result<T, QueryError>;
 
@@ -15,10 +17,12 @@ pub type Result<T> = std::result::Result<T, QueryError>;
 #[async_trait]
 pub trait Query: Send + Sync {
     /// Sub-query discriminator (e.g. `VanillaQuery`, `FabricQuery`).
-    type Query: Eq + Hash + Clone + Send + Sync + 'static;
+    /// Serialized into the on-disk cache key.
+    type Query: Eq + Hash + Clone + Serialize + Send + Sync + 'static;
 
     /// Extracted payload returned to callers — typically [`VersionMetaData`].
-    type Data: Clone + Send + Sync + 'static;
+    /// Serializable so the repository can persist it on disk.
+    type Data: Clone + Serialize + DeserializeOwned + Send + Sync + 'static;
 
     /// Raw manifest type returned by [`Self::fetch_full_data`]
     /// (typically a JSON-deserialized struct).
@@ -50,7 +54,8 @@ pub trait Query: Send + Sync {
 
 
 /// Cache key combining instance name and sub-query discriminator.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Its JSON form is the on-disk cache key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct QueryKey<Q> {
     pub version: String,
     pub query: Q,
@@ -32,7 +32,7 @@ impl LightyVersionBuilder {
             minecraft_version: None,
             loader: None,
             game_dirs: AppState::data_dir().join(name),
-            java_dirs: AppState::config_dir().join("jre"),
+            java_dirs: AppState::java_dir().to_path_buf(),
         }
     }
 }
@@ -12,10 +12,11 @@ use lighty_loaders::types::VersionInfo;
 /// Default directories are derived from the global [`AppState`]:
 /// - `game_dirs`   = `AppState::data_dir().join(name)`
 /// - `runtime_dir` = alias of `game_dirs` until overridden
-/// - `java_dirs`   = `AppState::config_dir().join("jre")`
+/// - `java_dirs`   = `AppState::java_dir()` (`<config_dir>/jre` by default)
 ///
-/// Call [`AppState::init`] once at startup before constructing any
-/// `VersionBuilder`.
+/// Call [`AppState::init`] (or [`AppState::init_portable`] /
+/// [`AppState::init_with_paths`]) once at startup before constructing
+/// any `VersionBuilder`.
 ///
 /// # Example
 /// ```rust
@@ -68,7 +69,7 @@ impl<L> VersionBuilder<L> {
         minecraft_version: &str,
     ) -> Self {
         let game_dirs = AppState::data_dir().join(name);
-        let java_dirs = AppState::config_dir().join("jre");
+        let java_dirs = AppState::java_dir().to_path_buf();
         Self {
             name: name.to_string(),
             loader,
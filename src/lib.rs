@@ -95,8 +95,15 @@ pub mod event {
         CoreEvent,
         InstanceLaunchedEvent,
         InstanceExitedEvent,
+        InstanceCrashedEvent,
+        CrashCategory,
+        InstanceReadyEvent,
         ConsoleOutputEvent,
+        GameLogEvent,
+        GameLogLevel,
         InstanceDeletedEvent,
+        InstanceShutdownEvent,
+        ShutdownStage,
         ConsoleStream,
         EventReceiveError,
         EventTryReceiveError,
@@ -121,7 +128,7 @@ pub mod java {
     pub use lighty_java::{
         JavaDistribution,
         DistributionSelection,
-        runtime::JavaRuntime,
+        runtime::{JavaRuntime, ProcessOptions},
         jre_downloader,
         JreError,
         JreResult,
@@ -148,14 +155,23 @@ pub mod launch {
     //! - Process spawning
 
     pub use lighty_launch::{
-        launch::{Launch, LaunchBuilder, LaunchConfig},
+        launch::{
+            Launch, LaunchBuilder, LaunchConfig, LaunchPlan, QuickPlay, QuickPlayKind, QuickPlayLogEntry,
+            ScriptKind, ScriptToken,
+        },
         installer::{
             Installer,
-            config::{DownloaderConfig, init_downloader_config},
+            VerifyReport,
+            config::{DownloaderConfig, init_downloader_config, update_downloader_config},
+            gc::{GarbageCollector, GcEntry, GcKind, GcMode, GcReport},
         },
-        arguments::Arguments as LaunchArguments,
+        arguments::{ArgumentOptions, Arguments as LaunchArguments, LaunchFeature},
         errors::{InstallerError, InstallerResult},
-        instance::{InstanceControl, InstanceError, InstanceResult},
+        instance::{
+            ConsoleLine, ConsoleLines, ConsoleSource, GameHandle, InstanceControl, InstanceError,
+            InstanceResult, InstanceStats, LaunchRecord, LogLevel, LogRecord, LogRecords, CrashKind, CrashReport,
+            Readiness, DEFAULT_GRACE_PERIOD, DEFAULT_READY_TIMEOUT, reattach_instances,
+        },
     };
 
     /// Launch argument keys for customization
@@ -179,6 +195,12 @@ pub mod launch {
             KEY_LAUNCHER_VERSION,
             KEY_CLASSPATH,
             KEY_CLASSPATH_SEPARATOR,
+            KEY_RESOLUTION_WIDTH,
+            KEY_RESOLUTION_HEIGHT,
+            KEY_QUICK_PLAY_PATH,
+            KEY_QUICK_PLAY_SINGLEPLAYER,
+            KEY_QUICK_PLAY_MULTIPLAYER,
+            KEY_QUICK_PLAY_REALMS,
         };
     }
 }
@@ -211,10 +233,12 @@ pub mod loaders {
                 Library,
                 Asset,
                 AssetIndex,
+                LoggingConfig,
                 Arguments,
                 MainClass,
                 Mods,
                 Native,
+                Digests,
             },
         },
         utils::{cache, error, manifest, query},
@@ -268,6 +292,7 @@ pub mod core {
     //! Provides low-level functionality:
     //! - File system operations
     //! - HTTP client management
+    //! - Download mirror policy
     //! - Archive extraction (ZIP, TAR.GZ)
     //! - SHA1 hashing and verification
     //! - Download utilities
@@ -275,10 +300,16 @@ pub mod core {
     pub use lighty_core::{
         system,
         hosts,
+        mirrors,
+        scheduler,
+        store,
+        offline,
+        cancel,
+        persist,
         download,
         extract,
         hash,
-        app_state::AppState,
+        app_state::{AppState, LauncherPaths},
         errors::{AppStateError, AppStateResult},
         SystemError,
         SystemResult,
@@ -286,6 +317,7 @@ pub mod core {
         ExtractResult,
         DownloadError,
         DownloadResult,
+        OfflineUnavailable,
         HashError,
         HashResult,
         verify_file_sha1,
@@ -369,8 +401,9 @@ pub mod prelude {
     #[cfg(feature = "events")]
     pub use crate::event::{
         EventBus, Event, AuthEvent, JavaEvent, LaunchEvent, LoaderEvent, CoreEvent,
-        InstanceLaunchedEvent, InstanceExitedEvent, ConsoleOutputEvent, InstanceDeletedEvent,
-        ConsoleStream, EVENT_BUS,
+        InstanceLaunchedEvent, InstanceExitedEvent, InstanceCrashedEvent, ConsoleOutputEvent, GameLogEvent,
+        InstanceDeletedEvent, InstanceReadyEvent,
+        InstanceShutdownEvent, ShutdownStage, ConsoleStream, EVENT_BUS,
     };
 
     // Java
@@ -379,7 +412,7 @@ pub mod prelude {
     // Launch
     pub use crate::launch::{
         Launch, LaunchBuilder, DownloaderConfig, init_downloader_config,
-        InstanceControl, InstanceError, InstanceResult,
+        InstanceControl, InstanceError, InstanceResult, GameHandle,
     };
     pub use crate::launch::keys::*;
 